@@ -13,7 +13,9 @@ pub struct Meta {
     pub title: Option<String>,
     pub description: Option<String>,
     pub image: Option<String>,
+    pub canonical: Option<String>,
 
+    has_canonical_link: bool,
     buffer: Vec<u8>,
 }
 
@@ -26,6 +28,8 @@ impl Meta {
             title: None,
             description: None,
             image: None,
+            canonical: None,
+            has_canonical_link: false,
             buffer: Vec::new(),
         }
     }
@@ -73,6 +77,12 @@ impl Meta {
                                 b"twitter:image" | b"twitter:image:src" | b"og:image" => {
                                     self.image = decode_str_bytes(meta_tag.content);
                                 }
+                                b"og:url" => {
+                                    // prefer the canonical link if available
+                                    if !self.has_canonical_link {
+                                        self.canonical = decode_str_bytes(meta_tag.content);
+                                    }
+                                }
                                 _ => {}
                             },
                             Tag::Title(title_tag) => {
@@ -80,6 +90,15 @@ impl Meta {
                                     self.title = decode_str_bytes(title_tag.title);
                                 }
                             }
+                            Tag::Link(link_tag) => {
+                                if is_canonical_rel(link_tag.rel) {
+                                    let href = decode_str_bytes(link_tag.href);
+                                    if href.is_some() {
+                                        self.canonical = href;
+                                        self.has_canonical_link = true;
+                                    }
+                                }
+                            }
                         }
                         // clean up used buffer
                         self.buffer = rest.to_vec();
@@ -108,6 +127,11 @@ impl Default for Meta {
     }
 }
 
+fn is_canonical_rel(rel: &[u8]) -> bool {
+    rel.split(|c| c.is_ascii_whitespace())
+        .any(|rel| rel.eq_ignore_ascii_case(b"canonical"))
+}
+
 fn decode_str_bytes(bytes: &[u8]) -> Option<String> {
     let html = String::from_utf8_lossy(bytes);
     let clean = decode_html_entities(&html).trim().to_string();
@@ -169,6 +193,35 @@ mod tests {
             Some("How much does culture influence creative thinking?".into())
         );
         assert_eq!(meta.image, Some("http://static01.nyt.com/images/2015/02/19/arts/international/19iht-btnumbers19A/19iht-btnumbers19A-facebookJumbo-v2.jpg".into()));
+        assert_eq!(meta.canonical, Some("http://www.nytimes.com/2015/02/19/arts/international/when-great-minds-dont-think-alike.html".into()));
+    }
+
+    #[test]
+    fn test_canonical_link() {
+        let canonical_example = r#"
+            <meta property="og:url" content="https://example.com/amp/article" />
+            <link rel="stylesheet" href="/style.css">
+            <link rel="alternate" hreflang="de" href="https://example.com/de/article" />
+            <LINK href="https://example.com/article?a=1&amp;b=2" REL="canonical">
+            <meta property="og:url" content="https://example.com/other" />
+        "#;
+
+        let mut meta = Meta::new();
+        meta.parse(canonical_example.as_bytes());
+
+        assert_eq!(
+            meta.canonical,
+            Some("https://example.com/article?a=1&b=2".into())
+        );
+    }
+
+    #[test]
+    fn test_canonical_link_split() {
+        let mut meta = Meta::new();
+        meta.parse(br#"<html><head><link rel="canon"#);
+        meta.parse(br#"ical" href="https://example.com/article" /></head>"#);
+
+        assert_eq!(meta.canonical, Some("https://example.com/article".into()));
     }
 
     #[test]
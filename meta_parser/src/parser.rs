@@ -1,9 +1,9 @@
 use nom::branch::{alt, permutation};
-use nom::bytes::streaming::{tag_no_case, take_till};
+use nom::bytes::streaming::{tag_no_case, take_till, take_till1};
 use nom::character::streaming::{char, multispace0, multispace1};
-use nom::combinator::not;
+use nom::combinator::{map, not, verify};
 use nom::sequence::{delimited, preceded};
-use nom::{error::ParseError, Err, IResult};
+use nom::{error::ParseError, IResult};
 
 #[derive(Debug, Clone, Copy)]
 pub struct MetaTag<'a> {
@@ -16,10 +16,17 @@ pub struct TitleTag<'a> {
     pub(super) title: &'a [u8],
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct LinkTag<'a> {
+    pub(super) rel: &'a [u8],
+    pub(super) href: &'a [u8],
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Tag<'a> {
     Meta(MetaTag<'a>),
     Title(TitleTag<'a>),
+    Link(LinkTag<'a>),
 }
 
 /// matches `<`
@@ -88,11 +95,34 @@ fn keyword_title<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8],
     Ok((rest, ()))
 }
 
+/// matches `link` (case insensitive)
+fn keyword_link<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], (), E> {
+    let (rest, _) = tag_no_case("link")(input)?;
+    Ok((rest, ()))
+}
+
+/// matches `rel` (case insensitive)
+fn keyword_rel<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], (), E> {
+    let (rest, _) = tag_no_case("rel")(input)?;
+    Ok((rest, ()))
+}
+
+/// matches `href` (case insensitive)
+fn keyword_href<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], (), E> {
+    let (rest, _) = tag_no_case("href")(input)?;
+    Ok((rest, ()))
+}
+
 /// matches `< *{meta}`
 fn open_meta_tag<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], (), E> {
     preceded(preceded(open_bracket, multispace0), keyword_meta)(input)
 }
 
+/// matches `< *{link}`
+fn open_link_tag<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], (), E> {
+    preceded(preceded(open_bracket, multispace0), keyword_link)(input)
+}
+
 /// matches `/>` or `>`
 fn close_tag<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], (), E> {
     alt((preceded(slash, close_bracket), close_bracket))(input)
@@ -156,6 +186,40 @@ fn tag_white_space<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8
     }
 }
 
+/// matches a key-value pair on a link tag which is not `rel` or `href`. Keys
+/// are matched exactly, since e.g. `hreflang` is a common attribute on links.
+fn uninteresting_link_kw_pair<'a, E: ParseError<&'a [u8]>>(
+    input: &'a [u8],
+) -> IResult<&[u8], (), E> {
+    let key = take_till1(|i: u8| i == b'=' || i == b'>' || i.is_ascii_whitespace());
+    let uninteresting_key = verify(key, |key: &[u8]| {
+        !key.eq_ignore_ascii_case(b"rel") && !key.eq_ignore_ascii_case(b"href")
+    });
+    let (rest, _) = delimited(uninteresting_key, equals, text)(input)?;
+    Ok((rest, ()))
+}
+
+/// matches and discards white-space (and uninteresting key value pairs) in link tags
+fn link_white_space<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], (), E> {
+    let ws =
+        |i: &'a [u8]| -> IResult<&'a [u8], (), E> { multispace0(i).map(|(rest, _)| (rest, ())) };
+    let ws_pair = preceded(multispace0, uninteresting_link_kw_pair);
+    let mut ws_or_kw = alt((ws_pair, ws));
+    let mut rest = input;
+    loop {
+        match ws_or_kw(rest) {
+            Ok((r, ())) => {
+                if rest == r {
+                    return Ok((rest, ()));
+                } else {
+                    rest = r
+                }
+            }
+            Err(_) => return Ok((rest, ())),
+        }
+    }
+}
+
 /// Parses a single `<meta ... />` tag.
 fn meta_tag<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], MetaTag<'a>, E> {
     let name_kw = alt((keyword_name, keyword_property));
@@ -188,9 +252,28 @@ fn title_tag<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], Tit
     Ok((rest, TitleTag { title }))
 }
 
-/// Parses either a meta, or a title tag.
+/// Parses a single `<link ... />` tag.
+fn link_tag<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], LinkTag<'a>, E> {
+    let rel = preceded(preceded(keyword_rel, equals), text);
+    let href = preceded(preceded(keyword_href, equals), text);
+
+    let rel_ws = preceded(link_white_space, rel);
+    let href_ws = preceded(link_white_space, href);
+
+    let rel_href = permutation((rel_ws, href_ws));
+
+    let open = preceded(open_link_tag, multispace1);
+    let close = preceded(link_white_space, close_tag);
+
+    let (rest, (rel, href)) = delimited(open, rel_href, close)(input)?;
+    Ok((rest, LinkTag { rel, href }))
+}
+
+/// Parses either a meta, a title, or a link tag.
 pub fn run<'a, E: ParseError<&'a [u8]>>(input: &'a [u8]) -> IResult<&[u8], Tag<'a>, E> {
-    meta_tag(input)
-        .map(|(rest, tag)| (rest, Tag::Meta(tag)))
-        .or_else(|_: Err<E>| title_tag(input).map(|(rest, tag)| (rest, Tag::Title(tag))))
+    alt((
+        map(meta_tag, Tag::Meta),
+        map(title_tag, Tag::Title),
+        map(link_tag, Tag::Link),
+    ))(input)
 }
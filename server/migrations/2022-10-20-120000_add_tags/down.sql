@@ -0,0 +1,2 @@
+DROP TABLE url_tags;
+DROP TABLE tags;
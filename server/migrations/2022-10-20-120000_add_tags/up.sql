@@ -0,0 +1,14 @@
+CREATE TABLE tags (
+  id          VARCHAR(21) PRIMARY KEY NOT NULL,
+  created_at  TIMESTAMP NOT NULL,
+  updated_at  TIMESTAMP NOT NULL,
+
+  name        TEXT UNIQUE NOT NULL
+);
+
+CREATE TABLE url_tags (
+  url_id      VARCHAR(21) NOT NULL REFERENCES urls(id),
+  tag_id      VARCHAR(21) NOT NULL REFERENCES tags(id),
+  created_at  TIMESTAMP NOT NULL,
+  PRIMARY KEY (url_id, tag_id)
+);
@@ -0,0 +1 @@
+DROP INDEX comments_url_id;
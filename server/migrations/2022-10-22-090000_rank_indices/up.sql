@@ -0,0 +1 @@
+CREATE INDEX comments_url_id ON comments(url_id);
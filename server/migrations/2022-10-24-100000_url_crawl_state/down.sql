@@ -0,0 +1 @@
+ALTER TABLE urls DROP COLUMN crawl_state;
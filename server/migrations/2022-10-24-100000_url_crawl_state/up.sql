@@ -0,0 +1 @@
+ALTER TABLE urls ADD COLUMN crawl_state TEXT NOT NULL DEFAULT 'crawled';
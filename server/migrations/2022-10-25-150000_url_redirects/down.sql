@@ -0,0 +1,4 @@
+DROP TABLE url_redirects;
+
+DROP INDEX urls_final_url;
+ALTER TABLE urls DROP COLUMN final_url;
@@ -0,0 +1,10 @@
+ALTER TABLE urls ADD COLUMN final_url TEXT;
+CREATE INDEX urls_final_url ON urls(final_url);
+
+CREATE TABLE url_redirects (
+  url_id      VARCHAR(21) NOT NULL REFERENCES urls(id),
+  position    INTEGER NOT NULL,
+  location    TEXT    NOT NULL,
+  status_code INTEGER NOT NULL,
+  PRIMARY KEY (url_id, position)
+);
@@ -0,0 +1,3 @@
+DROP INDEX urls_canonical_url;
+ALTER TABLE urls DROP COLUMN duplicate_of;
+ALTER TABLE urls DROP COLUMN canonical_url;
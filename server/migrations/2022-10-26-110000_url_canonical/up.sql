@@ -0,0 +1,3 @@
+ALTER TABLE urls ADD COLUMN canonical_url TEXT;
+ALTER TABLE urls ADD COLUMN duplicate_of VARCHAR(21);
+CREATE INDEX urls_canonical_url ON urls(canonical_url);
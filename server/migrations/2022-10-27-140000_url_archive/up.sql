@@ -0,0 +1 @@
+ALTER TABLE urls ADD COLUMN archived_at TIMESTAMP;
@@ -0,0 +1,3 @@
+DROP TABLE url_checks;
+
+ALTER TABLE urls DROP COLUMN dead_since;
@@ -0,0 +1,8 @@
+ALTER TABLE urls ADD COLUMN dead_since TIMESTAMP;
+
+CREATE TABLE url_checks (
+  url_id      VARCHAR(21) NOT NULL REFERENCES urls(id),
+  checked_at  TIMESTAMP NOT NULL,
+  status_code INTEGER   NOT NULL,
+  PRIMARY KEY (url_id, checked_at)
+);
@@ -0,0 +1 @@
+ALTER TABLE urls DROP COLUMN thumbnail_at;
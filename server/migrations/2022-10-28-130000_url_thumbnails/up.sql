@@ -0,0 +1 @@
+ALTER TABLE urls ADD COLUMN thumbnail_at TIMESTAMP;
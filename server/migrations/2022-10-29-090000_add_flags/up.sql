@@ -0,0 +1,17 @@
+CREATE TABLE flags (
+  id            VARCHAR(21) PRIMARY KEY NOT NULL,
+  created_at    TIMESTAMP NOT NULL,
+  created_by    VARCHAR(21) NOT NULL REFERENCES users(id),
+
+  url_id        VARCHAR(21) REFERENCES urls(id),
+  comment_id    VARCHAR(21) REFERENCES comments(id),
+  reason        TEXT NOT NULL,
+  dismissed_at  TIMESTAMP,
+
+  CHECK ((url_id IS NULL) <> (comment_id IS NULL)),
+  UNIQUE (created_by, url_id),
+  UNIQUE (created_by, comment_id)
+);
+
+CREATE INDEX flags_url_id ON flags (url_id);
+CREATE INDEX flags_comment_id ON flags (comment_id);
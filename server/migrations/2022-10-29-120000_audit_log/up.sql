@@ -0,0 +1,23 @@
+CREATE TABLE audit_log (
+  id          VARCHAR(21) PRIMARY KEY NOT NULL,
+  created_at  TIMESTAMP NOT NULL,
+  actor       VARCHAR(21) NOT NULL REFERENCES users(id),
+
+  action      TEXT NOT NULL,
+  target_id   VARCHAR(21) NOT NULL,
+  reason      TEXT,
+  snapshot    TEXT
+);
+
+CREATE INDEX audit_log_created_at ON audit_log (created_at);
+
+-- entries can only be appended
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+  SELECT RAISE(ABORT, 'The audit log can not be modified');
+END;
+
+CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+  SELECT RAISE(ABORT, 'The audit log can not be modified');
+END;
@@ -0,0 +1,4 @@
+DROP INDEX users_feed_token;
+ALTER TABLE users DROP COLUMN feed_token;
+
+DROP TABLE url_saves;
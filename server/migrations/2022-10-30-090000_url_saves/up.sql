@@ -0,0 +1,11 @@
+CREATE TABLE url_saves (
+  url_id      VARCHAR(21) NOT NULL REFERENCES urls(id),
+  user_id     VARCHAR(21) NOT NULL REFERENCES users(id),
+  created_at  TIMESTAMP NOT NULL,
+  PRIMARY KEY (url_id, user_id)
+);
+
+CREATE INDEX url_saves_user_id_created_at ON url_saves(user_id, created_at);
+
+ALTER TABLE users ADD COLUMN feed_token VARCHAR(64);
+CREATE UNIQUE INDEX users_feed_token ON users(feed_token);
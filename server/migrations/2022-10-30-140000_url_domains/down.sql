@@ -0,0 +1,2 @@
+DROP INDEX urls_domain_created_at;
+ALTER TABLE urls DROP COLUMN domain;
@@ -0,0 +1,16 @@
+ALTER TABLE urls ADD COLUMN domain TEXT NOT NULL DEFAULT '';
+
+-- backfill the domain of existing submissions, this mirrors
+-- `canonical::domain`: strip the scheme, path, query, fragment,
+-- user info, and port, then a leading `www.`. IPv6 hosts keep
+-- their brackets, e.g. `[::1]`, and contain colons themselves
+UPDATE urls SET domain = lower(substr(url, instr(url, '://') + 3));
+UPDATE urls SET domain = substr(domain, 1, instr(domain, '/') - 1) WHERE instr(domain, '/') > 0;
+UPDATE urls SET domain = substr(domain, 1, instr(domain, '?') - 1) WHERE instr(domain, '?') > 0;
+UPDATE urls SET domain = substr(domain, 1, instr(domain, '#') - 1) WHERE instr(domain, '#') > 0;
+UPDATE urls SET domain = substr(domain, instr(domain, '@') + 1) WHERE instr(domain, '@') > 0;
+UPDATE urls SET domain = substr(domain, 1, instr(domain, ']')) WHERE domain LIKE '[%' AND instr(domain, ']') > 0;
+UPDATE urls SET domain = substr(domain, 1, instr(domain, ':') - 1) WHERE domain NOT LIKE '[%' AND instr(domain, ':') > 0;
+UPDATE urls SET domain = substr(domain, 5) WHERE domain LIKE 'www._%';
+
+CREATE INDEX urls_domain_created_at ON urls(domain, created_at);
@@ -0,0 +1,4 @@
+DROP INDEX urls_held_at;
+ALTER TABLE urls DROP COLUMN held_at;
+
+DROP TABLE domain_rules;
@@ -0,0 +1,12 @@
+CREATE TABLE domain_rules (
+  id          VARCHAR(21) PRIMARY KEY NOT NULL,
+  created_at  TIMESTAMP NOT NULL,
+  updated_at  TIMESTAMP NOT NULL,
+  created_by  VARCHAR(21) NOT NULL REFERENCES users(id),
+
+  domain      TEXT UNIQUE NOT NULL,
+  action      TEXT NOT NULL
+);
+
+ALTER TABLE urls ADD COLUMN held_at TIMESTAMP;
+CREATE INDEX urls_held_at ON urls(held_at);
@@ -0,0 +1,12 @@
+DELETE FROM url_upvotes WHERE url_id IN (SELECT id FROM urls WHERE text IS NOT NULL);
+DELETE FROM url_saves WHERE url_id IN (SELECT id FROM urls WHERE text IS NOT NULL);
+DELETE FROM url_tags WHERE url_id IN (SELECT id FROM urls WHERE text IS NOT NULL);
+DELETE FROM flags WHERE url_id IN (SELECT id FROM urls WHERE text IS NOT NULL);
+DELETE FROM flags WHERE comment_id IN (
+    SELECT comments.id FROM comments
+    INNER JOIN urls ON comments.url_id = urls.id
+    WHERE urls.text IS NOT NULL
+);
+DELETE FROM comments WHERE url_id IN (SELECT id FROM urls WHERE text IS NOT NULL);
+DELETE FROM urls WHERE text IS NOT NULL;
+ALTER TABLE urls DROP COLUMN text;
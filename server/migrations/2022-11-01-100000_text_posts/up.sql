@@ -0,0 +1,3 @@
+-- text posts link to their own discussion, and are
+-- never crawled
+ALTER TABLE urls ADD COLUMN text TEXT;
@@ -0,0 +1,10 @@
+-- remembers where merged submissions went, so
+-- links to their comments can be redirected
+CREATE TABLE url_merges (
+  from_id     VARCHAR(21) PRIMARY KEY NOT NULL,
+  into_id     VARCHAR(21) NOT NULL REFERENCES urls(id),
+  merged_at   TIMESTAMP NOT NULL,
+  merged_by   VARCHAR(21) NOT NULL REFERENCES users(id)
+);
+
+CREATE INDEX url_merges_into_id ON url_merges(into_id);
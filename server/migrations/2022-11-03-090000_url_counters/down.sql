@@ -0,0 +1,3 @@
+DROP INDEX urls_upvote_count_created_at;
+ALTER TABLE urls DROP COLUMN comment_count;
+ALTER TABLE urls DROP COLUMN upvote_count;
@@ -0,0 +1,10 @@
+-- cached counters, these are kept up to date when upvoting
+-- and commenting, and checked by a background job
+ALTER TABLE urls ADD COLUMN upvote_count BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE urls ADD COLUMN comment_count BIGINT NOT NULL DEFAULT 0;
+
+UPDATE urls SET
+  upvote_count = (SELECT COUNT(*) FROM url_upvotes WHERE url_upvotes.url_id = urls.id),
+  comment_count = (SELECT COUNT(*) FROM comments WHERE comments.url_id = urls.id);
+
+CREATE INDEX urls_upvote_count_created_at ON urls(upvote_count, created_at);
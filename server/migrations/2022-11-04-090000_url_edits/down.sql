@@ -0,0 +1,3 @@
+DROP TABLE url_edits;
+ALTER TABLE urls DROP COLUMN description_override;
+ALTER TABLE urls DROP COLUMN title_override;
@@ -0,0 +1,16 @@
+-- titles and descriptions set by the submitter or a moderator,
+-- these take precedence over the crawled values
+ALTER TABLE urls ADD COLUMN title_override TEXT;
+ALTER TABLE urls ADD COLUMN description_override TEXT;
+
+-- history of edits to the title and description
+CREATE TABLE url_edits (
+  id          VARCHAR(21) PRIMARY KEY NOT NULL,
+  created_at  TIMESTAMP   NOT NULL,
+  created_by  VARCHAR(21) NOT NULL REFERENCES users(id),
+  url_id      VARCHAR(21) NOT NULL REFERENCES urls(id),
+  title       TEXT,
+  description TEXT
+);
+
+CREATE INDEX url_edits_url_id_created_at ON url_edits(url_id, created_at);
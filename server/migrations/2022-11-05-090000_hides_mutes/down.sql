@@ -0,0 +1,3 @@
+DROP TABLE user_mutes;
+DROP TABLE domain_mutes;
+DROP TABLE url_hides;
@@ -0,0 +1,22 @@
+CREATE TABLE url_hides (
+  url_id      VARCHAR(21) NOT NULL REFERENCES urls(id),
+  user_id     VARCHAR(21) NOT NULL REFERENCES users(id),
+  created_at  TIMESTAMP NOT NULL,
+  PRIMARY KEY (url_id, user_id)
+);
+
+CREATE INDEX url_hides_user_id_created_at ON url_hides(user_id, created_at);
+
+CREATE TABLE domain_mutes (
+  user_id     VARCHAR(21) NOT NULL REFERENCES users(id),
+  domain      TEXT NOT NULL,
+  created_at  TIMESTAMP NOT NULL,
+  PRIMARY KEY (user_id, domain)
+);
+
+CREATE TABLE user_mutes (
+  user_id     VARCHAR(21) NOT NULL REFERENCES users(id),
+  muted_id    VARCHAR(21) NOT NULL REFERENCES users(id),
+  created_at  TIMESTAMP NOT NULL,
+  PRIMARY KEY (user_id, muted_id)
+);
@@ -0,0 +1,4 @@
+DROP INDEX url_upvotes_url_id_created_at;
+DROP INDEX url_upvotes_user_id_created_at;
+
+ALTER TABLE users DROP COLUMN public_upvotes;
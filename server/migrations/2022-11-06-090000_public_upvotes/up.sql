@@ -0,0 +1,4 @@
+ALTER TABLE users ADD COLUMN public_upvotes BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE INDEX url_upvotes_user_id_created_at ON url_upvotes(user_id, created_at);
+CREATE INDEX url_upvotes_url_id_created_at ON url_upvotes(url_id, created_at);
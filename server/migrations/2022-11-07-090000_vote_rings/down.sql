@@ -0,0 +1,3 @@
+DROP TABLE vote_ring_members;
+DROP TABLE vote_rings;
+ALTER TABLE url_upvotes DROP COLUMN discounted_at;
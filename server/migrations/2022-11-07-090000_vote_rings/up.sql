@@ -0,0 +1,23 @@
+-- upvotes which were discounted as part of a voting ring, these
+-- are kept but don't count towards the ranking
+ALTER TABLE url_upvotes ADD COLUMN discounted_at TIMESTAMP;
+
+-- suspicious clusters of accounts, found by a background job
+CREATE TABLE vote_rings (
+  id                VARCHAR(21) PRIMARY KEY NOT NULL,
+  created_at        TIMESTAMP   NOT NULL,
+  updated_at        TIMESTAMP   NOT NULL,
+  member_key        TEXT        NOT NULL UNIQUE,
+  ring_upvotes      BIGINT      NOT NULL,
+  shared_ip         BOOLEAN     NOT NULL,
+  invite_tree       BOOLEAN     NOT NULL,
+  single_submitter  BOOLEAN     NOT NULL,
+  discounted_at     TIMESTAMP,
+  dismissed_at      TIMESTAMP
+);
+
+CREATE TABLE vote_ring_members (
+  ring_id     VARCHAR(21) NOT NULL REFERENCES vote_rings(id),
+  user_id     VARCHAR(21) NOT NULL REFERENCES users(id),
+  PRIMARY KEY (ring_id, user_id)
+);
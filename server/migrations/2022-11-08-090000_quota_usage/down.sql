@@ -0,0 +1,2 @@
+DROP INDEX quota_usage_user_id_action_used_at;
+DROP TABLE quota_usage;
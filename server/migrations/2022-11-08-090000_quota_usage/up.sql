@@ -0,0 +1,20 @@
+-- append-only record of actions which count towards
+-- the quotas of a user, such that deleting a submission
+-- or comment doesn't free up the quota again
+CREATE TABLE quota_usage (
+  id          VARCHAR(21) PRIMARY KEY NOT NULL,
+  user_id     VARCHAR(21) NOT NULL REFERENCES users(id),
+  action      TEXT        NOT NULL,
+  used_at     TIMESTAMP   NOT NULL
+);
+
+CREATE INDEX quota_usage_user_id_action_used_at ON quota_usage(user_id, action, used_at);
+
+-- existing submissions and comments still count towards
+-- the current quotas
+INSERT INTO quota_usage (id, user_id, action, used_at)
+  SELECT id, created_by, 'submissions', created_at FROM urls
+  WHERE created_at > datetime('now', '-1 day');
+INSERT INTO quota_usage (id, user_id, action, used_at)
+  SELECT id, created_by, 'comments', created_at FROM comments
+  WHERE created_at > datetime('now', '-1 day');
@@ -2,11 +2,36 @@ use anyhow::Result;
 use dotenv::var;
 use nanoid::nanoid;
 use once_cell::sync::Lazy;
+use std::fmt::Display;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 static DEFAULT_WWW: &str = "www/static";
 static DEFAULT_SMTP_PORT: u16 = 587;
 static DEFAULT_INDEX: &str = "index";
+static DEFAULT_ARCHIVE: &str = "archive";
+static DEFAULT_THUMBNAILS: &str = "thumbnails";
+static DEFAULT_RANK_GRAVITY: f64 = 1.8;
+static DEFAULT_RANK_COMMENT_WEIGHT: f64 = 0.5;
+static DEFAULT_RANK_AGE_OFFSET_HOURS: f64 = 2.0;
+static DEFAULT_DEAD_AFTER_FAILURES: i64 = 5;
+static DEFAULT_SUBMISSIONS: Quota = Quota {
+    per_hour: 10,
+    per_day: 50,
+};
+static DEFAULT_COMMENTS: Quota = Quota {
+    per_hour: 30,
+    per_day: 200,
+};
+static DEFAULT_NEW_ACCOUNT_DAYS: i64 = 7;
+static DEFAULT_NEW_ACCOUNT_SUBMISSIONS: Quota = Quota {
+    per_hour: 3,
+    per_day: 10,
+};
+static DEFAULT_NEW_ACCOUNT_COMMENTS: Quota = Quota {
+    per_hour: 10,
+    per_day: 50,
+};
 
 static ENV: Lazy<Config> = Lazy::new(|| match load_from_env() {
     Ok(conf) => conf,
@@ -20,9 +45,16 @@ static ENV: Lazy<Config> = Lazy::new(|| match load_from_env() {
 pub struct Config {
     database_url: String,
     search_idx: Option<PathBuf>,
+    archive_dir: Option<PathBuf>,
+    thumbnail_dir: Option<PathBuf>,
     www_dir: PathBuf,
     hostname: String,
+    base_url: String,
     smtp: Option<SmtpConfig>,
+    ranking: RankingConfig,
+    link_health: LinkHealthConfig,
+    rate_limits: RateLimitConfig,
+    canonical_rules: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +65,44 @@ pub struct SmtpConfig {
     password: String,
 }
 
+/// Parameters for the time-decayed ranking used on
+/// the home page. A submission is scored as
+/// `(upvotes + comment_weight * comments + 1) / (age_hours + age_offset_hours)^gravity`.
+#[derive(Debug, Clone, Copy)]
+pub struct RankingConfig {
+    gravity: f64,
+    comment_weight: f64,
+    age_offset_hours: f64,
+}
+
+/// Parameters for deciding when a submitted link
+/// is considered dead.
+#[derive(Debug, Clone, Copy)]
+pub struct LinkHealthConfig {
+    dead_after_failures: i64,
+    hide_dead_from_ranked: bool,
+}
+
+/// Per user limits on how many submissions and comments
+/// can be made. Accounts younger than `new_account_days`
+/// are held to tighter limits.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    submissions: Quota,
+    comments: Quota,
+    new_account_days: i64,
+    new_account_submissions: Quota,
+    new_account_comments: Quota,
+}
+
+/// How often something can be done per
+/// hour and per day.
+#[derive(Debug, Clone, Copy)]
+pub struct Quota {
+    per_hour: i64,
+    per_day: i64,
+}
+
 impl Config {
     /// Configuration loaded from the
     /// environment.
@@ -48,9 +118,16 @@ impl Config {
         Self {
             database_url: format!("file:{}?mode=memory&cache=shared", nanoid!(16)),
             search_idx: None,
+            archive_dir: None,
+            thumbnail_dir: None,
             www_dir: DEFAULT_WWW.into(),
             hostname: "localhost".into(),
+            base_url: "http://localhost".into(),
             smtp: None,
+            ranking: RankingConfig::default(),
+            link_health: LinkHealthConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            canonical_rules: None,
         }
     }
 
@@ -77,6 +154,20 @@ impl Config {
         self.search_idx.as_ref().map(|p| p.as_ref())
     }
 
+    /// Directory to store page snapshots in, if
+    /// defined. (`None`, should be interpreted as
+    /// keeping snapshots in memory).
+    pub fn archive(&self) -> Option<&Path> {
+        self.archive_dir.as_deref()
+    }
+
+    /// Directory to cache image thumbnails in, if
+    /// defined. (`None`, should be interpreted as
+    /// keeping thumbnails in memory).
+    pub fn thumbnails(&self) -> Option<&Path> {
+        self.thumbnail_dir.as_deref()
+    }
+
     /// Directory to serve static files
     /// from.
     pub fn www(&self) -> &Path {
@@ -93,6 +184,34 @@ impl Config {
     pub fn hostname(&self) -> &str {
         &self.hostname
     }
+
+    /// The URL the server is reachable under, without a
+    /// trailing slash. E.g. `http://localhost:8080`.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Parameters used to rank submissions.
+    pub fn ranking(&self) -> &RankingConfig {
+        &self.ranking
+    }
+
+    /// Parameters used to decide if links are dead.
+    pub fn link_health(&self) -> &LinkHealthConfig {
+        &self.link_health
+    }
+
+    /// Limits on submissions and comments per user.
+    pub fn rate_limits(&self) -> &RateLimitConfig {
+        &self.rate_limits
+    }
+
+    /// File with rules used to canonicalize submitted
+    /// URLs. If this is not set, the default rules are
+    /// used (see `canonical.toml`).
+    pub fn canonical_rules(&self) -> Option<&Path> {
+        self.canonical_rules.as_deref()
+    }
 }
 
 impl SmtpConfig {
@@ -113,6 +232,129 @@ impl SmtpConfig {
     }
 }
 
+impl RankingConfig {
+    /// How quickly submissions fall in the ranking
+    /// as they age. Larger values favor newer submissions.
+    pub fn gravity(&self) -> f64 {
+        self.gravity
+    }
+
+    /// How much a comment counts towards the score,
+    /// relative to an upvote.
+    pub fn comment_weight(&self) -> f64 {
+        self.comment_weight
+    }
+
+    /// Hours added to the age of every submission, this
+    /// prevents brand new submissions from dominating
+    /// the ranking.
+    pub fn age_offset_hours(&self) -> f64 {
+        self.age_offset_hours
+    }
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            gravity: DEFAULT_RANK_GRAVITY,
+            comment_weight: DEFAULT_RANK_COMMENT_WEIGHT,
+            age_offset_hours: DEFAULT_RANK_AGE_OFFSET_HOURS,
+        }
+    }
+}
+
+impl LinkHealthConfig {
+    /// The number of consecutive failed checks after
+    /// which a link is considered dead.
+    pub fn dead_after_failures(&self) -> i64 {
+        self.dead_after_failures
+    }
+
+    /// Whether dead links are left out of the ranked
+    /// listing on the home page.
+    pub fn hide_dead_from_ranked(&self) -> bool {
+        self.hide_dead_from_ranked
+    }
+}
+
+impl Default for LinkHealthConfig {
+    fn default() -> Self {
+        Self {
+            dead_after_failures: DEFAULT_DEAD_AFTER_FAILURES,
+            hide_dead_from_ranked: false,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Quota for submitting URLs and text posts.
+    pub fn submissions(&self, new_account: bool) -> Quota {
+        if new_account {
+            self.new_account_submissions
+        } else {
+            self.submissions
+        }
+    }
+
+    /// Quota for posting comments.
+    pub fn comments(&self, new_account: bool) -> Quota {
+        if new_account {
+            self.new_account_comments
+        } else {
+            self.comments
+        }
+    }
+
+    /// The number of days for which a new account
+    /// is held to the tighter quotas.
+    pub fn new_account_days(&self) -> i64 {
+        self.new_account_days
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            submissions: DEFAULT_SUBMISSIONS,
+            comments: DEFAULT_COMMENTS,
+            new_account_days: DEFAULT_NEW_ACCOUNT_DAYS,
+            new_account_submissions: DEFAULT_NEW_ACCOUNT_SUBMISSIONS,
+            new_account_comments: DEFAULT_NEW_ACCOUNT_COMMENTS,
+        }
+    }
+}
+
+impl Quota {
+    pub fn per_hour(&self) -> i64 {
+        self.per_hour
+    }
+
+    pub fn per_day(&self) -> i64 {
+        self.per_day
+    }
+}
+
+fn load_or<T: FromStr + Display>(name: &str, default: T) -> T {
+    var(name)
+        .ok()
+        .and_then(|value| {
+            value
+                .parse()
+                .map_err(|_| {
+                    log::warn!("Invalid {} set, using default {}", name, default);
+                })
+                .ok()
+        })
+        .unwrap_or(default)
+}
+
+fn load_quota_or(name: &str, default: Quota) -> Quota {
+    Quota {
+        per_hour: load_or(&format!("{}_PER_HOUR", name), default.per_hour).max(1),
+        per_day: load_or(&format!("{}_PER_DAY", name), default.per_day).max(1),
+    }
+}
+
 fn load_from_env() -> Result<Config> {
     let database_url = var("DATABASE_URL")?;
 
@@ -126,6 +368,26 @@ fn load_from_env() -> Result<Config> {
         })
         .into();
 
+    let archive_dir: PathBuf = var("ARCHIVE_DIR")
+        .unwrap_or_else(|_| {
+            log::info!(
+                "ARCHIVE_DIR configuration not set, using default '{}'",
+                DEFAULT_ARCHIVE
+            );
+            DEFAULT_ARCHIVE.to_string()
+        })
+        .into();
+
+    let thumbnail_dir: PathBuf = var("THUMBNAIL_DIR")
+        .unwrap_or_else(|_| {
+            log::info!(
+                "THUMBNAIL_DIR configuration not set, using default '{}'",
+                DEFAULT_THUMBNAILS
+            );
+            DEFAULT_THUMBNAILS.to_string()
+        })
+        .into();
+
     let www_dir = var("WWW_DIR")
         .unwrap_or_else(|_| {
             log::info!(
@@ -156,12 +418,46 @@ fn load_from_env() -> Result<Config> {
     };
 
     let hostname = var("HOSTNAME")?;
+    let base_url = var("BASE_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| format!("https://{}", hostname));
+
+    let ranking = RankingConfig {
+        gravity: load_or("RANK_GRAVITY", DEFAULT_RANK_GRAVITY),
+        comment_weight: load_or("RANK_COMMENT_WEIGHT", DEFAULT_RANK_COMMENT_WEIGHT),
+        age_offset_hours: load_or("RANK_AGE_OFFSET_HOURS", DEFAULT_RANK_AGE_OFFSET_HOURS),
+    };
+
+    let link_health = LinkHealthConfig {
+        dead_after_failures: load_or("DEAD_AFTER_FAILURES", DEFAULT_DEAD_AFTER_FAILURES).max(1),
+        hide_dead_from_ranked: matches!(var("HIDE_DEAD_URLS").as_deref(), Ok("true" | "1")),
+    };
+
+    let rate_limits = RateLimitConfig {
+        submissions: load_quota_or("SUBMISSIONS", DEFAULT_SUBMISSIONS),
+        comments: load_quota_or("COMMENTS", DEFAULT_COMMENTS),
+        new_account_days: load_or("NEW_ACCOUNT_DAYS", DEFAULT_NEW_ACCOUNT_DAYS).max(0),
+        new_account_submissions: load_quota_or(
+            "NEW_ACCOUNT_SUBMISSIONS",
+            DEFAULT_NEW_ACCOUNT_SUBMISSIONS,
+        ),
+        new_account_comments: load_quota_or("NEW_ACCOUNT_COMMENTS", DEFAULT_NEW_ACCOUNT_COMMENTS),
+    };
+
+    let canonical_rules = var("CANONICAL_RULES").ok().map(PathBuf::from);
 
     Ok(Config {
         database_url,
         search_idx: Some(search_idx),
+        archive_dir: Some(archive_dir),
+        thumbnail_dir: Some(thumbnail_dir),
         www_dir,
         smtp,
         hostname,
+        base_url,
+        ranking,
+        link_health,
+        rate_limits,
+        canonical_rules,
     })
 }
@@ -1,13 +1,16 @@
+use crate::config::{LinkHealthConfig, RateLimitConfig};
 use crate::db::id::UserID;
 use crate::db::models::User;
-use crate::db::{Pool, PooledConnection, SearchIndex};
+use crate::db::{Archive, Loaders, Pool, PooledConnection, SearchIndex, Thumbnails};
 use crate::email::Mailer;
+use crate::fetch::Fetcher;
 use crate::schema::users;
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use diesel::{query_dsl::methods::FindDsl, RunQueryDsl};
 use once_cell::sync::Lazy;
 use std::net::IpAddr;
+use std::sync::Arc;
 
 const SERVER_XSRF_TOKEN: &str = "server_xsrt_token";
 static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
@@ -24,6 +27,11 @@ static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
         .map_err(|err| log::error!("Failed to build http client: {}", err))
         .unwrap()
 });
+static FETCHER: Lazy<Fetcher> = Lazy::new(|| {
+    Fetcher::new()
+        .map_err(|err| log::error!("Failed to build fetcher: {}", err))
+        .unwrap()
+});
 
 /// Application request context. The context holds information
 /// about the current request, and also can provide access to
@@ -41,6 +49,7 @@ pub struct Context {
     request_time: DateTime<Utc>,
     user_agent: Option<String>,
     remote_ip: Option<IpAddr>,
+    loaders: Arc<Loaders>,
 }
 
 impl Context {
@@ -60,6 +69,7 @@ impl Context {
             request_time: Utc::now(),
             user_agent,
             remote_ip,
+            loaders: Default::default(),
         }
     }
 
@@ -74,6 +84,7 @@ impl Context {
             request_time: Utc::now(),
             user_agent: None,
             remote_ip: None,
+            loaders: Default::default(),
         }
     }
 
@@ -82,6 +93,14 @@ impl Context {
     /// context, and is probably not what you want.
     pub fn set_logged_in_user(&mut self, user: UserID, session_token: String) {
         self.login_session = Some((user, session_token));
+        self.loaders = Default::default();
+    }
+
+    /// Overrides the time returned by [`now`](now). This
+    /// exists to make time dependent behavior testable,
+    /// and is probably not what you want.
+    pub fn set_now(&mut self, now: DateTime<Utc>) {
+        self.request_time = now;
     }
 
     /// Retrieve a database connection from the
@@ -90,11 +109,52 @@ impl Context {
         Ok(self.pool.db.get().await?)
     }
 
+    /// Retrieve the batch loaders of this context. Data
+    /// loaded through these is cached for the life-time
+    /// of the request.
+    pub fn loaders(&self) -> &Loaders {
+        &self.loaders
+    }
+
     /// Retrieve a handle to the search index.
     pub fn search(&self) -> &SearchIndex {
         &self.pool.search
     }
 
+    /// Retrieve a handle to the page archive.
+    pub fn archive(&self) -> &Archive {
+        &self.pool.archive
+    }
+
+    /// Retrieve a handle to the image thumbnail cache.
+    pub fn thumbnails(&self) -> &Thumbnails {
+        &self.pool.thumbnails
+    }
+
+    /// Parameters for deciding when links are
+    /// considered dead.
+    pub fn link_health(&self) -> &LinkHealthConfig {
+        &self.pool.link_health
+    }
+
+    /// Limits on how many submissions and comments
+    /// users can make.
+    pub fn rate_limits(&self) -> &RateLimitConfig {
+        &self.pool.rate_limits
+    }
+
+    /// Host name the server is reachable under,
+    /// e.g. `localhost:8080`.
+    pub fn hostname(&self) -> &str {
+        &self.pool.hostname
+    }
+
+    /// URL the server is reachable under, without a
+    /// trailing slash, e.g. `http://localhost:8080`.
+    pub fn base_url(&self) -> &str {
+        &self.pool.base_url
+    }
+
     /// Retrieve the mailer to send an email
     /// message. Note that sending emails costs
     /// money.
@@ -109,6 +169,14 @@ impl Context {
         HTTP_CLIENT.clone()
     }
 
+    /// Retrieve the fetcher used to load user
+    /// submitted URLs. Prefer this over the
+    /// [`http_client`](http_client) for any URL
+    /// which is not trusted.
+    pub fn fetcher(&self) -> &Fetcher {
+        &FETCHER
+    }
+
     /// Retrieve the ID of the logged in user.
     pub fn maybe_user_id(&self) -> Option<UserID> {
         self.login_session.as_ref().map(|(id, _)| *id)
@@ -0,0 +1,125 @@
+use crate::db::id::UrlID;
+use crate::Config;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const MAX_SNAPSHOT_BYTES: usize = 512 * 1024;
+
+#[derive(Clone)]
+enum Storage {
+    Dir(PathBuf),
+    Memory(Arc<Mutex<HashMap<UrlID, Vec<u8>>>>),
+}
+
+/// Stores snapshots of the HTML pages linked
+/// by submitted URLs.
+#[derive(Clone)]
+pub struct Archive {
+    storage: Storage,
+}
+
+impl Archive {
+    /// Opens the archive. If no archive directory is
+    /// configured, snapshots are kept in memory.
+    pub async fn new(conf: &Config) -> Result<Self> {
+        let storage = if let Some(path) = conf.archive() {
+            tokio::fs::create_dir_all(path).await?;
+            Storage::Dir(path.to_path_buf())
+        } else {
+            Storage::Memory(Default::default())
+        };
+        Ok(Self { storage })
+    }
+
+    /// Stores a snapshot for the given URL, replacing any
+    /// existing snapshot. Snapshots are truncated to
+    /// `MAX_SNAPSHOT_BYTES`.
+    pub async fn store(&self, url_id: UrlID, html: &[u8]) -> Result<()> {
+        let html = &html[..html.len().min(MAX_SNAPSHOT_BYTES)];
+        match &self.storage {
+            Storage::Dir(dir) => tokio::fs::write(snapshot_path(dir, url_id), html).await?,
+            Storage::Memory(map) => {
+                map.lock().await.insert(url_id, html.to_vec());
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the snapshot of the given URL, if
+    /// there is one.
+    pub async fn load(&self, url_id: UrlID) -> Result<Option<Vec<u8>>> {
+        match &self.storage {
+            Storage::Dir(dir) => match tokio::fs::read(snapshot_path(dir, url_id)).await {
+                Ok(html) => Ok(Some(html)),
+                Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err.into()),
+            },
+            Storage::Memory(map) => Ok(map.lock().await.get(&url_id).cloned()),
+        }
+    }
+
+    /// Removes the snapshot of the given URL.
+    pub async fn delete(&self, url_id: UrlID) -> Result<()> {
+        match &self.storage {
+            Storage::Dir(dir) => match tokio::fs::remove_file(snapshot_path(dir, url_id)).await {
+                Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+                _ => Ok(()),
+            },
+            Storage::Memory(map) => {
+                map.lock().await.remove(&url_id);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn snapshot_path(dir: &Path, url_id: UrlID) -> PathBuf {
+    // ids are url-safe, and can be used as file names
+    dir.join(format!("{}.html", url_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn check_archive(archive: Archive) {
+        let url_id = UrlID::new();
+        assert_eq!(archive.load(url_id).await.unwrap(), None);
+
+        archive.store(url_id, b"<h1>Hello</h1>").await.unwrap();
+        assert_eq!(
+            archive.load(url_id).await.unwrap(),
+            Some(b"<h1>Hello</h1>".to_vec())
+        );
+
+        let large = vec![b'x'; MAX_SNAPSHOT_BYTES + 1];
+        archive.store(url_id, &large).await.unwrap();
+        let stored = archive.load(url_id).await.unwrap().unwrap();
+        assert_eq!(stored.len(), MAX_SNAPSHOT_BYTES);
+
+        archive.delete(url_id).await.unwrap();
+        assert_eq!(archive.load(url_id).await.unwrap(), None);
+        archive.delete(url_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_memory_archive() {
+        let archive = Archive::new(&Config::test()).await.unwrap();
+        check_archive(archive).await;
+    }
+
+    #[tokio::test]
+    async fn test_dir_archive() {
+        let dir = std::env::temp_dir().join(format!("archive-{}", UrlID::new()));
+        let archive = Archive {
+            storage: Storage::Dir(dir.clone()),
+        };
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        check_archive(archive).await;
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -6,3 +6,10 @@ pub type RoleID = ID<2>;
 pub type InviteID = ID<3>;
 pub type UrlID = ID<4>;
 pub type CommentID = ID<5>;
+pub type TagID = ID<6>;
+pub type FlagID = ID<7>;
+pub type AuditEntryID = ID<8>;
+pub type DomainRuleID = ID<9>;
+pub type UrlEditID = ID<10>;
+pub type VoteRingID = ID<11>;
+pub type QuotaUsageID = ID<12>;
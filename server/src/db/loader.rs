@@ -0,0 +1,222 @@
+use crate::db::id::{UrlID, UserID};
+use crate::db::models::{Tag, Url, User};
+use crate::Context;
+use anyhow::{anyhow, Result};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Caches values by key, and loads missing values in batches.
+/// Lookups which are made concurrently, e.g. while resolving a
+/// list of GraphQL objects, are coalesced into a single fetch.
+pub struct Loader<K, V> {
+    cache: Mutex<HashMap<K, Option<V>>>,
+    queue: Mutex<Vec<K>>,
+    dispatch: tokio::sync::Mutex<()>,
+    batches: AtomicUsize,
+}
+
+impl<K, V> Default for Loader<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: Mutex::new(HashMap::new()),
+            queue: Mutex::new(Vec::new()),
+            dispatch: tokio::sync::Mutex::new(()),
+            batches: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<K, V> Loader<K, V>
+where
+    K: Copy + Eq + Hash,
+    V: Clone,
+{
+    /// Load the value for the given key, or `None` if there is
+    /// no such value. If the key is not cached, it is fetched
+    /// together with all other keys queued at the time. `fetch`
+    /// may omit keys which have no value.
+    pub async fn load<F, Fut>(&self, key: K, fetch: F) -> Result<Option<V>>
+    where
+        F: FnOnce(Vec<K>) -> Fut,
+        Fut: Future<Output = Result<HashMap<K, V>>>,
+    {
+        if let Some(value) = self.cached(&key) {
+            return Ok(value);
+        }
+        self.queue.lock().unwrap().push(key);
+        // give concurrent lookups a chance to join the batch
+        tokio::task::yield_now().await;
+
+        let _dispatch = self.dispatch.lock().await;
+        if let Some(value) = self.cached(&key) {
+            return Ok(value);
+        }
+        let mut seen = HashSet::new();
+        let mut keys: Vec<K> = self.queue.lock().unwrap().drain(..).collect();
+        keys.retain(|key| seen.insert(*key));
+        if !seen.contains(&key) {
+            keys.push(key);
+        }
+
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        let mut values = fetch(keys.clone()).await?;
+        let mut cache = self.cache.lock().unwrap();
+        for key in keys {
+            let value = values.remove(&key);
+            cache.insert(key, value);
+        }
+        Ok(cache.get(&key).cloned().flatten())
+    }
+
+    /// Drops the cached value for the given key, e.g.
+    /// because it was changed.
+    pub fn forget(&self, key: &K) {
+        self.cache.lock().unwrap().remove(key);
+    }
+
+    /// The number of batches fetched by this loader so far.
+    pub fn batch_count(&self) -> usize {
+        self.batches.load(Ordering::Relaxed)
+    }
+
+    fn cached(&self, key: &K) -> Option<Option<V>> {
+        self.cache.lock().unwrap().get(key).cloned()
+    }
+}
+
+/// The batch loaders of a request, see [`Context::loaders`]. These
+/// are used by model methods which are called once per item when
+/// rendering lists, e.g. [`Url::created_by`].
+#[derive(Default)]
+pub struct Loaders {
+    users: Loader<UserID, User>,
+    urls: Loader<UrlID, Url>,
+    upvoted_by_viewer: Loader<UrlID, bool>,
+    saved_by_viewer: Loader<UrlID, bool>,
+    hidden_by_viewer: Loader<UrlID, bool>,
+    tags: Loader<UrlID, Vec<Tag>>,
+}
+
+impl Loaders {
+    pub async fn user(&self, ctx: &Context, id: UserID) -> Result<User> {
+        self.users
+            .load(id, |ids| User::find_all(ctx, ids))
+            .await?
+            .ok_or_else(|| anyhow!("User not found"))
+    }
+
+    pub async fn url(&self, ctx: &Context, id: UrlID) -> Result<Url> {
+        self.urls
+            .load(id, |ids| Url::find_all(ctx, ids))
+            .await?
+            .ok_or_else(|| anyhow!("Url not found"))
+    }
+
+    /// If the logged in user upvoted the given URL.
+    pub async fn upvoted_by_viewer(&self, ctx: &Context, id: UrlID) -> Result<bool> {
+        let upvoted = self
+            .upvoted_by_viewer
+            .load(id, |ids| Url::upvoted_by_viewer_all(ctx, ids))
+            .await?;
+        Ok(upvoted.unwrap_or(false))
+    }
+
+    /// If the logged in user saved the given URL.
+    pub async fn saved_by_viewer(&self, ctx: &Context, id: UrlID) -> Result<bool> {
+        let saved = self
+            .saved_by_viewer
+            .load(id, |ids| Url::saved_by_viewer_all(ctx, ids))
+            .await?;
+        Ok(saved.unwrap_or(false))
+    }
+
+    /// If the logged in user hid the given URL.
+    pub async fn hidden_by_viewer(&self, ctx: &Context, id: UrlID) -> Result<bool> {
+        let hidden = self
+            .hidden_by_viewer
+            .load(id, |ids| Url::hidden_by_viewer_all(ctx, ids))
+            .await?;
+        Ok(hidden.unwrap_or(false))
+    }
+
+    /// Tags of the given URL, in alphabetical order.
+    pub async fn tags(&self, ctx: &Context, id: UrlID) -> Result<Vec<Tag>> {
+        let tags = self.tags.load(id, |ids| Tag::for_urls(ctx, ids)).await?;
+        Ok(tags.unwrap_or_default())
+    }
+
+    /// The number of batches fetched by all loaders so far,
+    /// which is useful for spotting N+1 queries in tests.
+    pub fn batch_count(&self) -> usize {
+        self.users.batch_count()
+            + self.urls.batch_count()
+            + self.upvoted_by_viewer.batch_count()
+            + self.saved_by_viewer.batch_count()
+            + self.hidden_by_viewer.batch_count()
+            + self.tags.batch_count()
+    }
+
+    /// Drops cached information about the given user.
+    pub fn forget_user(&self, id: UserID) {
+        self.users.forget(&id);
+    }
+
+    /// Drops cached information about the given URL.
+    pub fn forget_url(&self, id: UrlID) {
+        self.urls.forget(&id);
+        self.upvoted_by_viewer.forget(&id);
+        self.saved_by_viewer.forget(&id);
+        self.hidden_by_viewer.forget(&id);
+        self.tags.forget(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    type Batches = Arc<Mutex<Vec<Vec<u32>>>>;
+
+    /// Loads the given key, recording the keys of every batch.
+    async fn load(loader: &Loader<u32, u32>, batches: &Batches, key: u32) -> Option<u32> {
+        loader
+            .load(key, |keys| async move {
+                batches.lock().unwrap().push(keys.clone());
+                // odd keys have no value
+                Ok(keys
+                    .into_iter()
+                    .filter(|key| key % 2 == 0)
+                    .map(|key| (key, key * 10))
+                    .collect())
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_loads_are_batched() {
+        let loader = Loader::default();
+        let batches = Batches::default();
+
+        let keys = [1, 2, 3, 2, 4];
+        let values =
+            futures_util::future::join_all(keys.iter().map(|key| load(&loader, &batches, *key)))
+                .await;
+        assert_eq!(values, vec![None, Some(20), None, Some(20), Some(40)]);
+        assert_eq!(*batches.lock().unwrap(), vec![vec![1, 2, 3, 4]]);
+
+        // cached keys, including missing values, are not fetched again
+        assert_eq!(load(&loader, &batches, 3).await, None);
+        assert_eq!(load(&loader, &batches, 4).await, Some(40));
+        assert_eq!(batches.lock().unwrap().len(), 1);
+        assert_eq!(loader.batch_count(), 1);
+
+        loader.forget(&4);
+        assert_eq!(load(&loader, &batches, 4).await, Some(40));
+        assert_eq!(batches.lock().unwrap().last(), Some(&vec![4]));
+    }
+}
@@ -1,30 +1,46 @@
+use crate::config::{LinkHealthConfig, RankingConfig, RateLimitConfig};
 use crate::db::models::Url;
 use crate::schema::urls;
 use crate::Config;
 use anyhow::Result;
 use async_trait::async_trait;
 use bb8_diesel::{bb8, DieselConnection, DieselConnectionManager};
-use diesel::{sqlite::SqliteConnection, RunQueryDsl};
+use diesel::{sqlite::SqliteConnection, ExpressionMethods, QueryDsl, RunQueryDsl};
 
+pub mod archive;
 pub mod id;
+pub mod loader;
 pub mod models;
+pub mod ranking;
 pub mod search;
+pub mod thumbnails;
 
 type DBPool = bb8::Pool<DieselConnectionManager<SqliteConnection>>;
 pub type PooledConnection<'a> =
     bb8::PooledConnection<'a, DieselConnectionManager<SqliteConnection>>;
+pub use archive::Archive;
+pub use loader::Loaders;
 pub use search::SearchIndex;
+pub use thumbnails::Thumbnails;
 
 #[derive(Clone)]
 pub struct Pool {
     pub db: DBPool,
     pub search: SearchIndex,
+    pub archive: Archive,
+    pub thumbnails: Thumbnails,
+    pub link_health: LinkHealthConfig,
+    pub rate_limits: RateLimitConfig,
+    pub hostname: String,
+    pub base_url: String,
 }
 
 diesel_migrations::embed_migrations!();
 
 #[derive(Debug)]
-struct Customizer;
+struct Customizer {
+    ranking: RankingConfig,
+}
 
 #[async_trait]
 impl bb8::CustomizeConnection<DieselConnection<SqliteConnection>, diesel::r2d2::Error>
@@ -39,6 +55,10 @@ impl bb8::CustomizeConnection<DieselConnection<SqliteConnection>, diesel::r2d2::
             log::error!("Failed to customize connection: {}", err);
             diesel::r2d2::Error::QueryError(err)
         })?;
+        ranking::register(&**conn, self.ranking).map_err(|err| {
+            log::error!("Failed to register ranking function: {}", err);
+            diesel::r2d2::Error::QueryError(err)
+        })?;
         Ok(())
     }
 }
@@ -47,11 +67,15 @@ pub async fn connect(config: &Config) -> Result<Pool> {
     let manager = DieselConnectionManager::new(config.database());
     let db = bb8::Pool::builder()
         .max_size(8)
-        .connection_customizer(Box::new(Customizer))
+        .connection_customizer(Box::new(Customizer {
+            ranking: *config.ranking(),
+        }))
         .build(manager)
         .await?;
 
     let search = SearchIndex::new(config).await?;
+    let archive = Archive::new(config).await?;
+    let thumbnails = Thumbnails::new(config).await?;
 
     {
         // Run migrations
@@ -60,10 +84,21 @@ pub async fn connect(config: &Config) -> Result<Pool> {
 
         // Set up search index on startup
         log::info!("Building search index ...");
-        let urls: Vec<Url> = urls::table.load(&*conn)?;
+        let urls: Vec<Url> = urls::table
+            .filter(urls::dsl::held_at.is_null())
+            .load(&*conn)?;
         search.index_urls(urls.iter())?;
         log::info!("Search index build completed");
     }
 
-    Ok(Pool { db, search })
+    Ok(Pool {
+        db,
+        search,
+        archive,
+        thumbnails,
+        link_health: *config.link_health(),
+        rate_limits: *config.rate_limits(),
+        hostname: config.hostname().to_string(),
+        base_url: config.base_url().to_string(),
+    })
 }
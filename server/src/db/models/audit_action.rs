@@ -0,0 +1,96 @@
+use diesel::backend::Backend;
+use diesel::deserialize::FromSql;
+use diesel::serialize::{Output, ToSql};
+use diesel::sql_types::Text;
+use juniper::GraphQLEnum;
+use std::io::Write;
+
+/// An action which is recorded in the
+/// audit log.
+#[derive(GraphQLEnum, AsExpression, FromSqlRow, Debug, Clone, Copy, PartialEq, Eq)]
+#[sql_type = "Text"]
+pub enum AuditAction {
+    /// A submitted URL was deleted.
+    DeleteUrl,
+    /// A comment was deleted.
+    DeleteComment,
+    /// A duplicate submission was merged into another
+    /// submission.
+    MergeUrl,
+    /// A permission was granted to a user.
+    GrantPermission,
+    /// A permission was revoked from a user.
+    RevokePermission,
+    /// The upvotes of a voting ring were discounted.
+    DiscountVotes,
+    /// A voting ring was dismissed, keeping its upvotes.
+    DismissVoteRing,
+    /// A submission held for moderation was approved.
+    ApproveUrl,
+    /// A domain rule was created or changed.
+    SetDomainRule,
+    /// A domain rule was removed.
+    DeleteDomainRule,
+}
+
+impl AuditAction {
+    /// A human readable name for the action.
+    pub fn label(&self) -> &'static str {
+        match *self {
+            AuditAction::DeleteUrl => "Deleted submission",
+            AuditAction::DeleteComment => "Deleted comment",
+            AuditAction::MergeUrl => "Merged submission",
+            AuditAction::GrantPermission => "Granted permission",
+            AuditAction::RevokePermission => "Revoked permission",
+            AuditAction::DiscountVotes => "Discounted voting ring",
+            AuditAction::DismissVoteRing => "Dismissed voting ring",
+            AuditAction::ApproveUrl => "Approved submission",
+            AuditAction::SetDomainRule => "Set domain rule",
+            AuditAction::DeleteDomainRule => "Removed domain rule",
+        }
+    }
+}
+
+impl<DB> ToSql<Text, DB> for AuditAction
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> diesel::serialize::Result {
+        let t = match *self {
+            AuditAction::DeleteUrl => "delete_url",
+            AuditAction::DeleteComment => "delete_comment",
+            AuditAction::MergeUrl => "merge_url",
+            AuditAction::GrantPermission => "grant_permission",
+            AuditAction::RevokePermission => "revoke_permission",
+            AuditAction::DiscountVotes => "discount_votes",
+            AuditAction::DismissVoteRing => "dismiss_vote_ring",
+            AuditAction::ApproveUrl => "approve_url",
+            AuditAction::SetDomainRule => "set_domain_rule",
+            AuditAction::DeleteDomainRule => "delete_domain_rule",
+        };
+        t.to_sql(out)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for AuditAction
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> diesel::deserialize::Result<Self> {
+        match String::from_sql(bytes)?.as_str() {
+            "delete_url" => Ok(AuditAction::DeleteUrl),
+            "delete_comment" => Ok(AuditAction::DeleteComment),
+            "merge_url" => Ok(AuditAction::MergeUrl),
+            "grant_permission" => Ok(AuditAction::GrantPermission),
+            "revoke_permission" => Ok(AuditAction::RevokePermission),
+            "discount_votes" => Ok(AuditAction::DiscountVotes),
+            "dismiss_vote_ring" => Ok(AuditAction::DismissVoteRing),
+            "approve_url" => Ok(AuditAction::ApproveUrl),
+            "set_domain_rule" => Ok(AuditAction::SetDomainRule),
+            "delete_domain_rule" => Ok(AuditAction::DeleteDomainRule),
+            _ => Err("Unrecognized audit action".into()),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::db::id::{AuditEntryID, UserID};
+use crate::db::models::{AuditAction, User};
+use crate::schema::audit_log;
+use crate::Context;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde_json::Value;
+
+/// An entry in the append-only log of moderation and
+/// administration actions.
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[table_name = "audit_log"]
+pub struct AuditEntry {
+    id: AuditEntryID,
+    created_at: NaiveDateTime,
+    actor: UserID,
+
+    action: AuditAction,
+    target_id: String,
+    reason: Option<String>,
+    snapshot: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn id(&self) -> AuditEntryID {
+        self.id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.created_at, Utc)
+    }
+
+    /// The user who performed the action.
+    pub async fn actor(&self, ctx: &Context) -> Result<User> {
+        User::find(ctx, self.actor).await
+    }
+
+    pub fn action(&self) -> AuditAction {
+        self.action
+    }
+
+    /// The ID of the object the action was performed on, e.g.
+    /// the deleted URL, or the user who was granted a permission.
+    pub fn target_id(&self) -> &str {
+        &self.target_id
+    }
+
+    /// The reason given for the action, if any.
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// A JSON snapshot of the affected content, as it was
+    /// before the action was performed.
+    pub fn snapshot(&self) -> Option<&str> {
+        self.snapshot.as_deref()
+    }
+}
+
+impl AuditEntry {
+    /// Appends an entry for an action performed by the logged
+    /// in user to the audit log. This takes a connection, such
+    /// that the entry can be recorded in the same transaction as
+    /// the action itself.
+    pub(super) fn record(
+        ctx: &Context,
+        conn: &SqliteConnection,
+        action: AuditAction,
+        target_id: &str,
+        reason: Option<&str>,
+        snapshot: Value,
+    ) -> Result<Self> {
+        let entry = AuditEntry {
+            id: AuditEntryID::new(),
+            created_at: ctx.now().naive_utc(),
+            actor: ctx.user_id()?,
+
+            action,
+            target_id: target_id.to_string(),
+            reason: reason
+                .map(str::trim)
+                .filter(|reason| !reason.is_empty())
+                .map(String::from),
+            snapshot: Some(snapshot.to_string()),
+        };
+        diesel::insert_into(audit_log::table)
+            .values(&entry)
+            .execute(conn)?;
+        Ok(entry)
+    }
+
+    /// Returns a page of audit log entries, newest first, as well
+    /// as the total number of pages. Only administrators can view
+    /// the audit log.
+    pub async fn paginate(ctx: &Context, page: i64, page_size: i64) -> Result<(Vec<Self>, i64)> {
+        check_view_permissions(ctx).await?;
+        let conn = ctx.conn().await?;
+        let total_count: i64 = audit_log::table
+            .select(diesel::dsl::count_star())
+            .get_result(&*conn)?;
+        let page_count = if total_count % page_size != 0 {
+            total_count / page_size + 1
+        } else {
+            total_count / page_size
+        };
+
+        let entries = audit_log::table
+            .order_by(audit_log::dsl::created_at.desc())
+            .then_order_by(audit_log::dsl::id.desc())
+            .offset(page * page_size)
+            .limit(page_size)
+            .load(&*conn)?;
+        Ok((entries, page_count))
+    }
+
+    /// Returns audit log entries in reverse chronological order, in
+    /// a way that's suitable for use with a Relay connection. Only
+    /// administrators can view the audit log.
+    pub async fn all(
+        ctx: &Context,
+        after: Option<AuditEntryID>,
+        before: Option<AuditEntryID>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Self>> {
+        check_view_permissions(ctx).await?;
+        let conn = ctx.conn().await?;
+
+        let mut query = audit_log::table
+            .order_by(audit_log::dsl::created_at.desc())
+            .then_order_by(audit_log::dsl::id.desc())
+            .into_boxed();
+
+        // entries recorded by the same request share a timestamp,
+        // and are ordered by ID
+        if let Some(after) = after {
+            let after: AuditEntry = audit_log::table.find(after).get_result(&*conn)?;
+            query = query.filter(
+                audit_log::dsl::created_at
+                    .lt(after.created_at)
+                    .or(audit_log::dsl::created_at
+                        .eq(after.created_at)
+                        .and(audit_log::dsl::id.lt(after.id))),
+            );
+        }
+
+        if let Some(before) = before {
+            let before: AuditEntry = audit_log::table.find(before).get_result(&*conn)?;
+            query = query.filter(
+                audit_log::dsl::created_at
+                    .gt(before.created_at)
+                    .or(audit_log::dsl::created_at
+                        .eq(before.created_at)
+                        .and(audit_log::dsl::id.gt(before.id))),
+            );
+        }
+
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+
+        Ok(query.load(&*conn)?)
+    }
+}
+
+async fn check_view_permissions(ctx: &Context) -> Result<()> {
+    ctx.user()
+        .await?
+        .check_permissions(ctx, |perm| perm.view_audit_log())
+        .await
+}
@@ -1,15 +1,19 @@
 use crate::db::id::{CommentID, UrlID, UserID};
-use crate::db::models::{Url, User};
-use crate::schema::comments;
+use crate::db::models::{AuditAction, AuditEntry, Flag, QuotaAction, Url, User};
+use crate::schema::{comments, urls};
 use crate::Context;
 use anyhow::Result;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
 use juniper::GraphQLInputObject;
 use pulldown_cmark::{html, Options, Parser};
+use serde_json::json;
 use validator::Validate;
 
-#[derive(Debug, Clone, Queryable, Identifiable, Insertable, AsChangeset, Associations)]
+#[derive(
+    Debug, Clone, Queryable, QueryableByName, Identifiable, Insertable, AsChangeset, Associations,
+)]
+#[table_name = "comments"]
 #[belongs_to(Url)]
 #[belongs_to(User, foreign_key = "created_by")]
 #[belongs_to(Comment, foreign_key = "replies_to")]
@@ -45,14 +49,7 @@ impl Comment {
     /// html. This safely escapes and html present
     /// on the input.
     pub fn html(&self) -> String {
-        let mut out = String::new();
-        let mut opts = Options::empty();
-        opts.insert(Options::ENABLE_STRIKETHROUGH);
-        opts.insert(Options::ENABLE_TASKLISTS);
-        opts.insert(Options::ENABLE_SMART_PUNCTUATION);
-        let parser = Parser::new_ext(self.text(), opts);
-        html::push_html(&mut out, parser);
-        out
+        markdown_html(self.text())
     }
 
     pub fn created_at(&self) -> DateTime<Utc> {
@@ -64,11 +61,11 @@ impl Comment {
     }
 
     pub async fn url(&self, ctx: &Context) -> Result<Url> {
-        Ok(Url::find(ctx, self.url_id).await?)
+        ctx.loaders().url(ctx, self.url_id).await
     }
 
     pub async fn created_by(&self, ctx: &Context) -> Result<User> {
-        Ok(User::find(ctx, self.created_by).await?)
+        ctx.loaders().user(ctx, self.created_by).await
     }
 
     pub async fn replies_to(&self, ctx: &Context) -> Result<Option<Self>> {
@@ -95,6 +92,7 @@ impl Comment {
     pub async fn create(ctx: &Context, mut input: NewCommentInput) -> Result<Self> {
         input.comment = input.comment.trim().into();
         input.validate()?;
+        let quota = QuotaAction::Comment.quota(ctx, ctx.user_id()?).await?;
 
         let comment = Comment {
             id: CommentID::new(),
@@ -106,17 +104,27 @@ impl Comment {
             created_by: ctx.user_id()?,
             replies_to: input.replies_to,
         };
-        diesel::insert_into(comments::table)
-            .values(&comment)
-            .execute(&*ctx.conn().await?)?;
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            quota.consume(ctx, &*conn)?;
+            diesel::insert_into(comments::table)
+                .values(&comment)
+                .execute(&*conn)?;
+            diesel::update(urls::table.find(comment.url_id))
+                .set(urls::dsl::comment_count.eq(urls::dsl::comment_count + 1))
+                .execute(&*conn)?;
+            Ok(())
+        })?;
+        ctx.loaders().forget_url(comment.url_id);
 
         Ok(comment)
     }
 
     /// Deletes a given comment from the database. If the comment
     /// has replies, the comment is censored instead. (This is done
-    /// to prevent loosing deletion of replies.)
-    pub async fn delete(&mut self, ctx: &Context) -> Result<()> {
+    /// to prevent loosing deletion of replies.) The deletion is recorded
+    /// in the audit log, together with the optional reason.
+    pub async fn delete(&mut self, ctx: &Context, reason: Option<&str>) -> Result<()> {
         if self.created_by != ctx.user_id()? {
             ctx.user()
                 .await?
@@ -124,19 +132,69 @@ impl Comment {
                 .await?;
         }
 
+        let snapshot = json!({
+            "comment": self.comment,
+            "url_id": self.url_id.to_string(),
+            "created_by": self.created_by.to_string(),
+            "created_at": self.created_at().to_rfc3339(),
+            "replies_to": self.replies_to.map(|id| id.to_string()),
+        });
+
         let replies_count: i64 = comments::table
             .filter(comments::dsl::replies_to.eq(self.id()))
             .select(diesel::dsl::count_star())
             .get_result(&*ctx.conn().await?)?;
 
         if replies_count > 0 {
-            self.updated_at = ctx.now().naive_utc();
-            self.comment = "[DELETED]".to_string();
-            *self = self.save_changes(&*ctx.conn().await?)?;
+            let conn = ctx.conn().await?;
+            conn.transaction::<_, anyhow::Error, _>(|| {
+                self.updated_at = ctx.now().naive_utc();
+                self.comment = "[DELETED]".to_string();
+                *self = self.save_changes(&*conn)?;
+                Flag::resolve_for_comment(ctx, &*conn, self.id)?;
+                AuditEntry::record(
+                    ctx,
+                    &*conn,
+                    AuditAction::DeleteComment,
+                    &self.id.to_string(),
+                    reason,
+                    snapshot,
+                )?;
+                Ok(())
+            })?;
         } else {
-            diesel::delete(&*self).execute(&*ctx.conn().await?)?;
+            let conn = ctx.conn().await?;
+            conn.transaction::<_, anyhow::Error, _>(|| {
+                Flag::delete_for_comment(&*conn, self.id)?;
+                diesel::delete(&*self).execute(&*conn)?;
+                diesel::update(urls::table.find(self.url_id))
+                    .set(urls::dsl::comment_count.eq(urls::dsl::comment_count - 1))
+                    .execute(&*conn)?;
+                AuditEntry::record(
+                    ctx,
+                    &*conn,
+                    AuditAction::DeleteComment,
+                    &self.id.to_string(),
+                    reason,
+                    snapshot,
+                )?;
+                Ok(())
+            })?;
+            ctx.loaders().forget_url(self.url_id);
         }
-
         Ok(())
     }
 }
+
+/// Render markdown as html, the way comments and
+/// text posts are displayed.
+pub(super) fn markdown_html(text: &str) -> String {
+    let mut out = String::new();
+    let mut opts = Options::empty();
+    opts.insert(Options::ENABLE_STRIKETHROUGH);
+    opts.insert(Options::ENABLE_TASKLISTS);
+    opts.insert(Options::ENABLE_SMART_PUNCTUATION);
+    let parser = Parser::new_ext(text, opts);
+    html::push_html(&mut out, parser);
+    out
+}
@@ -0,0 +1,60 @@
+use diesel::backend::Backend;
+use diesel::deserialize::FromSql;
+use diesel::serialize::{Output, ToSql};
+use diesel::sql_types::Text;
+use juniper::GraphQLEnum;
+use std::io::Write;
+
+/// The state of fetching the page a submitted
+/// URL links to.
+#[derive(GraphQLEnum, AsExpression, FromSqlRow, Debug, Clone, Copy, PartialEq, Eq)]
+#[sql_type = "Text"]
+pub enum CrawlState {
+    /// The page has not been fetched yet.
+    Pending,
+    /// The page was fetched successfully.
+    Crawled,
+    /// The page could not be fetched or returned
+    /// an error status.
+    Failed,
+}
+
+impl CrawlState {
+    pub fn is_pending(&self) -> bool {
+        *self == CrawlState::Pending
+    }
+
+    pub fn is_failed(&self) -> bool {
+        *self == CrawlState::Failed
+    }
+}
+
+impl<DB> ToSql<Text, DB> for CrawlState
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> diesel::serialize::Result {
+        let t = match *self {
+            CrawlState::Pending => "pending",
+            CrawlState::Crawled => "crawled",
+            CrawlState::Failed => "failed",
+        };
+        t.to_sql(out)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for CrawlState
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> diesel::deserialize::Result<Self> {
+        match String::from_sql(bytes)?.as_str() {
+            "pending" => Ok(CrawlState::Pending),
+            "crawled" => Ok(CrawlState::Crawled),
+            "failed" => Ok(CrawlState::Failed),
+            _ => Err("Unrecognized crawl state".into()),
+        }
+    }
+}
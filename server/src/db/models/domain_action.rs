@@ -0,0 +1,62 @@
+use diesel::backend::Backend;
+use diesel::deserialize::FromSql;
+use diesel::serialize::{Output, ToSql};
+use diesel::sql_types::Text;
+use juniper::GraphQLEnum;
+use std::io::Write;
+
+/// What happens to submissions from a domain
+/// which has a domain rule.
+#[derive(GraphQLEnum, AsExpression, FromSqlRow, Debug, Clone, Copy, PartialEq, Eq)]
+#[sql_type = "Text"]
+pub enum DomainAction {
+    /// Submissions are rejected.
+    Block,
+    /// Submissions are held until a moderator
+    /// approves them.
+    RequireModeration,
+    /// Submissions are accepted, even if a rule for
+    /// a parent domain would block or hold them.
+    Allow,
+}
+
+impl DomainAction {
+    /// A human readable name for the action.
+    pub fn label(&self) -> &'static str {
+        match *self {
+            DomainAction::Block => "Block",
+            DomainAction::RequireModeration => "Require moderation",
+            DomainAction::Allow => "Allow",
+        }
+    }
+}
+
+impl<DB> ToSql<Text, DB> for DomainAction
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> diesel::serialize::Result {
+        let t = match *self {
+            DomainAction::Block => "block",
+            DomainAction::RequireModeration => "require_moderation",
+            DomainAction::Allow => "allow",
+        };
+        t.to_sql(out)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for DomainAction
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> diesel::deserialize::Result<Self> {
+        match String::from_sql(bytes)?.as_str() {
+            "block" => Ok(DomainAction::Block),
+            "require_moderation" => Ok(DomainAction::RequireModeration),
+            "allow" => Ok(DomainAction::Allow),
+            _ => Err("Unrecognized domain action".into()),
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use crate::canonical;
+use crate::db::id::{DomainRuleID, UrlID, UserID};
+use crate::db::models::{AuditAction, AuditEntry, DomainAction, Url, User};
+use crate::schema::{domain_rules, urls};
+use crate::Context;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde_json::json;
+
+/// A rule which decides how submissions from a domain, and
+/// all its subdomains, are treated. If multiple rules match
+/// a domain, the most specific rule applies.
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable, AsChangeset)]
+pub struct DomainRule {
+    id: DomainRuleID,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+    created_by: UserID,
+
+    domain: String,
+    action: DomainAction,
+}
+
+impl DomainRule {
+    pub fn id(&self) -> DomainRuleID {
+        self.id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.created_at, Utc)
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.updated_at, Utc)
+    }
+
+    /// The administrator who created the rule.
+    pub async fn created_by(&self, ctx: &Context) -> Result<User> {
+        User::find(ctx, self.created_by).await
+    }
+
+    /// The domain this rule applies to. The rule also
+    /// applies to all subdomains.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    pub fn action(&self) -> DomainAction {
+        self.action
+    }
+}
+
+impl DomainRule {
+    /// Creates a rule for the given domain, or replaces the action
+    /// of the existing rule. Only administrators can manage domain
+    /// rules, and every change is recorded in the audit log.
+    pub async fn set(ctx: &Context, domain: &str, action: DomainAction) -> Result<Self> {
+        check_manage_permissions(ctx).await?;
+        let domain = canonical::normalize_domain(domain);
+        let parsed = canonical::domain(&format!("https://{}/", domain)).ok();
+        if parsed.as_deref() != Some(domain.as_str()) {
+            return Err(anyhow!("Invalid domain"));
+        }
+
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            let existing: Option<Self> = domain_rules::table
+                .filter(domain_rules::dsl::domain.eq(&domain))
+                .get_result(&*conn)
+                .optional()?;
+            let previous = existing.as_ref().map(|rule| rule.action.label());
+            let rule = match existing {
+                Some(mut rule) => {
+                    rule.action = action;
+                    rule.updated_at = ctx.now().naive_utc();
+                    rule.save_changes(&*conn)?
+                }
+                None => {
+                    let rule = Self {
+                        id: DomainRuleID::new(),
+                        created_at: ctx.now().naive_utc(),
+                        updated_at: ctx.now().naive_utc(),
+                        created_by: ctx.user_id()?,
+
+                        domain,
+                        action,
+                    };
+                    diesel::insert_into(domain_rules::table)
+                        .values(&rule)
+                        .execute(&*conn)?;
+                    rule
+                }
+            };
+
+            let snapshot = json!({
+                "domain": rule.domain,
+                "action": action.label(),
+                "previous_action": previous,
+            });
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::SetDomainRule,
+                &rule.id.to_string(),
+                None,
+                snapshot,
+            )?;
+            Ok(rule)
+        })
+    }
+
+    /// Removes this rule. Only administrators can manage
+    /// domain rules, and the removal is recorded in the audit
+    /// log.
+    pub async fn delete(&self, ctx: &Context) -> Result<()> {
+        check_manage_permissions(ctx).await?;
+        let snapshot = json!({
+            "domain": self.domain,
+            "action": self.action.label(),
+            "created_by": self.created_by.to_string(),
+            "created_at": self.created_at().to_rfc3339(),
+        });
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            diesel::delete(self).execute(&*conn)?;
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::DeleteDomainRule,
+                &self.id.to_string(),
+                None,
+                snapshot,
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Load the rule for exactly the given domain.
+    pub async fn find_by_domain(ctx: &Context, domain: &str) -> Result<Self> {
+        let rule = domain_rules::table
+            .filter(domain_rules::dsl::domain.eq(canonical::normalize_domain(domain)))
+            .get_result(&*ctx.conn().await?)?;
+        Ok(rule)
+    }
+
+    /// The most specific rule which applies to the given
+    /// domain, if any.
+    pub async fn matching(ctx: &Context, domain: &str) -> Result<Option<Self>> {
+        let rules: Vec<Self> = domain_rules::table
+            .filter(domain_rules::dsl::domain.eq_any(parent_domains(domain)))
+            .load(&*ctx.conn().await?)?;
+        Ok(rules.into_iter().max_by_key(|rule| rule.domain.len()))
+    }
+
+    /// Returns all rules in alphabetical order of their domains, in
+    /// a way that's suitable for use with a Relay connection. Only
+    /// administrators can view domain rules.
+    pub async fn all(
+        ctx: &Context,
+        after: Option<DomainRuleID>,
+        before: Option<DomainRuleID>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Self>> {
+        check_manage_permissions(ctx).await?;
+        let conn = ctx.conn().await?;
+
+        let mut query = domain_rules::table
+            .order_by(domain_rules::dsl::domain.asc())
+            .into_boxed();
+
+        if let Some(after) = after {
+            let after: DomainRule = domain_rules::table.find(after).get_result(&*conn)?;
+            query = query.filter(domain_rules::dsl::domain.gt(after.domain));
+        }
+
+        if let Some(before) = before {
+            let before: DomainRule = domain_rules::table.find(before).get_result(&*conn)?;
+            query = query.filter(domain_rules::dsl::domain.lt(before.domain));
+        }
+
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+
+        Ok(query.load(&*conn)?)
+    }
+
+    /// The number of existing submissions from the domain of this
+    /// rule, or any of its subdomains.
+    pub async fn matching_url_count(&self, ctx: &Context) -> Result<i64> {
+        check_manage_permissions(ctx).await?;
+        let count = urls::table
+            .filter(
+                urls::dsl::domain.eq(&self.domain).or(urls::dsl::domain
+                    .like(self.subdomain_pattern())
+                    .escape('\\')),
+            )
+            .select(diesel::dsl::count_star())
+            .get_result(&*ctx.conn().await?)?;
+        Ok(count)
+    }
+
+    /// Existing submissions from the domain of this rule, or any of
+    /// its subdomains, newest first. This can be used to review and
+    /// remove submissions after a domain was blocked.
+    pub async fn matching_urls(
+        &self,
+        ctx: &Context,
+        after: Option<UrlID>,
+        before: Option<UrlID>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Url>> {
+        check_manage_permissions(ctx).await?;
+        let conn = ctx.conn().await?;
+
+        let mut query = urls::table
+            .filter(
+                urls::dsl::domain.eq(&self.domain).or(urls::dsl::domain
+                    .like(self.subdomain_pattern())
+                    .escape('\\')),
+            )
+            .order_by(urls::dsl::created_at.desc())
+            .into_boxed();
+
+        if let Some(after) = after {
+            let after: Url = urls::table.find(after).get_result(&*conn)?;
+            query = query.filter(urls::dsl::created_at.lt(after.created_at().naive_utc()));
+        }
+
+        if let Some(before) = before {
+            let before: Url = urls::table.find(before).get_result(&*conn)?;
+            query = query.filter(urls::dsl::created_at.gt(before.created_at().naive_utc()));
+        }
+
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+
+        Ok(query.load(&*conn)?)
+    }
+
+    /// A `LIKE` pattern matching all subdomains of the
+    /// domain of this rule.
+    fn subdomain_pattern(&self) -> String {
+        let domain = self
+            .domain
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        format!("%.{}", domain)
+    }
+}
+
+async fn check_manage_permissions(ctx: &Context) -> Result<()> {
+    ctx.user()
+        .await?
+        .check_permissions(ctx, |perm| perm.manage_domain_rules())
+        .await
+}
+
+/// The given domain, and all its parent domains, e.g.
+/// `a.example.com`, `example.com`, and `com`.
+fn parent_domains(domain: &str) -> Vec<&str> {
+    let mut domains = vec![domain];
+    let mut rest = domain;
+    while let Some((_, parent)) = rest.split_once('.') {
+        domains.push(parent);
+        rest = parent;
+    }
+    domains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parent_domains() {
+        assert_eq!(
+            parent_domains("a.b.example.com"),
+            vec!["a.b.example.com", "b.example.com", "example.com", "com"]
+        );
+        assert_eq!(parent_domains("localhost"), vec!["localhost"]);
+    }
+}
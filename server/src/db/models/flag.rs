@@ -0,0 +1,354 @@
+use crate::db::id::{CommentID, FlagID, UrlID, UserID};
+use crate::db::models::{Comment, FlagReason, Url, User};
+use crate::schema::{comments, flags};
+use crate::Context;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Text};
+
+/// A report by a member that a submission or
+/// comment should be reviewed by moderators.
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+pub struct Flag {
+    id: FlagID,
+    created_at: NaiveDateTime,
+    created_by: UserID,
+
+    url_id: Option<UrlID>,
+    comment_id: Option<CommentID>,
+    reason: FlagReason,
+    dismissed_at: Option<NaiveDateTime>,
+}
+
+impl Flag {
+    pub fn id(&self) -> FlagID {
+        self.id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.created_at, Utc)
+    }
+
+    pub async fn created_by(&self, ctx: &Context) -> Result<User> {
+        User::find(ctx, self.created_by).await
+    }
+
+    pub fn reason(&self) -> FlagReason {
+        self.reason
+    }
+
+    /// The time a moderator dismissed this flag, if
+    /// it was dismissed.
+    pub fn dismissed_at(&self) -> Option<DateTime<Utc>> {
+        self.dismissed_at
+            .map(|dismissed_at| DateTime::from_utc(dismissed_at, Utc))
+    }
+}
+
+impl Flag {
+    /// Flag the given URL as the logged in user. Every user can
+    /// flag a URL at most once.
+    pub async fn create_for_url(ctx: &Context, url_id: UrlID, reason: FlagReason) -> Result<Self> {
+        let created_by = ctx.user_id()?;
+        let exists: i64 = flags::table
+            .filter(flags::dsl::created_by.eq(created_by))
+            .filter(flags::dsl::url_id.eq(url_id))
+            .select(diesel::dsl::count_star())
+            .get_result(&*ctx.conn().await?)?;
+        if exists > 0 {
+            return Err(anyhow!("You already flagged this submission"));
+        }
+        Self::insert(ctx, created_by, Some(url_id), None, reason).await
+    }
+
+    /// Flag the given comment as the logged in user. Every user
+    /// can flag a comment at most once.
+    pub async fn create_for_comment(
+        ctx: &Context,
+        comment_id: CommentID,
+        reason: FlagReason,
+    ) -> Result<Self> {
+        let created_by = ctx.user_id()?;
+        let exists: i64 = flags::table
+            .filter(flags::dsl::created_by.eq(created_by))
+            .filter(flags::dsl::comment_id.eq(comment_id))
+            .select(diesel::dsl::count_star())
+            .get_result(&*ctx.conn().await?)?;
+        if exists > 0 {
+            return Err(anyhow!("You already flagged this comment"));
+        }
+        Self::insert(ctx, created_by, None, Some(comment_id), reason).await
+    }
+
+    async fn insert(
+        ctx: &Context,
+        created_by: UserID,
+        url_id: Option<UrlID>,
+        comment_id: Option<CommentID>,
+        reason: FlagReason,
+    ) -> Result<Self> {
+        let flag = Flag {
+            id: FlagID::new(),
+            created_at: ctx.now().naive_utc(),
+            created_by,
+
+            url_id,
+            comment_id,
+            reason,
+            dismissed_at: None,
+        };
+        diesel::insert_into(flags::table)
+            .values(&flag)
+            .execute(&*ctx.conn().await?)?;
+        Ok(flag)
+    }
+
+    /// Flags of the given URL which were not dismissed yet, newest
+    /// first. Only moderators can see flags.
+    pub async fn pending_for_url(ctx: &Context, url_id: UrlID) -> Result<Vec<Self>> {
+        check_view_permissions(ctx).await?;
+        let flags = flags::table
+            .filter(flags::dsl::url_id.eq(url_id))
+            .filter(flags::dsl::dismissed_at.is_null())
+            .order_by(flags::dsl::created_at.desc())
+            .load(&*ctx.conn().await?)?;
+        Ok(flags)
+    }
+
+    /// Flags of the given comment which were not dismissed yet,
+    /// newest first. Only moderators can see flags.
+    pub async fn pending_for_comment(ctx: &Context, comment_id: CommentID) -> Result<Vec<Self>> {
+        check_view_permissions(ctx).await?;
+        let flags = flags::table
+            .filter(flags::dsl::comment_id.eq(comment_id))
+            .filter(flags::dsl::dismissed_at.is_null())
+            .order_by(flags::dsl::created_at.desc())
+            .load(&*ctx.conn().await?)?;
+        Ok(flags)
+    }
+
+    /// If the logged in user flagged the given URL.
+    pub async fn url_flagged_by_viewer(ctx: &Context, url_id: UrlID) -> Result<bool> {
+        if let Some(user_id) = ctx.maybe_user_id() {
+            let count: i64 = flags::table
+                .filter(flags::dsl::url_id.eq(url_id))
+                .filter(flags::dsl::created_by.eq(user_id))
+                .select(diesel::dsl::count_star())
+                .get_result(&*ctx.conn().await?)?;
+            Ok(count > 0)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// If the logged in user flagged the given comment.
+    pub async fn comment_flagged_by_viewer(ctx: &Context, comment_id: CommentID) -> Result<bool> {
+        if let Some(user_id) = ctx.maybe_user_id() {
+            let count: i64 = flags::table
+                .filter(flags::dsl::comment_id.eq(comment_id))
+                .filter(flags::dsl::created_by.eq(user_id))
+                .select(diesel::dsl::count_star())
+                .get_result(&*ctx.conn().await?)?;
+            Ok(count > 0)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Dismiss all pending flags of the given URL, keeping the
+    /// URL. Only moderators can dismiss flags.
+    pub async fn dismiss_for_url(ctx: &Context, url_id: UrlID) -> Result<()> {
+        ctx.user()
+            .await?
+            .check_permissions(ctx, |perm| perm.dismiss_flags())
+            .await?;
+        let pending = flags::table
+            .filter(flags::dsl::url_id.eq(url_id))
+            .filter(flags::dsl::dismissed_at.is_null());
+        diesel::update(pending)
+            .set(flags::dsl::dismissed_at.eq(ctx.now().naive_utc()))
+            .execute(&*ctx.conn().await?)?;
+        Ok(())
+    }
+
+    /// Dismiss all pending flags of the given comment, keeping
+    /// the comment. Only moderators can dismiss flags.
+    pub async fn dismiss_for_comment(ctx: &Context, comment_id: CommentID) -> Result<()> {
+        ctx.user()
+            .await?
+            .check_permissions(ctx, |perm| perm.dismiss_flags())
+            .await?;
+        Self::resolve_for_comment(ctx, &*ctx.conn().await?, comment_id)
+    }
+
+    /// Marks all pending flags of the given comment as dismissed,
+    /// e.g. after the comment was censored.
+    pub(super) fn resolve_for_comment(
+        ctx: &Context,
+        conn: &SqliteConnection,
+        comment_id: CommentID,
+    ) -> Result<()> {
+        let pending = flags::table
+            .filter(flags::dsl::comment_id.eq(comment_id))
+            .filter(flags::dsl::dismissed_at.is_null());
+        diesel::update(pending)
+            .set(flags::dsl::dismissed_at.eq(ctx.now().naive_utc()))
+            .execute(conn)?;
+        Ok(())
+    }
+
+    /// Removes all flags of the given URL and its comments,
+    /// before the URL is deleted.
+    pub(super) fn delete_for_url(conn: &SqliteConnection, url_id: UrlID) -> Result<()> {
+        let comment_ids = comments::table
+            .filter(comments::dsl::url_id.eq(url_id))
+            .select(comments::dsl::id.nullable());
+        let flags = flags::table.filter(
+            flags::dsl::url_id
+                .eq(url_id)
+                .or(flags::dsl::comment_id.eq_any(comment_ids)),
+        );
+        diesel::delete(flags).execute(conn)?;
+        Ok(())
+    }
+
+    /// Removes all flags of the given comment, before the
+    /// comment is deleted.
+    pub(super) fn delete_for_comment(conn: &SqliteConnection, comment_id: CommentID) -> Result<()> {
+        let flags = flags::table.filter(flags::dsl::comment_id.eq(comment_id));
+        diesel::delete(flags).execute(conn)?;
+        Ok(())
+    }
+}
+
+/// The moderation queue, i.e. submissions and comments with pending
+/// flags. Items with the most flags come first, ties are broken by
+/// the most recent flag.
+impl Flag {
+    /// Flagged URLs, in moderation queue order. The queue can be
+    /// paginated using the IDs of earlier or later URLs as cursors.
+    pub async fn flagged_urls(
+        ctx: &Context,
+        after: Option<UrlID>,
+        before: Option<UrlID>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Url>> {
+        check_view_permissions(ctx).await?;
+        let urls = diesel::sql_query(queue_query("url"))
+            .bind::<Nullable<Text>, _>(after)
+            .bind::<Nullable<Text>, _>(after)
+            .bind::<Nullable<Text>, _>(after)
+            .bind::<Nullable<Text>, _>(before)
+            .bind::<Nullable<Text>, _>(before)
+            .bind::<Nullable<Text>, _>(before)
+            .bind::<BigInt, _>(limit.unwrap_or(-1))
+            .load(&*ctx.conn().await?)?;
+        Ok(urls)
+    }
+
+    /// Flagged comments, in moderation queue order. The queue can
+    /// be paginated using the IDs of earlier or later comments as
+    /// cursors.
+    pub async fn flagged_comments(
+        ctx: &Context,
+        after: Option<CommentID>,
+        before: Option<CommentID>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Comment>> {
+        check_view_permissions(ctx).await?;
+        let comments = diesel::sql_query(queue_query("comment"))
+            .bind::<Nullable<Text>, _>(after)
+            .bind::<Nullable<Text>, _>(after)
+            .bind::<Nullable<Text>, _>(after)
+            .bind::<Nullable<Text>, _>(before)
+            .bind::<Nullable<Text>, _>(before)
+            .bind::<Nullable<Text>, _>(before)
+            .bind::<BigInt, _>(limit.unwrap_or(-1))
+            .load(&*ctx.conn().await?)?;
+        Ok(comments)
+    }
+}
+
+/// Query for the moderation queue of the given kind of item, i.e.
+/// `url` or `comment`. Items are ordered by their number of pending
+/// flags, the time of their latest flag, and their ID. The `after`
+/// and `before` cursors are compared by the same key, each is bound
+/// three times, followed by the limit (`-1` for no limit).
+fn queue_query(item: &str) -> String {
+    let cursor = format!(
+        "SELECT COUNT(*), MAX(created_at), ? FROM flags
+        WHERE {item}_id = ? AND dismissed_at IS NULL",
+        item = item
+    );
+    format!(
+        "SELECT {item}s.* FROM {item}s
+        INNER JOIN (
+            SELECT {item}_id, COUNT(*) AS flag_count, MAX(created_at) AS flagged_at
+            FROM flags
+            WHERE {item}_id IS NOT NULL AND dismissed_at IS NULL
+            GROUP BY {item}_id
+        ) queue ON queue.{item}_id = {item}s.id
+        WHERE (? IS NULL OR (queue.flag_count, queue.flagged_at, {item}s.id) < ({cursor}))
+        AND (? IS NULL OR (queue.flag_count, queue.flagged_at, {item}s.id) > ({cursor}))
+        ORDER BY queue.flag_count DESC, queue.flagged_at DESC, {item}s.id DESC
+        LIMIT ?",
+        item = item,
+        cursor = cursor
+    )
+}
+
+async fn check_view_permissions(ctx: &Context) -> Result<()> {
+    ctx.user()
+        .await?
+        .check_permissions(ctx, |perm| perm.view_flags())
+        .await
+}
+
+/// Selects the IDs strictly between the `after` and `before`
+/// cursors, keeping at most `limit` IDs.
+pub(super) fn paginate_queue<T: PartialEq>(
+    mut ids: Vec<T>,
+    after: Option<T>,
+    before: Option<T>,
+    limit: Option<i64>,
+) -> Vec<T> {
+    if let Some(before) = before {
+        let end = ids.iter().position(|id| *id == before).unwrap_or(ids.len());
+        ids.truncate(end);
+    }
+    if let Some(after) = after {
+        let start = ids
+            .iter()
+            .position(|id| *id == after)
+            .map_or(0, |idx| idx + 1);
+        ids.drain(..start);
+    }
+    if let Some(limit) = limit {
+        ids.truncate(limit.max(0) as usize);
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_queue() {
+        let ids = vec![1, 2, 3, 4, 5];
+        assert_eq!(paginate_queue(ids.clone(), None, None, None), ids);
+        assert_eq!(
+            paginate_queue(ids.clone(), Some(2), None, Some(2)),
+            vec![3, 4]
+        );
+        assert_eq!(paginate_queue(ids.clone(), None, Some(3), None), vec![1, 2]);
+        assert_eq!(
+            paginate_queue(ids.clone(), Some(1), Some(4), None),
+            vec![2, 3]
+        );
+        assert_eq!(paginate_queue(ids.clone(), Some(5), None, None), vec![]);
+        // unknown cursors are ignored
+        assert_eq!(paginate_queue(ids, Some(9), Some(9), Some(1)), vec![1]);
+    }
+}
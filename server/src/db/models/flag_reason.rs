@@ -0,0 +1,70 @@
+use diesel::backend::Backend;
+use diesel::deserialize::FromSql;
+use diesel::serialize::{Output, ToSql};
+use diesel::sql_types::Text;
+use juniper::GraphQLEnum;
+use std::io::Write;
+
+/// The reason a member gave for flagging a
+/// submission or comment.
+#[derive(GraphQLEnum, AsExpression, FromSqlRow, Debug, Clone, Copy, PartialEq, Eq)]
+#[sql_type = "Text"]
+pub enum FlagReason {
+    /// Advertising or other unsolicited content.
+    Spam,
+    /// Content which does not fit the site.
+    OffTopic,
+    /// Harassment, hate speech, or otherwise abusive content.
+    Abusive,
+    /// A link which was already submitted.
+    Duplicate,
+    /// Any other reason.
+    Other,
+}
+
+impl FlagReason {
+    /// A human readable name for the reason.
+    pub fn label(&self) -> &'static str {
+        match *self {
+            FlagReason::Spam => "Spam",
+            FlagReason::OffTopic => "Off-topic",
+            FlagReason::Abusive => "Abusive",
+            FlagReason::Duplicate => "Duplicate",
+            FlagReason::Other => "Other",
+        }
+    }
+}
+
+impl<DB> ToSql<Text, DB> for FlagReason
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> diesel::serialize::Result {
+        let t = match *self {
+            FlagReason::Spam => "spam",
+            FlagReason::OffTopic => "off_topic",
+            FlagReason::Abusive => "abusive",
+            FlagReason::Duplicate => "duplicate",
+            FlagReason::Other => "other",
+        };
+        t.to_sql(out)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for FlagReason
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> diesel::deserialize::Result<Self> {
+        match String::from_sql(bytes)?.as_str() {
+            "spam" => Ok(FlagReason::Spam),
+            "off_topic" => Ok(FlagReason::OffTopic),
+            "abusive" => Ok(FlagReason::Abusive),
+            "duplicate" => Ok(FlagReason::Duplicate),
+            "other" => Ok(FlagReason::Other),
+            _ => Err("Unrecognized flag reason".into()),
+        }
+    }
+}
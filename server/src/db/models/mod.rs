@@ -1,15 +1,44 @@
+mod audit_action;
+mod audit_entry;
 mod comment;
+mod crawl_state;
+mod domain_action;
+mod domain_rule;
+mod flag;
+mod flag_reason;
 mod invite;
 mod login;
 mod permission;
+mod quota;
+mod redirect;
 mod role;
+mod tag;
+mod upvote;
 mod url;
+mod url_check;
+mod url_edit;
 mod user;
+mod vote_ring;
 
+pub use audit_action::AuditAction;
+pub use audit_entry::AuditEntry;
 pub use comment::{Comment, NewCommentInput};
+pub use crawl_state::CrawlState;
+pub use domain_action::DomainAction;
+pub use domain_rule::DomainRule;
+pub use flag::Flag;
+pub use flag_reason::FlagReason;
 pub use invite::Invite;
 pub use login::Login;
 pub use permission::Permission;
+pub use quota::{QuotaAction, QuotaExceeded};
+pub use redirect::Redirect;
 pub use role::Role;
-pub use url::{NewUrlInput, Url, UrlOrdering};
+pub use tag::Tag;
+pub use upvote::Upvote;
+pub(crate) use url::NOT_MUTED_DOMAIN;
+pub use url::{DomainStats, NewTextPostInput, NewUrlInput, UpdateUrlInput, Url, UrlOrdering};
+pub use url_check::UrlCheck;
+pub use url_edit::UrlEdit;
 pub use user::{NewUserInput, UpdateUserInput, User};
+pub use vote_ring::VoteRing;
@@ -13,6 +13,14 @@ pub enum Permission {
 }
 
 impl Permission {
+    /// The name under which this permission is stored.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Permission::Administrator => "administrator",
+            Permission::Moderator => "moderator",
+        }
+    }
+
     /// Determine if this permission grants the ability to
     /// create unlimited invite tokens.
     pub fn unlimited_invites(&self) -> bool {
@@ -49,6 +57,134 @@ impl Permission {
         }
     }
 
+    /// Determine if this permission grants the ability to
+    /// change the tags of urls not submitted by this user.
+    pub fn retag_any_url(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// edit the title and description of urls not submitted
+    /// by this user.
+    pub fn edit_any_url(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// view the history of edits to urls.
+    pub fn view_url_edits(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// see who upvoted urls, including users who did not
+    /// make their upvotes public.
+    pub fn view_upvotes(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// merge duplicate submissions.
+    pub fn merge_urls(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// merge tags.
+    pub fn merge_tags(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// view flagged submissions and comments.
+    pub fn view_flags(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// dismiss flags, keeping the flagged content.
+    pub fn dismiss_flags(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// approve submissions which were held for moderation.
+    pub fn approve_held_urls(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => true,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// block, hold, or allow submissions by domain.
+    pub fn manage_domain_rules(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => false,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// view the report of dead links.
+    pub fn view_dead_urls(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => false,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// view the audit log of moderation actions.
+    pub fn view_audit_log(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => false,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// view the report of suspected voting rings.
+    pub fn view_vote_rings(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => false,
+        }
+    }
+
+    /// Determine if this permission grants the ability to
+    /// discount the upvotes of voting rings, or dismiss them.
+    pub fn discount_votes(&self) -> bool {
+        match *self {
+            Permission::Administrator => true,
+            Permission::Moderator => false,
+        }
+    }
+
     /// Determine if this permission grants the ability to
     /// access database backups.
     pub fn access_admin_backups(&self) -> bool {
@@ -65,11 +201,7 @@ where
     str: ToSql<Text, DB>,
 {
     fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> diesel::serialize::Result {
-        let t = match *self {
-            Permission::Administrator => "administrator",
-            Permission::Moderator => "moderator",
-        };
-        t.to_sql(out)
+        self.name().to_sql(out)
     }
 }
 
@@ -0,0 +1,187 @@
+use crate::config::Quota;
+use crate::db::id::{QuotaUsageID, UserID};
+use crate::db::models::User;
+use crate::schema::quota_usage;
+use crate::Context;
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use std::fmt;
+
+/// Actions which count towards the hourly and daily
+/// quotas of a user, see [`RateLimitConfig`](crate::config::RateLimitConfig).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaAction {
+    Submission,
+    Comment,
+}
+
+/// Error returned when a user has used up their quota for
+/// an action. It reports the quota which is left, and when
+/// the action is possible again.
+#[derive(Debug, Clone)]
+pub struct QuotaExceeded {
+    action: QuotaAction,
+    limit: i64,
+    period: &'static str,
+    remaining_hour: i64,
+    remaining_day: i64,
+    resets_at: DateTime<Utc>,
+    new_account: bool,
+}
+
+impl QuotaAction {
+    fn name(&self) -> &'static str {
+        match *self {
+            QuotaAction::Submission => "submissions",
+            QuotaAction::Comment => "comments",
+        }
+    }
+
+    /// Looks up the quota the given user has for this kind of
+    /// action. Users who hold any permissions, i.e. moderators,
+    /// are not limited.
+    pub(super) async fn quota(self, ctx: &Context, user_id: UserID) -> Result<UserQuota> {
+        let user = User::find(ctx, user_id).await?;
+        let limits = ctx.rate_limits();
+        let new_account = ctx.now() - user.created_at() < Duration::days(limits.new_account_days());
+        let quota = if user.permissions(ctx).await?.is_empty() {
+            match self {
+                QuotaAction::Submission => Some(limits.submissions(new_account)),
+                QuotaAction::Comment => Some(limits.comments(new_account)),
+            }
+        } else {
+            None
+        };
+        Ok(UserQuota {
+            action: self,
+            user_id,
+            quota,
+            new_account,
+        })
+    }
+}
+
+/// The quota of a user for one kind of action, see
+/// [`QuotaAction::quota`].
+pub(super) struct UserQuota {
+    action: QuotaAction,
+    user_id: UserID,
+    quota: Option<Quota>,
+    new_account: bool,
+}
+
+impl UserQuota {
+    /// Checks that the user may perform another action, and returns
+    /// [`QuotaExceeded`] otherwise. The action is recorded, such that
+    /// it keeps counting towards the quota even if the submission or
+    /// comment is deleted. This takes a connection, such that the
+    /// check runs in the same transaction as the action itself.
+    pub(super) fn consume(&self, ctx: &Context, conn: &SqliteConnection) -> Result<()> {
+        let quota = match self.quota {
+            Some(quota) => quota,
+            None => return Ok(()),
+        };
+
+        let hour_ago = ctx.now() - Duration::hours(1);
+        let day_ago = ctx.now() - Duration::days(1);
+        let last_day: Vec<NaiveDateTime> = quota_usage::table
+            .filter(quota_usage::dsl::user_id.eq(self.user_id))
+            .filter(quota_usage::dsl::action.eq(self.action.name()))
+            .filter(quota_usage::dsl::used_at.gt(day_ago.naive_utc()))
+            .select(quota_usage::dsl::used_at)
+            .order_by(quota_usage::dsl::used_at.asc())
+            .load(conn)?;
+        let last_hour: Vec<NaiveDateTime> = last_day
+            .iter()
+            .copied()
+            .filter(|used_at| *used_at > hour_ago.naive_utc())
+            .collect();
+
+        // the quota frees up once the oldest actions which
+        // count towards it drop out of the window
+        let exceeded = |times: &[NaiveDateTime], limit: i64, window: Duration| {
+            let overflow = times.len() as i64 - limit;
+            if overflow >= 0 {
+                Some(DateTime::from_utc(times[overflow as usize], Utc) + window)
+            } else {
+                None
+            }
+        };
+        let hourly = exceeded(&last_hour, quota.per_hour(), Duration::hours(1));
+        let daily = exceeded(&last_day, quota.per_day(), Duration::days(1));
+
+        let (limit, period, resets_at) = match (hourly, daily) {
+            (_, Some(daily)) => (
+                quota.per_day(),
+                "day",
+                hourly.map_or(daily, |h| h.max(daily)),
+            ),
+            (Some(hourly), None) => (quota.per_hour(), "hour", hourly),
+            (None, None) => return self.record(ctx, conn),
+        };
+        Err(QuotaExceeded {
+            action: self.action,
+            limit,
+            period,
+            remaining_hour: (quota.per_hour() - last_hour.len() as i64).max(0),
+            remaining_day: (quota.per_day() - last_day.len() as i64).max(0),
+            resets_at,
+            new_account: self.new_account,
+        }
+        .into())
+    }
+
+    fn record(&self, ctx: &Context, conn: &SqliteConnection) -> Result<()> {
+        diesel::insert_into(quota_usage::table)
+            .values((
+                quota_usage::dsl::id.eq(QuotaUsageID::new()),
+                quota_usage::dsl::user_id.eq(self.user_id),
+                quota_usage::dsl::action.eq(self.action.name()),
+                quota_usage::dsl::used_at.eq(ctx.now().naive_utc()),
+            ))
+            .execute(conn)?;
+        Ok(())
+    }
+}
+
+impl QuotaExceeded {
+    pub fn action(&self) -> QuotaAction {
+        self.action
+    }
+
+    /// How many more actions are possible in
+    /// the current hour.
+    pub fn remaining_hour(&self) -> i64 {
+        self.remaining_hour
+    }
+
+    /// How many more actions are possible in
+    /// the current day.
+    pub fn remaining_day(&self) -> i64 {
+        self.remaining_day
+    }
+
+    /// When the next action will be possible.
+    pub fn resets_at(&self) -> DateTime<Utc> {
+        self.resets_at
+    }
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Exceeded the limit of {} {} per {}{} ({} left this hour, {} left today), try again after {}",
+            self.limit,
+            self.action.name(),
+            self.period,
+            if self.new_account { " for new accounts" } else { "" },
+            self.remaining_hour,
+            self.remaining_day,
+            self.resets_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        )
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
@@ -0,0 +1,69 @@
+use crate::db::id::UrlID;
+use crate::schema::url_redirects;
+use crate::Context;
+use anyhow::Result;
+use diesel::prelude::*;
+use std::convert::TryInto;
+use warp::http::StatusCode;
+
+/// A redirect which was followed when
+/// crawling a submitted URL.
+#[derive(Debug, Clone, Queryable, Insertable)]
+#[table_name = "url_redirects"]
+pub struct Redirect {
+    url_id: UrlID,
+    position: i32,
+    location: String,
+    status_code: i32,
+}
+
+impl Redirect {
+    /// The URL which responded with a redirect.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// The redirect status code, e.g. `301`.
+    pub fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status_code.try_into().unwrap_or(500))
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl Redirect {
+    /// The redirect chain of the given URL, in
+    /// the order the redirects were followed.
+    pub async fn for_url(ctx: &Context, url_id: UrlID) -> Result<Vec<Self>> {
+        let redirects = url_redirects::table
+            .filter(url_redirects::dsl::url_id.eq(url_id))
+            .order_by(url_redirects::dsl::position.asc())
+            .load(&*ctx.conn().await?)?;
+        Ok(redirects)
+    }
+
+    /// Replaces the redirect chain of the given URL. The
+    /// chain is given as pairs of location and status code.
+    pub(super) async fn replace_for_url(
+        ctx: &Context,
+        url_id: UrlID,
+        chain: &[(String, StatusCode)],
+    ) -> Result<()> {
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            let existing = url_redirects::table.filter(url_redirects::dsl::url_id.eq(url_id));
+            diesel::delete(existing).execute(&*conn)?;
+            for (position, (location, status)) in chain.iter().enumerate() {
+                let redirect = Redirect {
+                    url_id,
+                    position: position.try_into()?,
+                    location: location.clone(),
+                    status_code: status.as_u16().into(),
+                };
+                diesel::insert_into(url_redirects::table)
+                    .values(&redirect)
+                    .execute(&*conn)?;
+            }
+            Ok(())
+        })
+    }
+}
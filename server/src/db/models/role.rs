@@ -1,10 +1,11 @@
 use crate::db::id::{RoleID, UserID};
-use crate::db::models::{Permission, User};
+use crate::db::models::{AuditAction, AuditEntry, Permission, User};
 use crate::schema::roles;
 use crate::Context;
 use anyhow::Result;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
+use serde_json::json;
 
 #[derive(Debug, Clone, Queryable, Identifiable, Insertable, AsChangeset, Associations)]
 #[belongs_to(User)]
@@ -73,4 +74,87 @@ impl Role {
         role.delete(ctx).await?;
         Ok(role)
     }
+
+    /// Grants the given permission to the given user, as the logged in
+    /// user. Only administrators can grant permissions, and every grant
+    /// is recorded in the audit log.
+    pub async fn grant(
+        ctx: &Context,
+        user: &User,
+        permission: Permission,
+        reason: Option<&str>,
+    ) -> Result<Self> {
+        check_modify_permissions(ctx).await?;
+        let role = Self {
+            id: RoleID::new(),
+            created_at: ctx.now().naive_utc(),
+            updated_at: ctx.now().naive_utc(),
+
+            user_id: user.id(),
+            permission,
+        };
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            diesel::insert_into(roles::table)
+                .values(&role)
+                .execute(&*conn)?;
+            let snapshot = json!({
+                "permission": permission.name(),
+                "user": user.id().to_string(),
+                "user_name": user.name(),
+            });
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::GrantPermission,
+                &user.id().to_string(),
+                reason,
+                snapshot,
+            )?;
+            Ok(())
+        })?;
+        Ok(role)
+    }
+
+    /// Revokes the given permission from the given user, as the logged
+    /// in user. Only administrators can revoke permissions, and every
+    /// revocation is recorded in the audit log.
+    pub async fn revoke(
+        ctx: &Context,
+        user: &User,
+        permission: Permission,
+        reason: Option<&str>,
+    ) -> Result<Self> {
+        check_modify_permissions(ctx).await?;
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            let role: Self = roles::table
+                .filter(roles::dsl::user_id.eq(user.id()))
+                .filter(roles::dsl::permission.eq(permission))
+                .get_result(&*conn)?;
+            diesel::delete(&role).execute(&*conn)?;
+            let snapshot = json!({
+                "permission": permission.name(),
+                "user": user.id().to_string(),
+                "user_name": user.name(),
+                "granted_at": role.created_at().to_rfc3339(),
+            });
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::RevokePermission,
+                &user.id().to_string(),
+                reason,
+                snapshot,
+            )?;
+            Ok(role)
+        })
+    }
+}
+
+async fn check_modify_permissions(ctx: &Context) -> Result<()> {
+    ctx.user()
+        .await?
+        .check_permissions(ctx, |perm| perm.modify_user_roles())
+        .await
 }
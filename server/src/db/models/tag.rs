@@ -0,0 +1,250 @@
+use crate::db::id::{TagID, UrlID};
+use crate::schema::{tags, url_tags};
+use crate::Context;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+pub const MAX_TAGS_PER_URL: usize = 5;
+const MAX_TAG_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable, AsChangeset)]
+pub struct Tag {
+    id: TagID,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+
+    name: String,
+}
+
+impl Tag {
+    pub fn id(&self) -> TagID {
+        self.id
+    }
+
+    /// The normalized name of this tag, e.g. `rust`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.created_at, Utc)
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.updated_at, Utc)
+    }
+
+    /// The number of URLs labeled with this tag.
+    pub async fn url_count(&self, ctx: &Context) -> Result<i64> {
+        let count = url_tags::table
+            .filter(url_tags::dsl::tag_id.eq(self.id))
+            .select(diesel::dsl::count_star())
+            .get_result(&*ctx.conn().await?)?;
+        Ok(count)
+    }
+}
+
+impl Tag {
+    /// Normalizes a user provided tag name. Tags are lower case,
+    /// may be prefixed with a `#`, and consist of alphanumeric
+    /// words joined by `-`.
+    pub fn normalize(name: &str) -> Result<String> {
+        let name = name.trim().trim_start_matches('#').to_lowercase();
+        let words: Vec<&str> = name
+            .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+            .filter(|word| !word.is_empty())
+            .collect();
+        let name = words.join("-");
+
+        if name.is_empty() {
+            Err(anyhow!("A tag can not be empty"))
+        } else if name.len() > MAX_TAG_LENGTH {
+            Err(anyhow!(
+                "A tag can be at most {} characters long",
+                MAX_TAG_LENGTH
+            ))
+        } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            Err(anyhow!(
+                "A tag can only contain letters, numbers, and dashes"
+            ))
+        } else {
+            Ok(name)
+        }
+    }
+
+    /// Normalizes a list of tag names, removing any
+    /// duplicates.
+    pub fn normalize_all(names: &[String]) -> Result<Vec<String>> {
+        let mut normalized: Vec<String> = vec![];
+        for name in names {
+            let name = Self::normalize(name)?;
+            if !normalized.contains(&name) {
+                normalized.push(name);
+            }
+        }
+        if normalized.len() > MAX_TAGS_PER_URL {
+            return Err(anyhow!("A URL can have at most {} tags", MAX_TAGS_PER_URL));
+        }
+        Ok(normalized)
+    }
+
+    /// Load by ID.
+    pub async fn find(ctx: &Context, id: TagID) -> Result<Self> {
+        let tag = tags::table.find(id).get_result(&*ctx.conn().await?)?;
+        Ok(tag)
+    }
+
+    /// Retrieve a tag by it's name. The name is
+    /// normalized before the lookup.
+    pub async fn find_by_name(ctx: &Context, name: &str) -> Result<Self> {
+        let tag = tags::table
+            .filter(tags::dsl::name.eq(Self::normalize(name)?))
+            .get_result(&*ctx.conn().await?)?;
+        Ok(tag)
+    }
+
+    /// Retrieve a tag by it's (already normalized) name, creating
+    /// the tag if it does not exist yet. If the same tag is created
+    /// concurrently, the existing tag is returned.
+    fn find_or_create(ctx: &Context, conn: &SqliteConnection, name: &str) -> Result<Self> {
+        let tag = Tag {
+            id: TagID::new(),
+            created_at: ctx.now().naive_utc(),
+            updated_at: ctx.now().naive_utc(),
+
+            name: name.to_string(),
+        };
+        diesel::insert_or_ignore_into(tags::table)
+            .values(&tag)
+            .execute(conn)?;
+        let tag = tags::table
+            .filter(tags::dsl::name.eq(name))
+            .get_result(conn)?;
+        Ok(tag)
+    }
+
+    /// All tags for the given URL, in alphabetical order.
+    pub async fn for_url(ctx: &Context, url_id: UrlID) -> Result<Vec<Self>> {
+        let tags = url_tags::table
+            .inner_join(tags::table)
+            .filter(url_tags::dsl::url_id.eq(url_id))
+            .order_by(tags::dsl::name.asc())
+            .select(tags::all_columns)
+            .load(&*ctx.conn().await?)?;
+        Ok(tags)
+    }
+
+    /// The tags for each of the given URLs, in alphabetical order.
+    pub async fn for_urls(ctx: &Context, url_ids: Vec<UrlID>) -> Result<HashMap<UrlID, Vec<Self>>> {
+        let rows: Vec<(UrlID, Self)> = url_tags::table
+            .inner_join(tags::table)
+            .filter(url_tags::dsl::url_id.eq_any(&url_ids))
+            .order_by(tags::dsl::name.asc())
+            .select((url_tags::dsl::url_id, tags::all_columns))
+            .load(&*ctx.conn().await?)?;
+        let mut tags: HashMap<UrlID, Vec<Self>> =
+            url_ids.into_iter().map(|id| (id, vec![])).collect();
+        for (url_id, tag) in rows {
+            tags.entry(url_id).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+
+    /// Replaces the tags of the given URL. This does not
+    /// check any permissions, and expects the names to be
+    /// normalized. This is meant to be called within the
+    /// transaction which creates or updates the URL.
+    pub(super) fn replace_for_url(
+        ctx: &Context,
+        conn: &SqliteConnection,
+        url_id: UrlID,
+        names: &[String],
+    ) -> Result<()> {
+        let existing = url_tags::table.filter(url_tags::dsl::url_id.eq(url_id));
+        diesel::delete(existing).execute(conn)?;
+        for name in names {
+            let tag = Self::find_or_create(ctx, conn, name)?;
+            diesel::insert_into(url_tags::table)
+                .values((
+                    url_tags::dsl::url_id.eq(url_id),
+                    url_tags::dsl::tag_id.eq(tag.id()),
+                    url_tags::dsl::created_at.eq(ctx.now().naive_utc()),
+                ))
+                .execute(conn)?;
+        }
+        Ok(())
+    }
+
+    /// Merges this tag into the tag `into`. All URLs labeled with
+    /// this tag are labeled with `into` instead, and this tag is
+    /// deleted. Only moderators can merge tags.
+    pub async fn merge(&self, ctx: &Context, into: &Tag) -> Result<()> {
+        ctx.user()
+            .await?
+            .check_permissions(ctx, |perm| perm.merge_tags())
+            .await?;
+        if self.id == into.id {
+            return Err(anyhow!("A tag can not be merged into itself"));
+        }
+
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            let tagged: Vec<(UrlID, NaiveDateTime)> = url_tags::table
+                .filter(url_tags::dsl::tag_id.eq(self.id))
+                .select((url_tags::dsl::url_id, url_tags::dsl::created_at))
+                .load(&*conn)?;
+            for (url_id, created_at) in tagged {
+                // URLs which already have both tags keep their existing label
+                diesel::insert_or_ignore_into(url_tags::table)
+                    .values((
+                        url_tags::dsl::url_id.eq(url_id),
+                        url_tags::dsl::tag_id.eq(into.id),
+                        url_tags::dsl::created_at.eq(created_at),
+                    ))
+                    .execute(&*conn)?;
+            }
+
+            let old = url_tags::table.filter(url_tags::dsl::tag_id.eq(self.id));
+            diesel::delete(old).execute(&*conn)?;
+            diesel::delete(self).execute(&*conn)?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let pairs = [
+            ("rust", "rust"),
+            ("  Rust ", "rust"),
+            ("#databases", "databases"),
+            ("machine learning", "machine-learning"),
+            ("web_assembly", "web-assembly"),
+            ("--c--", "c"),
+        ];
+        for (raw, clean) in pairs {
+            assert_eq!(Tag::normalize(raw).unwrap(), clean);
+        }
+
+        let too_long = "x".repeat(MAX_TAG_LENGTH + 1);
+        let invalid = ["", "   ", "#", "c++", "ünicode", too_long.as_str()];
+        for raw in invalid {
+            assert!(Tag::normalize(raw).is_err());
+        }
+    }
+
+    #[test]
+    fn test_normalize_all() {
+        let names = vec!["Rust".to_string(), "rust".to_string(), "#rust".to_string()];
+        assert_eq!(Tag::normalize_all(&names).unwrap(), vec!["rust"]);
+
+        let names: Vec<String> = (0..6).map(|i| format!("tag-{}", i)).collect();
+        assert!(Tag::normalize_all(&names).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+use crate::db::id::{UrlID, UserID};
+use crate::db::models::{Url, User};
+use crate::Context;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+/// An upvote of a URL. Who upvoted what is only visible to
+/// moderators, unless the user made their upvotes public, see
+/// [`User::public_upvotes`].
+#[derive(Debug, Clone, Queryable)]
+pub struct Upvote {
+    url_id: UrlID,
+    user_id: UserID,
+    created_at: NaiveDateTime,
+    discounted_at: Option<NaiveDateTime>,
+}
+
+impl Upvote {
+    pub fn url_id(&self) -> UrlID {
+        self.url_id
+    }
+
+    pub fn user_id(&self) -> UserID {
+        self.user_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.created_at, Utc)
+    }
+
+    /// When the upvote was discounted as part of a voting ring.
+    /// Discounted upvotes don't count towards the ranking, see
+    /// [`VoteRing::discount`](crate::db::models::VoteRing::discount).
+    pub fn discounted_at(&self) -> Option<DateTime<Utc>> {
+        self.discounted_at
+            .map(|discounted_at| DateTime::from_utc(discounted_at, Utc))
+    }
+
+    /// The upvoted URL.
+    pub async fn url(&self, ctx: &Context) -> Result<Url> {
+        ctx.loaders().url(ctx, self.url_id).await
+    }
+
+    /// The user who upvoted the URL.
+    pub async fn user(&self, ctx: &Context) -> Result<User> {
+        ctx.loaders().user(ctx, self.user_id).await
+    }
+}
+
+impl Upvote {
+    /// If the current viewer can see all upvotes, rather than
+    /// only those of users who made their upvotes public.
+    pub async fn viewer_sees_all(ctx: &Context) -> Result<bool> {
+        match ctx.maybe_user().await? {
+            Some(viewer) => Ok(viewer
+                .permissions(ctx)
+                .await?
+                .into_iter()
+                .any(|perm| perm.view_upvotes())),
+            None => Ok(false),
+        }
+    }
+}
@@ -1,22 +1,44 @@
-use crate::db::id::{UrlID, UserID};
-use crate::db::models::{Comment, User};
-use crate::schema::{comments, url_upvotes, urls, users};
+use crate::canonical;
+use crate::db::id::{TagID, UrlID, UserID};
+use crate::db::models::comment::markdown_html;
+use crate::db::models::{
+    AuditAction, AuditEntry, Comment, CrawlState, DomainAction, DomainRule, Flag, QuotaAction,
+    Redirect, Tag, UrlCheck, UrlEdit, User,
+};
+use crate::schema::{
+    comments, domain_mutes, flags, url_checks, url_edits, url_hides, url_merges, url_redirects,
+    url_saves, url_tags, url_upvotes, urls, user_mutes,
+};
 use crate::Context;
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
-use form_urlencoded::Serializer;
-use futures_util::StreamExt;
 use juniper::GraphQLInputObject;
 use meta_parser::Meta;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
-use std::str::FromStr;
 use validator::Validate;
-use warp::http::{uri::Scheme, StatusCode, Uri};
+use warp::http::{StatusCode, Uri};
 
-const INCLUDE_DAYS_IN_RANKED: i64 = 7;
+const MAX_URLS_PER_CRAWL: i64 = 16;
+/// Fetched pages are passed to the meta
+/// parser in chunks of this size.
+const META_CHUNK_SIZE: usize = 4096;
+/// Status code stored for URLs which did not
+/// receive a response yet.
+const STATUS_NOT_FETCHED: i32 = 0;
+/// Excludes submissions from domains muted by the user bound
+/// to the placeholder, or any of their subdomains. This matches
+/// domains like [`DomainRule::matching`].
+pub(crate) const NOT_MUTED_DOMAIN: &str = "NOT EXISTS (
+    SELECT 1 FROM domain_mutes WHERE domain_mutes.user_id = ?
+    AND (urls.domain = domain_mutes.domain
+        OR substr(urls.domain, -length(domain_mutes.domain) - 1) = '.' || domain_mutes.domain)
+)";
 
-#[derive(Debug, Clone, Queryable, Identifiable, Insertable, AsChangeset, Associations)]
+#[derive(Debug, Clone, Queryable, QueryableByName, Identifiable, Insertable, Associations)]
+#[table_name = "urls"]
 #[belongs_to(User, foreign_key = "created_by")]
 pub struct Url {
     id: UrlID,
@@ -29,12 +51,111 @@ pub struct Url {
     description: Option<String>,
     image: Option<String>,
     created_by: UserID,
+    crawl_state: CrawlState,
+    final_url: Option<String>,
+    canonical_url: Option<String>,
+    duplicate_of: Option<UrlID>,
+    archived_at: Option<NaiveDateTime>,
+    dead_since: Option<NaiveDateTime>,
+    thumbnail_at: Option<NaiveDateTime>,
+    domain: String,
+    held_at: Option<NaiveDateTime>,
+    text: Option<String>,
+    upvote_count: i64,
+    comment_count: i64,
+    title_override: Option<String>,
+    description_override: Option<String>,
+}
+
+/// The columns of a [`Url`] which are written when saving
+/// it. The cached counters are left out, since they are only
+/// changed in place, see [`Url::upvote`]. Likewise, edited
+/// titles and descriptions are only changed by [`Url::update`].
+#[derive(AsChangeset)]
+#[table_name = "urls"]
+struct UrlChanges<'a> {
+    updated_at: NaiveDateTime,
+    url: &'a str,
+    status_code: i32,
+    title: Option<&'a str>,
+    description: Option<&'a str>,
+    image: Option<&'a str>,
+    crawl_state: CrawlState,
+    final_url: Option<&'a str>,
+    canonical_url: Option<&'a str>,
+    duplicate_of: Option<UrlID>,
+    archived_at: Option<NaiveDateTime>,
+    dead_since: Option<NaiveDateTime>,
+    thumbnail_at: Option<NaiveDateTime>,
+    domain: &'a str,
+    held_at: Option<NaiveDateTime>,
+    text: Option<&'a str>,
+}
+
+/// Aggregate statistics of the submissions
+/// from a domain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DomainStats {
+    /// Number of submissions from the domain.
+    pub submissions: i64,
+    /// Total number of upvotes submissions from the
+    /// domain received.
+    pub upvotes: i64,
+    /// Number of submissions from the domain which are
+    /// considered dead.
+    pub dead: i64,
+}
+
+impl DomainStats {
+    /// The share of submissions which are dead, between
+    /// `0.0` and `1.0`.
+    pub fn dead_share(&self) -> f64 {
+        if self.submissions > 0 {
+            self.dead as f64 / self.submissions as f64
+        } else {
+            0.0
+        }
+    }
 }
 
 #[derive(Debug, Clone, Validate, GraphQLInputObject)]
 pub struct NewUrlInput {
     #[validate(url(message = "Please submit a valid URL"))]
     url: String,
+    tags: Option<Vec<String>>,
+}
+
+impl NewUrlInput {
+    pub fn new(url: String, tags: Vec<String>) -> Self {
+        Self {
+            url,
+            tags: Some(tags),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Validate, GraphQLInputObject)]
+pub struct UpdateUrlInput {
+    #[validate(length(max = 200, message = "The title must be at most 200 characters long"))]
+    title: Option<String>,
+    #[validate(length(
+        max = 1000,
+        message = "The description must be at most 1000 characters long"
+    ))]
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, Validate, GraphQLInputObject)]
+pub struct NewTextPostInput {
+    #[validate(length(
+        min = 1,
+        max = 200,
+        message = "The title must be 1 to 200 characters long"
+    ))]
+    title: String,
+    #[validate(length(min = 1, message = "The text can not be empty"))]
+    text: String,
+    tags: Option<Vec<String>>,
 }
 
 impl Url {
@@ -55,6 +176,23 @@ impl Url {
             .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
     }
 
+    /// The status code which was returned when last trying
+    /// to fetch the given URL, or `None` if the URL did not
+    /// respond yet.
+    pub fn fetched_status(&self) -> Option<StatusCode> {
+        if self.status_code == STATUS_NOT_FETCHED {
+            None
+        } else {
+            Some(self.status())
+        }
+    }
+
+    /// Whether the linked page was fetched yet, and if
+    /// fetching it succeeded.
+    pub fn crawl_state(&self) -> CrawlState {
+        self.crawl_state
+    }
+
     /// Return the url as a `&str`. This always succeeds
     /// but might return an invalid Uri, since it simply
     /// returns the value found in the database.
@@ -62,18 +200,133 @@ impl Url {
         &self.url
     }
 
-    /// The title provided by the linked html document, if
-    /// available.
+    /// The domain the URL is listed under, see
+    /// [`canonical::domain`](canonical::domain).
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// The time the submission was held for moderation, if it
+    /// is waiting to be approved. See [`DomainRule`].
+    pub fn held_at(&self) -> Option<DateTime<Utc>> {
+        self.held_at.map(|held_at| DateTime::from_utc(held_at, Utc))
+    }
+
+    /// If the submission is waiting to be approved by a moderator.
+    /// Held submissions are not listed, and not crawled.
+    pub fn is_held(&self) -> bool {
+        self.held_at.is_some()
+    }
+
+    /// If the current viewer may see this submission. Submissions
+    /// held for moderation are only visible to moderators and the
+    /// user who submitted them.
+    pub async fn visible_to_viewer(&self, ctx: &Context) -> Result<bool> {
+        if !self.is_held() || ctx.maybe_user_id() == Some(self.created_by) {
+            return Ok(true);
+        }
+        match ctx.maybe_user().await? {
+            Some(viewer) => Ok(viewer
+                .permissions(ctx)
+                .await?
+                .into_iter()
+                .any(|perm| perm.approve_held_urls())),
+            None => Ok(false),
+        }
+    }
+
+    /// The markdown body of a text post. Text posts are not
+    /// tied to an external page, their URL is the path of
+    /// their own discussion.
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// Render the body of a text post as html, see
+    /// [`Comment::html`](Comment::html).
+    pub fn html(&self) -> Option<String> {
+        self.text().map(markdown_html)
+    }
+
+    /// If the submission is a text post, rather than a link
+    /// to an external page. Text posts are never crawled.
+    pub fn is_text_post(&self) -> bool {
+        self.text.is_some()
+    }
+
+    /// The (canonicalized) URL the submission resolved to
+    /// after following any redirects, if it was crawled.
+    pub fn final_url(&self) -> Option<&str> {
+        self.final_url.as_deref()
+    }
+
+    /// The (canonicalized) URL the linked page declares as its
+    /// canonical location, if it declares one.
+    pub fn canonical_url(&self) -> Option<&str> {
+        self.canonical_url.as_deref()
+    }
+
+    /// If this URL turned out to link to the same page as an earlier
+    /// submission, this is the ID of the earlier submission.
+    pub fn duplicate_of(&self) -> Option<UrlID> {
+        self.duplicate_of
+    }
+
+    /// The time a snapshot of the linked page was saved
+    /// to the archive, if there is one.
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+            .map(|archived_at| DateTime::from_utc(archived_at, Utc))
+    }
+
+    /// The time of the first of the consecutive failed checks
+    /// after which the URL was considered dead, if it is dead.
+    pub fn dead_since(&self) -> Option<DateTime<Utc>> {
+        self.dead_since
+            .map(|dead_since| DateTime::from_utc(dead_since, Utc))
+    }
+
+    /// If the URL could not be reached repeatedly when checked
+    /// by the background job, see [`record_check`](Url::record_check).
+    pub fn is_dead(&self) -> bool {
+        self.dead_since.is_some()
+    }
+
+    /// The title of the submission. This is the title set
+    /// by the submitter or a moderator, if any, and otherwise
+    /// the title provided by the linked html document.
     pub fn title(&self) -> Option<&str> {
+        self.title_override
+            .as_deref()
+            .or_else(|| self.crawled_title())
+    }
+
+    /// The description of the submission, which can be
+    /// edited like the [`title`](Url::title).
+    pub fn description(&self) -> Option<&str> {
+        self.description_override
+            .as_deref()
+            .or_else(|| self.crawled_description())
+    }
+
+    /// The title provided by the linked html document, if
+    /// available. For text posts, this is the original title.
+    pub fn crawled_title(&self) -> Option<&str> {
         self.title.as_ref().map(AsRef::as_ref)
     }
 
     /// The description provided by the linked html
     /// document, if available.
-    pub fn description(&self) -> Option<&str> {
+    pub fn crawled_description(&self) -> Option<&str> {
         self.description.as_ref().map(AsRef::as_ref)
     }
 
+    /// All edits of the title and description, newest
+    /// first. Only moderators can see the edit history.
+    pub async fn edits(&self, ctx: &Context) -> Result<Vec<UrlEdit>> {
+        UrlEdit::for_url(ctx, self.id).await
+    }
+
     /// The image uri provided by the linked html
     /// document, if available.
     pub fn image(&self) -> Result<Option<Uri>> {
@@ -85,6 +338,14 @@ impl Url {
         Ok(maybe_uri)
     }
 
+    /// Path of the cached thumbnail of the image provided by
+    /// the linked html document, if one was created. The path
+    /// changes whenever the thumbnail is replaced.
+    pub fn thumbnail(&self) -> Option<String> {
+        self.thumbnail_at
+            .map(|thumbnail_at| format!("/img/{}?v={}", self.id, thumbnail_at.timestamp()))
+    }
+
     /// Return the image uri as a `&str`. This always succeeds
     /// but might return an invalid Uri, since it simply
     /// returns the value found in the database.
@@ -101,31 +362,27 @@ impl Url {
     }
 
     pub async fn created_by(&self, ctx: &Context) -> Result<User> {
-        let user = users::table
-            .find(self.created_by)
-            .get_result(&*ctx.conn().await?)?;
-        Ok(user)
+        ctx.loaders().user(ctx, self.created_by).await
     }
 
-    pub async fn upvote_count(&self, ctx: &Context) -> Result<i64> {
-        let count = url_upvotes::table
-            .filter(url_upvotes::dsl::url_id.eq(self.id))
-            .select(diesel::dsl::count_star())
-            .get_result(&*ctx.conn().await?)?;
-        Ok(count)
+    /// The number of upvotes the URL received. This is
+    /// cached on the URL, see [`check_counts`](Url::check_counts).
+    pub fn upvote_count(&self) -> i64 {
+        self.upvote_count
     }
 
     pub async fn upvoted_by_viewer(&self, ctx: &Context) -> Result<bool> {
-        if let Some(user_id) = ctx.maybe_user_id() {
-            let count: i64 = url_upvotes::table
-                .filter(url_upvotes::dsl::url_id.eq(self.id))
-                .filter(url_upvotes::dsl::user_id.eq(user_id))
-                .select(diesel::dsl::count_star())
-                .get_result(&*ctx.conn().await?)?;
-            Ok(count == 1)
-        } else {
-            Ok(false)
-        }
+        ctx.loaders().upvoted_by_viewer(ctx, self.id).await
+    }
+
+    /// If the URL was saved by the current viewer.
+    pub async fn saved_by_viewer(&self, ctx: &Context) -> Result<bool> {
+        ctx.loaders().saved_by_viewer(ctx, self.id).await
+    }
+
+    /// If the URL was hidden by the current viewer.
+    pub async fn hidden_by_viewer(&self, ctx: &Context) -> Result<bool> {
+        ctx.loaders().hidden_by_viewer(ctx, self.id).await
     }
 
     pub async fn comments(&self, ctx: &Context, limit: i64) -> Result<Vec<Comment>> {
@@ -138,12 +395,22 @@ impl Url {
         Ok(comments)
     }
 
-    pub async fn comment_count(&self, ctx: &Context) -> Result<i64> {
-        let count = comments::table
-            .filter(comments::dsl::url_id.eq(self.id))
-            .select(diesel::dsl::count_star())
-            .get_result(&*ctx.conn().await?)?;
-        Ok(count)
+    /// The number of comments on the URL. This is cached
+    /// on the URL, see [`check_counts`](Url::check_counts).
+    pub fn comment_count(&self) -> i64 {
+        self.comment_count
+    }
+
+    /// Tags this URL is labeled with, in alphabetical
+    /// order.
+    pub async fn tags(&self, ctx: &Context) -> Result<Vec<Tag>> {
+        ctx.loaders().tags(ctx, self.id).await
+    }
+
+    /// The redirects which were followed when this URL
+    /// was last crawled.
+    pub async fn redirects(&self, ctx: &Context) -> Result<Vec<Redirect>> {
+        Redirect::for_url(ctx, self.id).await
     }
 
     pub fn slug(&self) -> Option<String> {
@@ -163,7 +430,7 @@ impl Url {
             slug.make_ascii_lowercase();
             slug
         };
-        self.title.as_ref().map(|title| slugify(title)).or_else(|| {
+        self.title().map(slugify).or_else(|| {
             let url = self.url().ok()?;
             let authority = url.authority().map(|authority| slugify(authority.as_str()));
             let path = slugify(url.path());
@@ -185,8 +452,9 @@ impl Url {
 /// Determine how to order and filter the url
 /// pagination.
 #[derive(Debug, Clone, Copy)]
-pub enum UrlOrdering {
-    /// Default ranking used on the home page.
+pub enum UrlOrdering<'a> {
+    /// Default ranking used on the home page. Submissions are
+    /// ranked by upvotes and comments, decayed by their age.
     Ranked,
     /// All time best submissions.
     Best,
@@ -195,6 +463,21 @@ pub enum UrlOrdering {
     User(UserID),
     /// All submissions, ranked chronologically.
     Recent,
+    /// Submissions labeled with the given tag, ranked
+    /// chronologically.
+    Tag(TagID),
+    /// Submissions which are considered dead, the longest
+    /// dead first.
+    Dead,
+    /// Submissions saved by the given user, the most
+    /// recently saved first.
+    Saved(UserID),
+    /// Submissions from the given domain, ranked
+    /// chronologically. See [`Url::domain`].
+    Domain(&'a str),
+    /// Submissions upvoted by the given user, the most
+    /// recently upvoted first.
+    Upvoted(UserID),
 }
 
 impl Url {
@@ -203,22 +486,168 @@ impl Url {
         Ok(url)
     }
 
+    /// Load all URLs with the given IDs, keyed by ID. IDs
+    /// which do not exist are omitted.
+    pub async fn find_all(ctx: &Context, ids: Vec<UrlID>) -> Result<HashMap<UrlID, Self>> {
+        let urls: Vec<Self> = urls::table
+            .filter(urls::dsl::id.eq_any(ids))
+            .load(&*ctx.conn().await?)?;
+        Ok(urls.into_iter().map(|url| (url.id, url)).collect())
+    }
+
+    /// For each of the given URLs, if it was upvoted by the
+    /// current viewer.
+    pub async fn upvoted_by_viewer_all(
+        ctx: &Context,
+        ids: Vec<UrlID>,
+    ) -> Result<HashMap<UrlID, bool>> {
+        let upvoted: HashSet<UrlID> = match ctx.maybe_user_id() {
+            Some(user_id) => url_upvotes::table
+                .filter(url_upvotes::dsl::url_id.eq_any(&ids))
+                .filter(url_upvotes::dsl::user_id.eq(user_id))
+                .select(url_upvotes::dsl::url_id)
+                .load::<UrlID>(&*ctx.conn().await?)?
+                .into_iter()
+                .collect(),
+            None => HashSet::new(),
+        };
+        Ok(ids
+            .into_iter()
+            .map(|id| (id, upvoted.contains(&id)))
+            .collect())
+    }
+
+    /// For each of the given URLs, if it was saved by the
+    /// current viewer.
+    pub async fn saved_by_viewer_all(
+        ctx: &Context,
+        ids: Vec<UrlID>,
+    ) -> Result<HashMap<UrlID, bool>> {
+        let saved: HashSet<UrlID> = match ctx.maybe_user_id() {
+            Some(user_id) => url_saves::table
+                .filter(url_saves::dsl::url_id.eq_any(&ids))
+                .filter(url_saves::dsl::user_id.eq(user_id))
+                .select(url_saves::dsl::url_id)
+                .load::<UrlID>(&*ctx.conn().await?)?
+                .into_iter()
+                .collect(),
+            None => HashSet::new(),
+        };
+        Ok(ids
+            .into_iter()
+            .map(|id| (id, saved.contains(&id)))
+            .collect())
+    }
+
+    /// For each of the given URLs, the time it was saved by the
+    /// given user. URLs the user did not save are left out.
+    pub async fn saved_at_all(
+        ctx: &Context,
+        user_id: UserID,
+        ids: Vec<UrlID>,
+    ) -> Result<HashMap<UrlID, DateTime<Utc>>> {
+        let saved: Vec<(UrlID, NaiveDateTime)> = url_saves::table
+            .filter(url_saves::dsl::url_id.eq_any(&ids))
+            .filter(url_saves::dsl::user_id.eq(user_id))
+            .select((url_saves::dsl::url_id, url_saves::dsl::created_at))
+            .load(&*ctx.conn().await?)?;
+        Ok(saved
+            .into_iter()
+            .map(|(id, created_at)| (id, DateTime::from_utc(created_at, Utc)))
+            .collect())
+    }
+
+    /// For each of the given URLs, if it was hidden by the
+    /// current viewer.
+    pub async fn hidden_by_viewer_all(
+        ctx: &Context,
+        ids: Vec<UrlID>,
+    ) -> Result<HashMap<UrlID, bool>> {
+        let hidden: HashSet<UrlID> = match ctx.maybe_user_id() {
+            Some(user_id) => url_hides::table
+                .filter(url_hides::dsl::url_id.eq_any(&ids))
+                .filter(url_hides::dsl::user_id.eq(user_id))
+                .select(url_hides::dsl::url_id)
+                .load::<UrlID>(&*ctx.conn().await?)?
+                .into_iter()
+                .collect(),
+            None => HashSet::new(),
+        };
+        Ok(ids
+            .into_iter()
+            .map(|id| (id, hidden.contains(&id)))
+            .collect())
+    }
+
     /// Returns URLs ranked according to the given ordering, as well, as the total number of
-    /// available pages for the given ordering.
+    /// available pages for the given ordering. Submissions the viewer hid, or which are from
+    /// domains or users the viewer muted, are left out. (Except when listing the submissions
+    /// of a given user or domain.)
     pub async fn paginate(
         ctx: &Context,
-        order: UrlOrdering,
+        order: UrlOrdering<'_>,
         page: i64,
         page_size: i64,
     ) -> Result<(Vec<Self>, i64)> {
         use UrlOrdering::*;
 
-        let total_count_query = urls::table.select(diesel::dsl::count_star());
+        // lists of a given user or domain are not filtered, and the
+        // private saved list is filtered for its owner, such that the
+        // feed of saved URLs respects their mutes
+        let viewer = match order {
+            User(_) | Domain(_) | Upvoted(_) => None,
+            Saved(user_id) => Some(user_id),
+            Ranked | Best | Recent | Tag(_) | Dead => ctx.maybe_user_id(),
+        };
+        let hidden = url_hides::table
+            .filter(url_hides::dsl::user_id.nullable().eq(viewer))
+            .select(url_hides::dsl::url_id);
+        let not_muted_domain =
+            diesel::dsl::sql::<diesel::sql_types::Bool>(NOT_MUTED_DOMAIN)
+                .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(viewer);
+        let muted_users = user_mutes::table
+            .filter(user_mutes::dsl::user_id.nullable().eq(viewer))
+            .select(user_mutes::dsl::muted_id);
+
+        let total_count_query = urls::table
+            .filter(urls::dsl::duplicate_of.is_null())
+            .filter(urls::dsl::held_at.is_null())
+            .filter(urls::dsl::id.ne_all(hidden.clone()))
+            .filter(not_muted_domain.clone())
+            .filter(urls::dsl::created_by.ne_all(muted_users.clone()))
+            .select(diesel::dsl::count_star());
+        let hide_dead = ctx.link_health().hide_dead_from_ranked();
         let total_count: i64 = match order {
+            Ranked if hide_dead => total_count_query
+                .filter(urls::dsl::dead_since.is_null())
+                .get_result(&*ctx.conn().await?)?,
             Ranked | Best | Recent => total_count_query.get_result(&*ctx.conn().await?)?,
             User(creator_id) => total_count_query
                 .filter(urls::dsl::created_by.eq(creator_id))
                 .get_result(&*ctx.conn().await?)?,
+            Tag(tag_id) => total_count_query
+                .filter(
+                    urls::dsl::id.eq_any(
+                        url_tags::table
+                            .filter(url_tags::dsl::tag_id.eq(tag_id))
+                            .select(url_tags::dsl::url_id),
+                    ),
+                )
+                .get_result(&*ctx.conn().await?)?,
+            Dead => total_count_query
+                .filter(urls::dsl::dead_since.is_not_null())
+                .get_result(&*ctx.conn().await?)?,
+            Saved(user_id) => total_count_query
+                .inner_join(url_saves::table)
+                .filter(url_saves::dsl::user_id.eq(user_id))
+                .get_result(&*ctx.conn().await?)?,
+            Domain(domain) => total_count_query
+                .filter(urls::dsl::domain.eq(domain))
+                .get_result(&*ctx.conn().await?)?,
+            Upvoted(user_id) => total_count_query
+                .inner_join(url_upvotes::table)
+                .filter(url_upvotes::dsl::user_id.eq(user_id))
+                .get_result(&*ctx.conn().await?)?,
         };
         let page_count = if total_count % page_size != 0 {
             total_count / page_size + 1
@@ -226,31 +655,48 @@ impl Url {
             total_count / page_size
         };
 
-        let query = urls::table.order_by(urls::dsl::created_at.desc());
+        let query = urls::table
+            .filter(urls::dsl::duplicate_of.is_null())
+            .filter(urls::dsl::held_at.is_null())
+            .filter(urls::dsl::id.ne_all(hidden))
+            .filter(not_muted_domain)
+            .filter(urls::dsl::created_by.ne_all(muted_users))
+            .order_by(urls::dsl::created_at.desc());
         let page = match order {
             Ranked => {
-                let count_vote_after = ctx.now() - Duration::days(INCLUDE_DAYS_IN_RANKED);
-                let join_on_recent = url_upvotes::dsl::url_id
-                    .eq(urls::dsl::id)
-                    .and(url_upvotes::dsl::created_at.ge(count_vote_after.naive_utc()));
-                query
-                    .left_outer_join(url_upvotes::table.on(join_on_recent))
-                    .group_by(urls::all_columns)
-                    .order_by(diesel::dsl::count(urls::dsl::id).desc())
-                    .then_order_by(url_upvotes::dsl::created_at.is_null().asc()) // order 1 higher than none
-                    .then_order_by(urls::dsl::created_at.desc())
-                    .select(urls::all_columns)
-                    .offset(page * page_size)
-                    .limit(page_size)
-                    .load(&*ctx.conn().await?)?
+                use diesel::sql_types::{BigInt, Nullable, Text, Timestamp};
+                let dead_filter = if hide_dead {
+                    "AND urls.dead_since IS NULL"
+                } else {
+                    ""
+                };
+                // see `db::ranking` for the definition of `rank_score`
+                diesel::sql_query(format!(
+                    "SELECT urls.* FROM urls
+                    WHERE urls.duplicate_of IS NULL AND urls.held_at IS NULL {}
+                    AND urls.id NOT IN (SELECT url_id FROM url_hides WHERE user_id = ?)
+                    AND {}
+                    AND urls.created_by NOT IN (SELECT muted_id FROM user_mutes WHERE user_id = ?)
+                    ORDER BY rank_score(
+                        urls.upvote_count,
+                        urls.comment_count,
+                        urls.created_at,
+                        ?
+                    ) DESC, urls.created_at DESC
+                    LIMIT ? OFFSET ?",
+                    dead_filter, NOT_MUTED_DOMAIN
+                ))
+                .bind::<Nullable<Text>, _>(viewer)
+                .bind::<Nullable<Text>, _>(viewer)
+                .bind::<Nullable<Text>, _>(viewer)
+                .bind::<Timestamp, _>(ctx.now().naive_utc())
+                .bind::<BigInt, _>(page_size)
+                .bind::<BigInt, _>(page * page_size)
+                .load(&*ctx.conn().await?)?
             }
             Best => query
-                .left_outer_join(url_upvotes::table)
-                .group_by(urls::all_columns)
-                .order_by(diesel::dsl::count(urls::dsl::id).desc())
-                .then_order_by(url_upvotes::dsl::created_at.is_null().asc()) // order 1 higher than none
+                .order_by(urls::dsl::upvote_count.desc())
                 .then_order_by(urls::dsl::created_at.desc())
-                .select(urls::all_columns)
                 .offset(page * page_size)
                 .limit(page_size)
                 .load(&*ctx.conn().await?)?,
@@ -263,11 +709,93 @@ impl Url {
                 .offset(page * page_size)
                 .limit(page_size)
                 .load(&*ctx.conn().await?)?,
+            Tag(tag_id) => query
+                .filter(
+                    urls::dsl::id.eq_any(
+                        url_tags::table
+                            .filter(url_tags::dsl::tag_id.eq(tag_id))
+                            .select(url_tags::dsl::url_id),
+                    ),
+                )
+                .offset(page * page_size)
+                .limit(page_size)
+                .load(&*ctx.conn().await?)?,
+            Dead => query
+                .filter(urls::dsl::dead_since.is_not_null())
+                .order_by(urls::dsl::dead_since.asc())
+                .offset(page * page_size)
+                .limit(page_size)
+                .load(&*ctx.conn().await?)?,
+            Saved(user_id) => query
+                .inner_join(url_saves::table)
+                .filter(url_saves::dsl::user_id.eq(user_id))
+                .order_by(url_saves::dsl::created_at.desc())
+                .then_order_by(url_saves::dsl::url_id.desc())
+                .select(urls::all_columns)
+                .offset(page * page_size)
+                .limit(page_size)
+                .load(&*ctx.conn().await?)?,
+            Domain(domain) => query
+                .filter(urls::dsl::domain.eq(domain))
+                .offset(page * page_size)
+                .limit(page_size)
+                .load(&*ctx.conn().await?)?,
+            Upvoted(user_id) => query
+                .inner_join(url_upvotes::table)
+                .filter(url_upvotes::dsl::user_id.eq(user_id))
+                .order_by(url_upvotes::dsl::created_at.desc())
+                .select(urls::all_columns)
+                .offset(page * page_size)
+                .limit(page_size)
+                .load(&*ctx.conn().await?)?,
         };
 
         Ok((page, page_count))
     }
 
+    /// Aggregate statistics of the submissions from the given
+    /// domain. Duplicate and held submissions are not counted.
+    pub async fn domain_stats(ctx: &Context, domain: &str) -> Result<DomainStats> {
+        let conn = ctx.conn().await?;
+        let listed = urls::table
+            .filter(urls::dsl::duplicate_of.is_null())
+            .filter(urls::dsl::held_at.is_null())
+            .filter(urls::dsl::domain.eq(domain));
+        let submissions: i64 = listed
+            .clone()
+            .select(diesel::dsl::count_star())
+            .get_result(&*conn)?;
+        let dead: i64 = listed
+            .filter(urls::dsl::dead_since.is_not_null())
+            .select(diesel::dsl::count_star())
+            .get_result(&*conn)?;
+        let upvotes: i64 = url_upvotes::table
+            .inner_join(urls::table)
+            .filter(urls::dsl::duplicate_of.is_null())
+            .filter(urls::dsl::held_at.is_null())
+            .filter(urls::dsl::domain.eq(domain))
+            .select(diesel::dsl::count_star())
+            .get_result(&*conn)?;
+        Ok(DomainStats {
+            submissions,
+            upvotes,
+            dead,
+        })
+    }
+
+    /// Returns URLs which still need to be crawled, oldest
+    /// submissions first.
+    pub async fn pending(ctx: &Context) -> Result<Vec<Self>> {
+        let urls = urls::table
+            .filter(urls::dsl::crawl_state.eq(CrawlState::Pending))
+            .filter(urls::dsl::held_at.is_null())
+            .filter(urls::dsl::text.is_null())
+            .order_by(urls::dsl::created_at.asc())
+            .limit(MAX_URLS_PER_CRAWL)
+            .load(&*ctx.conn().await?)?;
+        Ok(urls)
+    }
+
     /// Returns a list of URLs in reverse chronological order, in
     /// a way that's suitable for use with a Relay connection.
     pub async fn all_submissions(
@@ -279,6 +807,8 @@ impl Url {
         let conn = ctx.conn().await?;
 
         let mut query = urls::table
+            .filter(urls::dsl::duplicate_of.is_null())
+            .filter(urls::dsl::held_at.is_null())
             .order_by(urls::dsl::created_at.desc())
             .into_boxed();
 
@@ -298,176 +828,1067 @@ impl Url {
 
         Ok(query.load(&*conn)?)
     }
+
+    /// Returns submissions which are held for moderation, oldest
+    /// first, in a way that's suitable for use with a Relay
+    /// connection. Only moderators can see held submissions.
+    pub async fn held(
+        ctx: &Context,
+        after: Option<UrlID>,
+        before: Option<UrlID>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Self>> {
+        ctx.user()
+            .await?
+            .check_permissions(ctx, |perm| perm.approve_held_urls())
+            .await?;
+        let conn = ctx.conn().await?;
+
+        let mut query = urls::table
+            .filter(urls::dsl::held_at.is_not_null())
+            .order_by(urls::dsl::held_at.asc())
+            .into_boxed();
+
+        if let Some(after) = after {
+            let after: Url = urls::table.find(after).get_result(&*conn)?;
+            query = query.filter(urls::dsl::held_at.gt(after.held_at));
+        }
+
+        if let Some(before) = before {
+            let before: Url = urls::table.find(before).get_result(&*conn)?;
+            query = query.filter(urls::dsl::held_at.lt(before.held_at));
+        }
+
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+
+        Ok(query.load(&*conn)?)
+    }
 }
 
 impl Url {
-    /// Normalizes a given Uri by removing known query
-    /// parameters (e.g. those used for tracking).
-    fn canonicalize(uri_str: &str) -> Result<Uri> {
-        let uri = Uri::from_str(uri_str)?;
-        let builder = Uri::builder()
-            .scheme(uri.scheme().cloned().unwrap_or(Scheme::HTTPS))
-            .authority(
-                uri.authority()
-                    .ok_or_else(|| anyhow!("Malformed URL"))?
-                    .clone(),
-            );
+    /// Normalizes a given URL according to the configured
+    /// canonicalization rules, e.g. by removing parameters
+    /// used for tracking. See [`canonical`](crate::canonical).
+    fn canonicalize(uri_str: &str) -> Result<String> {
+        canonical::rules().canonicalize(uri_str)
+    }
 
-        let path_and_query = if let Some(raw) = uri.query() {
-            let query = form_urlencoded::parse(raw.as_bytes())
-                .filter(
-                    |(name, _value)| match (uri.host().unwrap_or(""), name.as_ref()) {
-                        // discard tracking parameters
-                        (_, "utm_source" | "utm_medium" | "utm_campaign") => false,
-                        (_, "utm_term" | "utm_content") => false,
-                        // discard youtube time stamps
-                        ("youtu.be" | "www.youtube.com", "t") => false,
-                        // discard twitter share method tracking
-                        ("twitter.com", "s") => false,
-                        // keep everything else
-                        (_, _) => true,
-                    },
-                )
-                .fold(
-                    Serializer::new(String::new()),
-                    |mut builder, (name, value)| {
-                        if value.is_empty() {
-                            builder.append_key_only(name.as_ref());
-                        } else {
-                            builder.append_pair(name.as_ref(), value.as_ref());
-                        }
-                        builder
-                    },
-                )
-                .finish();
-            if query.is_empty() {
-                uri.path().to_string()
-            } else {
-                format!("{}?{}", uri.path(), query)
-            }
-        } else {
-            uri.path().to_string()
-        };
+    /// Canonicalizes the given URL, keeping it as is if it
+    /// can not be parsed.
+    fn canonicalize_or_keep(uri_str: &str) -> String {
+        Self::canonicalize(uri_str).unwrap_or_else(|_| uri_str.to_string())
+    }
 
-        Ok(builder.path_and_query(path_and_query).build()?)
+    /// Finds an existing submission of the given URL. The URL is
+    /// canonicalized first, and this also finds submissions which
+    /// redirected to the URL or declared it as their canonical
+    /// location.
+    pub async fn find_submitted(ctx: &Context, url: &str) -> Result<Option<Self>> {
+        let url = Self::canonicalize_or_keep(url);
+        let existing = urls::table
+            .filter(
+                urls::dsl::url
+                    .eq(&url)
+                    .or(urls::dsl::final_url.eq(&url))
+                    .or(urls::dsl::canonical_url.eq(&url)),
+            )
+            .order_by(urls::dsl::created_at.asc())
+            .first(&*ctx.conn().await?)
+            .optional()?;
+        Ok(existing)
     }
 
-    /// Creates a new role for the given user and assigns the given
-    /// permission.
+    /// Submits a new URL. The URL is stored in a pending state and
+    /// the linked page is fetched later by a background job, see
+    /// [`crawl`](crawl).
     pub async fn create(ctx: &Context, input: NewUrlInput, created_by: UserID) -> Result<Self> {
         input.validate()?;
-        let NewUrlInput { url } = input;
-        let url = Self::canonicalize(&url)?.to_string();
+        let NewUrlInput { url, tags } = input;
+        let url = Self::canonicalize(&url)?;
+        let domain = canonical::domain(&url)?;
+        let tags = Tag::normalize_all(&tags.unwrap_or_default())?;
+        let quota = QuotaAction::Submission.quota(ctx, created_by).await?;
+
+        let held_at = match DomainRule::matching(ctx, &domain).await? {
+            Some(rule) => match rule.action() {
+                DomainAction::Block => {
+                    return Err(anyhow!("Submissions from {} are not allowed", domain))
+                }
+                DomainAction::RequireModeration => Some(ctx.now().naive_utc()),
+                DomainAction::Allow => None,
+            },
+            None => None,
+        };
 
         // verify URL is unique, to avoid an additional query
-        let exists: i64 = urls::table
-            .filter(urls::dsl::url.eq(&url))
-            .select(diesel::dsl::count_star())
-            .get_result(&*ctx.conn().await?)?;
-        if exists > 0 {
+        if Self::find_submitted(ctx, &url).await?.is_some() {
             return Err(anyhow!("The url was already submitted"));
         }
 
-        let resp = ctx.http_client().get(&url).send().await?;
-        let status = resp.status();
-        if !status.is_success() {
-            return Err(anyhow!("Failed to load url with status {}", status));
-        }
+        let url = Url {
+            id: UrlID::new(),
+            created_at: ctx.now().naive_utc(),
+            updated_at: ctx.now().naive_utc(),
 
-        let mut meta = Meta::new();
-        let mut stream = resp.bytes_stream();
-        while let Some(part) = stream.next().await {
-            meta.parse(&part?);
-        }
+            url,
+            status_code: STATUS_NOT_FETCHED,
+            title: None,
+            description: None,
+            image: None,
+            created_by,
+            crawl_state: CrawlState::Pending,
+            final_url: None,
+            canonical_url: None,
+            duplicate_of: None,
+            archived_at: None,
+            dead_since: None,
+            thumbnail_at: None,
+            domain,
+            held_at,
+            text: None,
+            upvote_count: 0,
+            comment_count: 0,
+            title_override: None,
+            description_override: None,
+        };
 
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            quota.consume(ctx, &*conn)?;
+            diesel::insert_into(urls::table)
+                .values(&url)
+                .execute(&*conn)?;
+            Tag::replace_for_url(ctx, &*conn, url.id, &tags)?;
+            Ok(())
+        })?;
+
+        Ok(url)
+    }
+
+    /// Submits a new text post, i.e. a discussion which is not
+    /// tied to an external page. Text posts link to their own
+    /// comments page, and are listed like any other submission,
+    /// under the domain of this server.
+    pub async fn create_text_post(
+        ctx: &Context,
+        mut input: NewTextPostInput,
+        created_by: UserID,
+    ) -> Result<Self> {
+        input.title = input.title.trim().into();
+        input.text = input.text.trim().into();
+        input.validate()?;
+        let NewTextPostInput { title, text, tags } = input;
+        let tags = Tag::normalize_all(&tags.unwrap_or_default())?;
+        let quota = QuotaAction::Submission.quota(ctx, created_by).await?;
+
+        let id = UrlID::new();
+        let url = Self::text_post_url(ctx.base_url(), id);
+        let domain = canonical::domain(&url)?;
         let url = Url {
-            id: UrlID::new(),
+            id,
             created_at: ctx.now().naive_utc(),
             updated_at: ctx.now().naive_utc(),
 
             url,
-            status_code: status.as_u16().into(),
-            title: meta.title,
-            description: meta.description,
-            image: meta.image,
+            status_code: STATUS_NOT_FETCHED,
+            title: Some(title),
+            description: None,
+            image: None,
             created_by,
+            crawl_state: CrawlState::Crawled,
+            final_url: None,
+            canonical_url: None,
+            duplicate_of: None,
+            archived_at: None,
+            dead_since: None,
+            thumbnail_at: None,
+            domain,
+            held_at: None,
+            text: Some(text),
+            upvote_count: 0,
+            comment_count: 0,
+            title_override: None,
+            description_override: None,
         };
 
-        diesel::insert_into(urls::table)
-            .values(&url)
-            .execute(&*ctx.conn().await?)?;
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            quota.consume(ctx, &*conn)?;
+            diesel::insert_into(urls::table)
+                .values(&url)
+                .execute(&*conn)?;
+            Tag::replace_for_url(ctx, &*conn, url.id, &tags)?;
+            Ok(())
+        })?;
 
         Ok(url)
     }
 
+    /// The URL of the comments page of a text post, which is
+    /// what text posts link to.
+    fn text_post_url(base_url: &str, id: UrlID) -> String {
+        format!("{}/comments/{}", base_url, id)
+    }
+
+    /// Approves a submission which was held for moderation. The
+    /// submission is listed and crawled afterwards. Only moderators
+    /// can approve submissions, and every approval is recorded in
+    /// the audit log.
+    pub async fn approve(&mut self, ctx: &Context) -> Result<()> {
+        ctx.user()
+            .await?
+            .check_permissions(ctx, |perm| perm.approve_held_urls())
+            .await?;
+        if !self.is_held() {
+            return Err(anyhow!("The submission is not held for moderation"));
+        }
+        let snapshot = json!({
+            "url": self.url,
+            "title": self.title,
+            "created_by": self.created_by.to_string(),
+            "created_at": self.created_at().to_rfc3339(),
+            "held_at": self.held_at().map(|held_at| held_at.to_rfc3339()),
+        });
+        self.held_at = None;
+        self.updated_at = ctx.now().naive_utc();
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            diesel::update(&*self)
+                .set((
+                    urls::dsl::held_at.eq(None::<NaiveDateTime>),
+                    urls::dsl::updated_at.eq(self.updated_at),
+                ))
+                .execute(&*conn)?;
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::ApproveUrl,
+                &self.id.to_string(),
+                None,
+                snapshot,
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Overrides the title and description of this URL. Fields which
+    /// are not given are kept, and empty values restore the crawled
+    /// values. Only moderators or the user who submitted the URL can
+    /// edit it. Every edit is kept, see [`edits`](Url::edits).
+    pub async fn update(&mut self, ctx: &Context, input: UpdateUrlInput) -> Result<()> {
+        if self.created_by != ctx.user_id()? {
+            ctx.user()
+                .await?
+                .check_permissions(ctx, |perm| perm.edit_any_url())
+                .await?;
+        }
+        let input = UpdateUrlInput {
+            title: input.title.map(|title| title.trim().into()),
+            description: input.description.map(|text| text.trim().into()),
+        };
+        input.validate()?;
+
+        let non_empty = |value: String| if value.is_empty() { None } else { Some(value) };
+        let title_override = match input.title {
+            Some(title) => non_empty(title),
+            None => self.title_override.clone(),
+        };
+        let description_override = match input.description {
+            Some(description) => non_empty(description),
+            None => self.description_override.clone(),
+        };
+        // nothing changed, don't record an edit
+        if title_override == self.title_override
+            && description_override == self.description_override
+        {
+            return Ok(());
+        }
+        self.title_override = title_override;
+        self.description_override = description_override;
+        self.updated_at = ctx.now().naive_utc();
+
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            diesel::update(urls::table.find(self.id))
+                .set((
+                    urls::dsl::updated_at.eq(self.updated_at),
+                    urls::dsl::title_override.eq(self.title_override.as_deref()),
+                    urls::dsl::description_override.eq(self.description_override.as_deref()),
+                ))
+                .execute(&*conn)?;
+            UrlEdit::record(
+                ctx,
+                &*conn,
+                self.id,
+                self.title_override.as_deref(),
+                self.description_override.as_deref(),
+            )
+        })?;
+        ctx.loaders().forget_url(self.id);
+        Ok(())
+    }
+
+    /// Replaces the tags of this URL. Tags can only be changed
+    /// by moderators or the user who submitted the URL.
+    pub async fn set_tags(&self, ctx: &Context, tags: &[String]) -> Result<()> {
+        if self.created_by != ctx.user_id()? {
+            ctx.user()
+                .await?
+                .check_permissions(ctx, |perm| perm.retag_any_url())
+                .await?;
+        }
+        let tags = Tag::normalize_all(tags)?;
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            Tag::replace_for_url(ctx, &*conn, self.id, &tags)
+        })?;
+        ctx.loaders().forget_url(self.id);
+        Ok(())
+    }
+
+    /// Writes this URL to the database, and reloads it. The cached
+    /// counters are never written, see [`UrlChanges`]. Like with
+    /// `save_changes`, `None` fields are skipped.
+    fn store(&mut self, conn: &SqliteConnection) -> Result<()> {
+        let changes = UrlChanges {
+            updated_at: self.updated_at,
+            url: &self.url,
+            status_code: self.status_code,
+            title: self.title.as_deref(),
+            description: self.description.as_deref(),
+            image: self.image.as_deref(),
+            crawl_state: self.crawl_state,
+            final_url: self.final_url.as_deref(),
+            canonical_url: self.canonical_url.as_deref(),
+            duplicate_of: self.duplicate_of,
+            archived_at: self.archived_at,
+            dead_since: self.dead_since,
+            thumbnail_at: self.thumbnail_at,
+            domain: &self.domain,
+            held_at: self.held_at,
+            text: self.text.as_deref(),
+        };
+        diesel::update(urls::table.find(self.id))
+            .set(&changes)
+            .execute(conn)?;
+        *self = urls::table.find(self.id).get_result(conn)?;
+        Ok(())
+    }
+
+    /// Recomputes the cached counters of the given URL from
+    /// its upvotes and comments. Discounted upvotes are not
+    /// counted.
+    pub(super) fn recount(conn: &SqliteConnection, url_id: UrlID) -> Result<()> {
+        let upvotes: i64 = url_upvotes::table
+            .filter(url_upvotes::dsl::url_id.eq(url_id))
+            .filter(url_upvotes::dsl::discounted_at.is_null())
+            .select(diesel::dsl::count_star())
+            .get_result(conn)?;
+        let comments: i64 = comments::table
+            .filter(comments::dsl::url_id.eq(url_id))
+            .select(diesel::dsl::count_star())
+            .get_result(conn)?;
+        diesel::update(urls::table.find(url_id))
+            .set((
+                urls::dsl::upvote_count.eq(upvotes),
+                urls::dsl::comment_count.eq(comments),
+            ))
+            .execute(conn)?;
+        Ok(())
+    }
+
+    /// Repairs cached counters which don't match the upvotes and
+    /// comments of their URL, and returns the number of repaired
+    /// URLs. The counters are updated together with upvotes and
+    /// comments, so this should usually not find anything.
+    pub async fn check_counts(ctx: &Context) -> Result<usize> {
+        let repaired = diesel::sql_query(
+            "UPDATE urls SET
+                upvote_count = (
+                    SELECT COUNT(*) FROM url_upvotes
+                    WHERE url_upvotes.url_id = urls.id AND url_upvotes.discounted_at IS NULL
+                ),
+                comment_count = (SELECT COUNT(*) FROM comments WHERE comments.url_id = urls.id)
+            WHERE
+                upvote_count != (
+                    SELECT COUNT(*) FROM url_upvotes
+                    WHERE url_upvotes.url_id = urls.id AND url_upvotes.discounted_at IS NULL
+                )
+                OR comment_count != (SELECT COUNT(*) FROM comments WHERE comments.url_id = urls.id)",
+        )
+        .execute(&*ctx.conn().await?)?;
+        Ok(repaired)
+    }
+
     /// Fetch the current contents of the URL and
-    /// update the meta information and status code.
+    /// update the meta information and status code. The
+    /// first page fetched successfully is saved to the
+    /// archive.
     pub async fn update_url_meta(&mut self, ctx: &Context) -> Result<()> {
-        let resp = ctx.http_client().get(self.url.as_str()).send().await?;
-        let status = resp.status();
+        if self.is_text_post() {
+            return Err(anyhow!("Text posts do not link to a page"));
+        }
+        let page = ctx.fetcher().get(self.url.as_str()).await?;
+        let status = page.status();
         self.status_code = status.as_u16().into();
         self.updated_at = ctx.now().naive_utc();
 
+        self.final_url = Some(Self::canonicalize_or_keep(page.url().as_str()));
+        let chain: Vec<(String, StatusCode)> = page
+            .redirects()
+            .iter()
+            .map(|(location, status)| (location.to_string(), *status))
+            .collect();
+        Redirect::replace_for_url(ctx, self.id, &chain).await?;
+
         if status.is_success() {
             let mut meta = Meta::new();
-            let mut stream = resp.bytes_stream();
-            while let Some(part) = stream.next().await {
-                meta.parse(&part?);
+            // feed the parser in chunks, as it would be when streaming
+            for chunk in page.html().unwrap_or_default().chunks(META_CHUNK_SIZE) {
+                meta.parse(chunk);
             }
             self.title = meta.title.or_else(|| self.title.clone());
             self.description = meta.description.or_else(|| self.description.clone());
+            let image_changed = meta.image.is_some() && meta.image != self.image;
             self.image = meta.image.or_else(|| self.image.clone());
+            if image_changed || self.thumbnail_at.is_none() {
+                if let Some(image) = self.image.as_ref() {
+                    // the page is still usable without an image
+                    match self.update_thumbnail(ctx, page.url(), image).await {
+                        Ok(()) => self.thumbnail_at = Some(ctx.now().naive_utc()),
+                        Err(err) => log::warn!("Failed to create thumbnail: {}", err),
+                    }
+                }
+            }
+            if let (Some(html), None) = (page.html(), self.archived_at) {
+                ctx.archive().store(self.id, html).await?;
+                self.archived_at = Some(ctx.now().naive_utc());
+            }
+            self.canonical_url = meta
+                .canonical
+                .and_then(|href| Self::resolve_canonical(page.url(), &href));
+            self.crawl_state = CrawlState::Crawled;
+        } else {
+            self.crawl_state = CrawlState::Failed;
+        }
+
+        self.store(&*ctx.conn().await?)?;
+        Ok(())
+    }
+
+    /// Resolves the canonical location a page declares. Only locations
+    /// with the same registrable domain as the page are kept (such that
+    /// e.g. `m.example.com` can point to `example.com`), otherwise any
+    /// page could claim to be the canonical version of another site's
+    /// page and thereby prevent it from being submitted.
+    fn resolve_canonical(page_url: &reqwest::Url, href: &str) -> Option<String> {
+        let canonical = Self::canonicalize_or_keep(page_url.join(href).ok()?.as_str());
+        let page_domain = canonical::registrable_domain(page_url.as_str()).ok()?;
+        if canonical::registrable_domain(&canonical).ok()? == page_domain {
+            Some(canonical)
+        } else {
+            None
+        }
+    }
+
+    /// Records the outcome of periodically checking if the URL
+    /// can still be reached, where `None` means there was no
+    /// response. After the configured number of consecutive failed
+    /// checks the URL is considered dead, until a check succeeds.
+    pub async fn record_check(&mut self, ctx: &Context, status: Option<StatusCode>) -> Result<()> {
+        let check = UrlCheck::insert(ctx, self.id, status).await?;
+        self.updated_at = check.checked_at().naive_utc();
+        if status.is_none() {
+            self.crawl_state = CrawlState::Failed;
+        }
+
+        if check.is_success() {
+            // `store` skips `None` fields
+            diesel::update(urls::table.find(self.id))
+                .set(urls::dsl::dead_since.eq(None::<NaiveDateTime>))
+                .execute(&*ctx.conn().await?)?;
+            self.dead_since = None;
+        } else if self.dead_since.is_none() {
+            let failures = ctx.link_health().dead_after_failures();
+            let recent = UrlCheck::for_url(ctx, self.id, failures).await?;
+            if recent.len() as i64 >= failures && !recent.iter().any(UrlCheck::is_success) {
+                self.dead_since = recent.last().map(|check| check.checked_at().naive_utc());
+            }
+        }
+        self.store(&*ctx.conn().await?)?;
+        Ok(())
+    }
+
+    /// Download the given image and store a thumbnail of it. Relative
+    /// image URLs are resolved against the URL of the page.
+    async fn update_thumbnail(
+        &self,
+        ctx: &Context,
+        page_url: &reqwest::Url,
+        image: &str,
+    ) -> Result<()> {
+        let image_url = page_url.join(image)?;
+        let image = ctx.fetcher().get_image(image_url.as_str()).await?;
+        ctx.thumbnails().store(self.id, image).await
+    }
+
+    /// Fetch a newly submitted URL for the first time. If the
+    /// page can not be reached, the URL is marked as failed. If
+    /// the page turns out to be an earlier submission, e.g. because
+    /// the URL redirects there or names it as its canonical URL, the
+    /// URL is marked as a duplicate and no longer listed.
+    pub async fn crawl(&mut self, ctx: &Context) -> Result<()> {
+        if let Err(err) = self.update_url_meta(ctx).await {
+            self.crawl_state = CrawlState::Failed;
+            self.updated_at = ctx.now().naive_utc();
+            self.store(&*ctx.conn().await?)?;
+            return Err(err);
         }
 
-        *self = self.save_changes(&*ctx.conn().await?)?;
+        let resolved: Vec<String> = self
+            .final_url
+            .iter()
+            .chain(self.canonical_url.iter())
+            .cloned()
+            .collect();
+        if resolved.is_empty() {
+            return Ok(());
+        }
+        let conn = ctx.conn().await?;
+        let original: Option<UrlID> = urls::table
+            .filter(urls::dsl::id.ne(self.id))
+            .filter(urls::dsl::duplicate_of.is_null())
+            .filter(urls::dsl::created_at.lt(self.created_at))
+            .filter(
+                urls::dsl::url
+                    .eq_any(resolved.clone())
+                    .or(urls::dsl::final_url.eq_any(resolved.clone()))
+                    .or(urls::dsl::canonical_url.eq_any(resolved)),
+            )
+            .order_by(urls::dsl::created_at.asc())
+            .select(urls::dsl::id)
+            .first(&*conn)
+            .optional()?;
+        if original.is_some() {
+            self.duplicate_of = original;
+            self.store(&*conn)?;
+        }
         Ok(())
     }
 
     /// Deletes the given URL from the database. URLs can only be deleted
-    /// by moderators or the user who created them.
-    pub async fn delete(&self, ctx: &Context) -> Result<()> {
+    /// by moderators or the user who created them. The deletion is
+    /// recorded in the audit log, together with the optional reason.
+    pub async fn delete(&self, ctx: &Context, reason: Option<&str>) -> Result<()> {
         if self.created_by != ctx.user_id()? {
             ctx.user()
                 .await?
                 .check_permissions(ctx, |perm| perm.delete_any_url())
                 .await?;
         }
+        let snapshot = json!({
+            "url": self.url,
+            "title": self.title,
+            "description": self.description,
+            "text": self.text,
+            "created_by": self.created_by.to_string(),
+            "created_at": self.created_at().to_rfc3339(),
+        });
         let conn = ctx.conn().await?;
-        let upvotes = url_upvotes::table.filter(url_upvotes::dsl::url_id.eq(self.id));
-        let comments = comments::table.filter(comments::dsl::url_id.eq(self.id));
-        diesel::delete(upvotes).execute(&*conn)?;
-        diesel::delete(comments).execute(&*conn)?;
-        diesel::delete(self).execute(&*conn)?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            Flag::delete_for_url(&*conn, self.id)?;
+            let upvotes = url_upvotes::table.filter(url_upvotes::dsl::url_id.eq(self.id));
+            let saves = url_saves::table.filter(url_saves::dsl::url_id.eq(self.id));
+            let hides = url_hides::table.filter(url_hides::dsl::url_id.eq(self.id));
+            let tags = url_tags::table.filter(url_tags::dsl::url_id.eq(self.id));
+            let redirects = url_redirects::table.filter(url_redirects::dsl::url_id.eq(self.id));
+            let checks = url_checks::table.filter(url_checks::dsl::url_id.eq(self.id));
+            let edits = url_edits::table.filter(url_edits::dsl::url_id.eq(self.id));
+            let comments = comments::table.filter(comments::dsl::url_id.eq(self.id));
+            diesel::delete(upvotes).execute(&*conn)?;
+            diesel::delete(saves).execute(&*conn)?;
+            diesel::delete(hides).execute(&*conn)?;
+            diesel::delete(tags).execute(&*conn)?;
+            diesel::delete(redirects).execute(&*conn)?;
+            diesel::delete(checks).execute(&*conn)?;
+            diesel::delete(edits).execute(&*conn)?;
+            diesel::update(urls::table.filter(urls::dsl::duplicate_of.eq(self.id)))
+                .set(urls::dsl::duplicate_of.eq(None::<UrlID>))
+                .execute(&*conn)?;
+            diesel::delete(url_merges::table.filter(url_merges::dsl::into_id.eq(self.id)))
+                .execute(&*conn)?;
+            diesel::delete(comments).execute(&*conn)?;
+            diesel::delete(self).execute(&*conn)?;
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::DeleteUrl,
+                &self.id.to_string(),
+                reason,
+                snapshot,
+            )?;
+            Ok(())
+        })?;
+        drop(conn);
         ctx.search().delete_url(self)?;
+        ctx.archive().delete(self.id).await?;
+        ctx.thumbnails().delete(self.id).await?;
         Ok(())
     }
 
+    /// Merges this duplicate submission into the submission `into`.
+    /// Upvotes, saves, hides, tags, comments, and flags are moved over, and
+    /// this submission is deleted. Links to its comments page redirect
+    /// to `into` afterwards, see [`merged_into`](Url::merged_into). Only
+    /// moderators can merge submissions. Submissions can not be merged
+    /// into a submission which is held for moderation, or which is itself
+    /// a duplicate. The merge is recorded in the audit log, together with
+    /// the optional reason.
+    pub async fn merge_into(&self, ctx: &Context, into: &Url, reason: Option<&str>) -> Result<()> {
+        let merged_by = ctx.user_id()?;
+        ctx.user()
+            .await?
+            .check_permissions(ctx, |perm| perm.merge_urls())
+            .await?;
+        if self.id == into.id {
+            return Err(anyhow!("A submission can not be merged into itself"));
+        }
+
+        let conn = ctx.conn().await?;
+        let mut unindexed = false;
+        let merged = conn.transaction::<_, anyhow::Error, _>(|| {
+            // `into` is checked within the transaction, such that it
+            // can not be merged or held concurrently
+            let (held_at, duplicate_of): (Option<NaiveDateTime>, Option<UrlID>) = urls::table
+                .find(into.id)
+                .select((urls::dsl::held_at, urls::dsl::duplicate_of))
+                .first(&*conn)
+                .optional()?
+                .ok_or_else(|| anyhow!("Submission not found"))?;
+            if held_at.is_some() {
+                return Err(anyhow!(
+                    "Can not merge into a submission held for moderation"
+                ));
+            }
+            if duplicate_of.map_or(false, |id| id != self.id) {
+                return Err(anyhow!("Can not merge into a duplicate submission"));
+            }
+
+            // users who upvoted or saved both submissions keep their
+            // existing upvote or save
+            let upvotes: Vec<(UserID, NaiveDateTime, Option<NaiveDateTime>)> = url_upvotes::table
+                .filter(url_upvotes::dsl::url_id.eq(self.id))
+                .select((
+                    url_upvotes::dsl::user_id,
+                    url_upvotes::dsl::created_at,
+                    url_upvotes::dsl::discounted_at,
+                ))
+                .load(&*conn)?;
+            for (user_id, created_at, discounted_at) in upvotes {
+                diesel::insert_or_ignore_into(url_upvotes::table)
+                    .values((
+                        url_upvotes::dsl::url_id.eq(into.id),
+                        url_upvotes::dsl::user_id.eq(user_id),
+                        url_upvotes::dsl::created_at.eq(created_at),
+                        url_upvotes::dsl::discounted_at.eq(discounted_at),
+                    ))
+                    .execute(&*conn)?;
+            }
+            let saves: Vec<(UserID, NaiveDateTime)> = url_saves::table
+                .filter(url_saves::dsl::url_id.eq(self.id))
+                .select((url_saves::dsl::user_id, url_saves::dsl::created_at))
+                .load(&*conn)?;
+            for (user_id, created_at) in saves {
+                diesel::insert_or_ignore_into(url_saves::table)
+                    .values((
+                        url_saves::dsl::url_id.eq(into.id),
+                        url_saves::dsl::user_id.eq(user_id),
+                        url_saves::dsl::created_at.eq(created_at),
+                    ))
+                    .execute(&*conn)?;
+            }
+            let hides: Vec<(UserID, NaiveDateTime)> = url_hides::table
+                .filter(url_hides::dsl::url_id.eq(self.id))
+                .select((url_hides::dsl::user_id, url_hides::dsl::created_at))
+                .load(&*conn)?;
+            for (user_id, created_at) in hides {
+                diesel::insert_or_ignore_into(url_hides::table)
+                    .values((
+                        url_hides::dsl::url_id.eq(into.id),
+                        url_hides::dsl::user_id.eq(user_id),
+                        url_hides::dsl::created_at.eq(created_at),
+                    ))
+                    .execute(&*conn)?;
+            }
+            let tags: Vec<(TagID, NaiveDateTime)> = url_tags::table
+                .filter(url_tags::dsl::url_id.eq(self.id))
+                .select((url_tags::dsl::tag_id, url_tags::dsl::created_at))
+                .load(&*conn)?;
+            for (tag_id, created_at) in tags {
+                diesel::insert_or_ignore_into(url_tags::table)
+                    .values((
+                        url_tags::dsl::url_id.eq(into.id),
+                        url_tags::dsl::tag_id.eq(tag_id),
+                        url_tags::dsl::created_at.eq(created_at),
+                    ))
+                    .execute(&*conn)?;
+            }
+
+            diesel::update(comments::table.filter(comments::dsl::url_id.eq(self.id)))
+                .set(comments::dsl::url_id.eq(into.id))
+                .execute(&*conn)?;
+            let flagged_both = flags::table
+                .filter(flags::dsl::url_id.eq(into.id))
+                .select(flags::dsl::created_by);
+            let duplicate_flags = flags::table
+                .filter(flags::dsl::url_id.eq(self.id))
+                .filter(flags::dsl::created_by.eq_any(flagged_both));
+            diesel::delete(duplicate_flags).execute(&*conn)?;
+            diesel::update(flags::table.filter(flags::dsl::url_id.eq(self.id)))
+                .set(flags::dsl::url_id.eq(into.id))
+                .execute(&*conn)?;
+            let duplicates = urls::table
+                .filter(urls::dsl::duplicate_of.eq(self.id))
+                .filter(urls::dsl::id.ne(into.id));
+            diesel::update(duplicates)
+                .set(urls::dsl::duplicate_of.eq(into.id))
+                .execute(&*conn)?;
+            // `into` is the original once this submission is gone
+            diesel::update(
+                urls::table
+                    .find(into.id)
+                    .filter(urls::dsl::duplicate_of.eq(self.id)),
+            )
+            .set(urls::dsl::duplicate_of.eq(None::<UrlID>))
+            .execute(&*conn)?;
+
+            // earlier merges into this submission now redirect to `into`
+            diesel::update(url_merges::table.filter(url_merges::dsl::into_id.eq(self.id)))
+                .set(url_merges::dsl::into_id.eq(into.id))
+                .execute(&*conn)?;
+            diesel::insert_into(url_merges::table)
+                .values((
+                    url_merges::dsl::from_id.eq(self.id),
+                    url_merges::dsl::into_id.eq(into.id),
+                    url_merges::dsl::merged_at.eq(ctx.now().naive_utc()),
+                    url_merges::dsl::merged_by.eq(merged_by),
+                ))
+                .execute(&*conn)?;
+
+            let upvotes = url_upvotes::table.filter(url_upvotes::dsl::url_id.eq(self.id));
+            let saves = url_saves::table.filter(url_saves::dsl::url_id.eq(self.id));
+            let hides = url_hides::table.filter(url_hides::dsl::url_id.eq(self.id));
+            let tags = url_tags::table.filter(url_tags::dsl::url_id.eq(self.id));
+            let redirects = url_redirects::table.filter(url_redirects::dsl::url_id.eq(self.id));
+            let checks = url_checks::table.filter(url_checks::dsl::url_id.eq(self.id));
+            let edits = url_edits::table.filter(url_edits::dsl::url_id.eq(self.id));
+            diesel::delete(upvotes).execute(&*conn)?;
+            diesel::delete(saves).execute(&*conn)?;
+            diesel::delete(hides).execute(&*conn)?;
+            diesel::delete(tags).execute(&*conn)?;
+            diesel::delete(redirects).execute(&*conn)?;
+            diesel::delete(checks).execute(&*conn)?;
+            diesel::delete(edits).execute(&*conn)?;
+            diesel::delete(self).execute(&*conn)?;
+            Self::recount(&*conn, into.id)?;
+
+            let snapshot = json!({
+                "url": self.url,
+                "title": self.title,
+                "into": into.id.to_string(),
+                "created_by": self.created_by.to_string(),
+                "created_at": self.created_at().to_rfc3339(),
+            });
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::MergeUrl,
+                &self.id.to_string(),
+                reason,
+                snapshot,
+            )?;
+
+            // removed from the index last, such that a failure
+            // rolls back the merge
+            ctx.search().delete_url(self)?;
+            unindexed = true;
+            Ok(())
+        });
+        drop(conn);
+        if let Err(err) = merged {
+            if unindexed {
+                ctx.search().index_url(self)?;
+            }
+            return Err(err);
+        }
+        ctx.archive().delete(self.id).await?;
+        ctx.thumbnails().delete(self.id).await?;
+        Ok(())
+    }
+
+    /// If the submission with the given ID was merged into another
+    /// submission, this is the ID of the surviving submission.
+    pub async fn merged_into(ctx: &Context, url_id: UrlID) -> Result<Option<UrlID>> {
+        let into = url_merges::table
+            .find(url_id)
+            .select(url_merges::dsl::into_id)
+            .get_result(&*ctx.conn().await?)
+            .optional()?;
+        Ok(into)
+    }
+
     /// Upvote the URL as the logged in user.
-    pub async fn upvote(&self, ctx: &Context) -> Result<()> {
-        diesel::insert_into(url_upvotes::table)
+    pub async fn upvote(&mut self, ctx: &Context) -> Result<()> {
+        let user_id = ctx.user_id()?;
+        let conn = ctx.conn().await?;
+        self.upvote_count = conn.transaction::<_, anyhow::Error, _>(|| {
+            diesel::insert_into(url_upvotes::table)
+                .values((
+                    url_upvotes::dsl::user_id.eq(user_id),
+                    url_upvotes::dsl::url_id.eq(self.id),
+                    url_upvotes::dsl::created_at.eq(ctx.now().naive_utc()),
+                ))
+                .execute(&*conn)?;
+            diesel::update(urls::table.find(self.id))
+                .set(urls::dsl::upvote_count.eq(urls::dsl::upvote_count + 1))
+                .execute(&*conn)?;
+            Ok(urls::table
+                .find(self.id)
+                .select(urls::dsl::upvote_count)
+                .get_result(&*conn)?)
+        })?;
+        ctx.loaders().forget_url(self.id);
+        Ok(())
+    }
+
+    /// Rescind an upvote for the URL as the logged in user.
+    pub async fn rescind_upvote(&mut self, ctx: &Context) -> Result<()> {
+        let user_id = ctx.user_id()?;
+        let conn = ctx.conn().await?;
+        self.upvote_count = conn.transaction::<_, anyhow::Error, _>(|| {
+            let upvote = url_upvotes::table
+                .filter(url_upvotes::dsl::url_id.eq(self.id))
+                .filter(url_upvotes::dsl::user_id.eq(user_id));
+            // discounted upvotes were already subtracted
+            let counted: i64 = upvote
+                .clone()
+                .filter(url_upvotes::dsl::discounted_at.is_null())
+                .select(diesel::dsl::count_star())
+                .get_result(&*conn)?;
+            diesel::delete(upvote).execute(&*conn)?;
+            diesel::update(urls::table.find(self.id))
+                .set(urls::dsl::upvote_count.eq(urls::dsl::upvote_count - counted))
+                .execute(&*conn)?;
+            Ok(urls::table
+                .find(self.id)
+                .select(urls::dsl::upvote_count)
+                .get_result(&*conn)?)
+        })?;
+        ctx.loaders().forget_url(self.id);
+        Ok(())
+    }
+
+    /// Save the URL to the reading list of the logged in user. Unlike
+    /// upvotes, saves are private and do not affect the ranking.
+    pub async fn save(&self, ctx: &Context) -> Result<()> {
+        diesel::insert_or_ignore_into(url_saves::table)
             .values((
-                url_upvotes::dsl::user_id.eq(ctx.user_id()?),
-                url_upvotes::dsl::url_id.eq(self.id()),
-                url_upvotes::dsl::created_at.eq(ctx.now().naive_utc()),
+                url_saves::dsl::user_id.eq(ctx.user_id()?),
+                url_saves::dsl::url_id.eq(self.id()),
+                url_saves::dsl::created_at.eq(ctx.now().naive_utc()),
             ))
             .execute(&*ctx.conn().await?)?;
+        ctx.loaders().forget_url(self.id);
         Ok(())
     }
 
-    /// Rescind an upvote for the URL as the logged in user.
-    pub async fn rescind_upvote(&self, ctx: &Context) -> Result<()> {
-        let upvote = url_upvotes::table
-            .filter(url_upvotes::dsl::url_id.eq(self.id()))
-            .filter(url_upvotes::dsl::user_id.eq(ctx.user_id()?));
-        diesel::delete(upvote).execute(&*ctx.conn().await?)?;
+    /// Remove the URL from the reading list of the logged in user.
+    pub async fn unsave(&self, ctx: &Context) -> Result<()> {
+        let save = url_saves::table
+            .filter(url_saves::dsl::url_id.eq(self.id()))
+            .filter(url_saves::dsl::user_id.eq(ctx.user_id()?));
+        diesel::delete(save).execute(&*ctx.conn().await?)?;
+        ctx.loaders().forget_url(self.id);
         Ok(())
     }
+
+    /// Hide the URL from the listings of the logged in user.
+    pub async fn hide(&self, ctx: &Context) -> Result<()> {
+        diesel::insert_or_ignore_into(url_hides::table)
+            .values((
+                url_hides::dsl::user_id.eq(ctx.user_id()?),
+                url_hides::dsl::url_id.eq(self.id()),
+                url_hides::dsl::created_at.eq(ctx.now().naive_utc()),
+            ))
+            .execute(&*ctx.conn().await?)?;
+        ctx.loaders().forget_url(self.id);
+        Ok(())
+    }
+
+    /// Show a previously hidden URL to the logged in user again.
+    pub async fn unhide(&self, ctx: &Context) -> Result<()> {
+        let hide = url_hides::table
+            .filter(url_hides::dsl::url_id.eq(self.id()))
+            .filter(url_hides::dsl::user_id.eq(ctx.user_id()?));
+        diesel::delete(hide).execute(&*ctx.conn().await?)?;
+        ctx.loaders().forget_url(self.id);
+        Ok(())
+    }
+
+    /// URLs hidden by the logged in user, the most
+    /// recently hidden first.
+    pub async fn hidden(ctx: &Context) -> Result<Vec<Self>> {
+        let urls = urls::table
+            .inner_join(url_hides::table)
+            .filter(url_hides::dsl::user_id.eq(ctx.user_id()?))
+            .order_by(url_hides::dsl::created_at.desc())
+            .select(urls::all_columns)
+            .load(&*ctx.conn().await?)?;
+        Ok(urls)
+    }
+
+    /// Mute the given domain for the logged in user, such that
+    /// submissions from it, or any of its subdomains, are no
+    /// longer listed. Returns the normalized domain, see
+    /// [`canonical::normalize_domain`].
+    pub async fn mute_domain(ctx: &Context, domain: &str) -> Result<String> {
+        let domain = canonical::normalize_domain(domain);
+        let parsed = canonical::domain(&format!("https://{}/", domain)).ok();
+        if parsed.as_deref() != Some(domain.as_str()) {
+            return Err(anyhow!("Invalid domain"));
+        }
+        diesel::insert_or_ignore_into(domain_mutes::table)
+            .values((
+                domain_mutes::dsl::user_id.eq(ctx.user_id()?),
+                domain_mutes::dsl::domain.eq(&domain),
+                domain_mutes::dsl::created_at.eq(ctx.now().naive_utc()),
+            ))
+            .execute(&*ctx.conn().await?)?;
+        Ok(domain)
+    }
+
+    /// Unmute the given domain for the logged in user.
+    pub async fn unmute_domain(ctx: &Context, domain: &str) -> Result<()> {
+        let mute = domain_mutes::table
+            .filter(domain_mutes::dsl::user_id.eq(ctx.user_id()?))
+            .filter(domain_mutes::dsl::domain.eq(canonical::normalize_domain(domain)));
+        diesel::delete(mute).execute(&*ctx.conn().await?)?;
+        Ok(())
+    }
+
+    /// Domains muted by the logged in user, in
+    /// alphabetical order.
+    pub async fn muted_domains(ctx: &Context) -> Result<Vec<String>> {
+        let domains = domain_mutes::table
+            .filter(domain_mutes::dsl::user_id.eq(ctx.user_id()?))
+            .order_by(domain_mutes::dsl::domain.asc())
+            .select(domain_mutes::dsl::domain)
+            .load(&*ctx.conn().await?)?;
+        Ok(domains)
+    }
+
+    /// If exactly the given domain was muted by the current viewer,
+    /// rather than one of its parent domains.
+    pub async fn domain_muted_by_viewer(ctx: &Context, domain: &str) -> Result<bool> {
+        match ctx.maybe_user_id() {
+            Some(user_id) => {
+                let count: i64 = domain_mutes::table
+                    .filter(domain_mutes::dsl::user_id.eq(user_id))
+                    .filter(domain_mutes::dsl::domain.eq(domain))
+                    .select(diesel::dsl::count_star())
+                    .get_result(&*ctx.conn().await?)?;
+                Ok(count > 0)
+            }
+            None => Ok(false),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{NaiveDate, NaiveTime};
+    use crate::db::id::CommentID;
+    use crate::db::models::{NewUserInput, Permission, QuotaExceeded, Role};
+    use chrono::{Duration, NaiveDate, NaiveTime};
+
+    async fn mock_context() -> Context {
+        let conf = crate::Config::test();
+        let pool = crate::db::connect(&conf).await.unwrap();
+        let mailer = crate::email::Mailer::File {
+            path: "./emails",
+            last_message: Default::default(),
+        };
+        Context::for_server(&pool, &mailer)
+    }
+
+    async fn insert_mock_url(
+        ctx: &Context,
+        title: &str,
+        age: Duration,
+        users: &[User],
+        upvotes: usize,
+        comments: usize,
+    ) -> Url {
+        let created_at = (ctx.now() - age).naive_utc();
+        let created_by = users[0].id();
+        let url = Url {
+            id: UrlID::new(),
+            created_at,
+            updated_at: created_at,
+            url: format!("https://urls.fyi/{}", title),
+            status_code: 200,
+            title: Some(title.into()),
+            description: None,
+            image: None,
+            created_by,
+            crawl_state: CrawlState::Crawled,
+            final_url: None,
+            canonical_url: None,
+            duplicate_of: None,
+            archived_at: None,
+            dead_since: None,
+            thumbnail_at: None,
+            domain: "urls.fyi".into(),
+            held_at: None,
+            text: None,
+            upvote_count: upvotes as i64,
+            comment_count: comments as i64,
+            title_override: None,
+            description_override: None,
+        };
+
+        let conn = ctx.conn().await.unwrap();
+        diesel::insert_into(urls::table)
+            .values(&url)
+            .execute(&*conn)
+            .unwrap();
+        for user in &users[..upvotes] {
+            diesel::insert_into(url_upvotes::table)
+                .values((
+                    url_upvotes::dsl::url_id.eq(url.id),
+                    url_upvotes::dsl::user_id.eq(user.id()),
+                    url_upvotes::dsl::created_at.eq(created_at),
+                ))
+                .execute(&*conn)
+                .unwrap();
+        }
+        for _ in 0..comments {
+            diesel::insert_into(comments::table)
+                .values((
+                    comments::dsl::id.eq(CommentID::new()),
+                    comments::dsl::created_at.eq(created_at),
+                    comments::dsl::updated_at.eq(created_at),
+                    comments::dsl::comment.eq("A comment"),
+                    comments::dsl::url_id.eq(url.id),
+                    comments::dsl::created_by.eq(created_by),
+                ))
+                .execute(&*conn)
+                .unwrap();
+        }
+        url
+    }
 
     #[test]
     fn test_canonicalize() {
@@ -477,10 +1898,30 @@ mod tests {
             ("https://urls.fyi/no-proto?other_test=&utm_medium=cpc&utm_content=text", "https://urls.fyi/no-proto?other_test"),
             ("https://www.youtube.com/watch?v=XXX&t=200s", "https://www.youtube.com/watch?v=XXX"),
             ("https://www.youtube.com/watch?v=XXX&t=200s", "https://www.youtube.com/watch?v=XXX"),
-            ("https://youtu.be/YYY?t=200", "https://youtu.be/YYY"),
+            ("https://youtu.be/YYY?t=200", "https://www.youtube.com/watch?v=YYY"),
         ];
         for (raw, clean) in pairs {
-            assert_eq!(Uri::from_static(clean), Url::canonicalize(raw).unwrap());
+            assert_eq!(clean, Url::canonicalize(raw).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_resolve_canonical() {
+        let page = reqwest::Url::parse("https://www.urls.fyi/a/b").unwrap();
+        let cases = [
+            ("/c", Some("https://www.urls.fyi/c")),
+            ("https://urls.fyi/c", Some("https://urls.fyi/c")),
+            ("https://blog.urls.fyi/c", Some("https://blog.urls.fyi/c")),
+            ("https://other.fyi/c", None),
+            ("https://urls.fyi.other.fyi/c", None),
+        ];
+        for (href, canonical) in cases {
+            assert_eq!(
+                Url::resolve_canonical(&page, href).as_deref(),
+                canonical,
+                "{}",
+                href
+            );
         }
     }
 
@@ -500,6 +1941,20 @@ mod tests {
             description: None,
             image: None,
             created_by: UserID::new(),
+            crawl_state: CrawlState::Crawled,
+            final_url: None,
+            canonical_url: None,
+            duplicate_of: None,
+            archived_at: None,
+            dead_since: None,
+            thumbnail_at: None,
+            domain: "urls.fyi".into(),
+            held_at: None,
+            text: None,
+            upvote_count: 0,
+            comment_count: 0,
+            title_override: None,
+            description_override: None,
         };
         assert_eq!(url.slug().unwrap(), "404-page-not-found");
         let url = Url { title: None, ..url };
@@ -510,4 +1965,587 @@ mod tests {
         };
         assert_eq!(url.slug().unwrap(), "tilman-dev");
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ranked_ordering() {
+        let mut ctx = mock_context().await;
+        ctx.set_now(DateTime::from_utc(
+            NaiveDateTime::new(
+                NaiveDate::from_ymd(2022, 10, 15),
+                NaiveTime::from_hms(16, 0, 0),
+            ),
+            Utc,
+        ));
+
+        let mut users = vec![];
+        for idx in 0..5 {
+            let input = NewUserInput {
+                name: format!("Test User {}", idx),
+                email: format!("test.user.{}@urls.fyi", idx),
+            };
+            users.push(User::create(&ctx, input).await.unwrap());
+        }
+
+        let submissions = [
+            ("ancient", Duration::days(10), 5, 0),
+            ("old-popular", Duration::days(3), 3, 0),
+            ("fresh-upvoted", Duration::hours(2), 2, 0),
+            ("fresh-discussed", Duration::hours(2), 0, 2),
+            ("brand-new", Duration::hours(1), 0, 0),
+        ];
+        for (title, age, upvotes, comments) in submissions {
+            insert_mock_url(&ctx, title, age, &users, upvotes, comments).await;
+        }
+
+        let (page, page_count) = Url::paginate(&ctx, UrlOrdering::Ranked, 0, 10)
+            .await
+            .unwrap();
+        let titles: Vec<&str> = page.iter().filter_map(|url| url.title()).collect();
+        assert_eq!(page_count, 1);
+        assert_eq!(
+            titles,
+            vec![
+                "fresh-upvoted",
+                "fresh-discussed",
+                "brand-new",
+                "old-popular",
+                "ancient"
+            ]
+        );
+
+        let (page, page_count) = Url::paginate(&ctx, UrlOrdering::Ranked, 1, 2)
+            .await
+            .unwrap();
+        let titles: Vec<&str> = page.iter().filter_map(|url| url.title()).collect();
+        assert_eq!(page_count, 3);
+        assert_eq!(titles, vec!["brand-new", "old-popular"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rejects_resolved_duplicates() {
+        let ctx = mock_context().await;
+        let input = NewUserInput {
+            name: "Test User".into(),
+            email: "test.user@urls.fyi".into(),
+        };
+        let user = User::create(&ctx, input).await.unwrap();
+        let users = [user.clone()];
+
+        let mut url = insert_mock_url(&ctx, "short", Duration::hours(1), &users, 0, 0).await;
+        url.final_url = Some("https://urls.fyi/resolved".into());
+        url.canonical_url = Some("https://urls.fyi/canonical".into());
+        url.store(&*ctx.conn().await.unwrap()).unwrap();
+
+        let submit = |url: &str| NewUrlInput {
+            url: url.into(),
+            tags: None,
+        };
+        let duplicates = [
+            url.url_str(),
+            "https://urls.fyi/resolved",
+            "https://urls.fyi/canonical",
+        ];
+        for duplicate in duplicates {
+            assert!(Url::create(&ctx, submit(duplicate), user.id())
+                .await
+                .is_err());
+        }
+        assert!(
+            Url::create(&ctx, submit("https://urls.fyi/other"), user.id())
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_duplicates_are_not_listed() {
+        let ctx = mock_context().await;
+        let input = NewUserInput {
+            name: "Test User".into(),
+            email: "test.user@urls.fyi".into(),
+        };
+        let users = [User::create(&ctx, input).await.unwrap()];
+
+        let original = insert_mock_url(&ctx, "original", Duration::hours(2), &users, 0, 0).await;
+        let mut duplicate =
+            insert_mock_url(&ctx, "duplicate", Duration::hours(1), &users, 0, 0).await;
+        duplicate.duplicate_of = Some(original.id());
+        duplicate.store(&*ctx.conn().await.unwrap()).unwrap();
+
+        for order in [UrlOrdering::Ranked, UrlOrdering::Best, UrlOrdering::Recent] {
+            let (page, page_count) = Url::paginate(&ctx, order, 0, 10).await.unwrap();
+            let titles: Vec<&str> = page.iter().filter_map(|url| url.title()).collect();
+            assert_eq!(page_count, 1);
+            assert_eq!(titles, vec!["original"]);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_merge_original_into_duplicate() {
+        let mut ctx = mock_context().await;
+        let input = NewUserInput {
+            name: "Test User".into(),
+            email: "test.user@urls.fyi".into(),
+        };
+        let user = User::create(&ctx, input).await.unwrap();
+        Role::create(&ctx, user.id(), Permission::Moderator)
+            .await
+            .unwrap();
+        ctx.set_logged_in_user(user.id(), "session".into());
+        let users = [user];
+
+        let original = insert_mock_url(&ctx, "original", Duration::hours(3), &users, 0, 0).await;
+        let mut duplicates = vec![];
+        for title in ["duplicate", "other duplicate"] {
+            let mut duplicate =
+                insert_mock_url(&ctx, title, Duration::hours(1), &users, 0, 0).await;
+            duplicate.duplicate_of = Some(original.id());
+            duplicate.store(&*ctx.conn().await.unwrap()).unwrap();
+            duplicates.push(duplicate);
+        }
+
+        // the surviving submission does not become a duplicate of itself
+        original
+            .merge_into(&ctx, &duplicates[0], None)
+            .await
+            .unwrap();
+        let into = Url::find(&ctx, duplicates[0].id()).await.unwrap();
+        assert_eq!(into.duplicate_of(), None);
+        let other = Url::find(&ctx, duplicates[1].id()).await.unwrap();
+        assert_eq!(other.duplicate_of(), Some(into.id()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dead_links() {
+        let mut ctx = mock_context().await;
+        let input = NewUserInput {
+            name: "Test User".into(),
+            email: "test.user@urls.fyi".into(),
+        };
+        let users = [User::create(&ctx, input).await.unwrap()];
+
+        let mut url = insert_mock_url(&ctx, "flaky", Duration::days(60), &users, 0, 0).await;
+        let failures = ctx.link_health().dead_after_failures();
+        let start = ctx.now();
+        for day in 0..failures {
+            ctx.set_now(start + Duration::days(day));
+            assert!(!url.is_dead());
+            url.record_check(&ctx, None).await.unwrap();
+        }
+        assert!(url.is_dead());
+        assert_eq!(url.dead_since(), Some(start));
+        assert!(url.crawl_state().is_failed());
+
+        let (page, _) = Url::paginate(&ctx, UrlOrdering::Dead, 0, 10).await.unwrap();
+        let titles: Vec<&str> = page.iter().filter_map(|url| url.title()).collect();
+        assert_eq!(titles, vec!["flaky"]);
+
+        ctx.set_now(start + Duration::days(failures));
+        url.record_check(&ctx, Some(StatusCode::OK)).await.unwrap();
+        assert!(!url.is_dead());
+        let url = Url::find(&ctx, url.id()).await.unwrap();
+        assert!(!url.is_dead());
+
+        let (page, page_count) = Url::paginate(&ctx, UrlOrdering::Dead, 0, 10).await.unwrap();
+        assert!(page.is_empty());
+        assert_eq!(page_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_saved_urls() {
+        let mut ctx = mock_context().await;
+        let input = NewUserInput {
+            name: "Test User".into(),
+            email: "test.user@urls.fyi".into(),
+        };
+        let users = [User::create(&ctx, input).await.unwrap()];
+        ctx.set_logged_in_user(users[0].id(), "session".into());
+
+        let first = insert_mock_url(&ctx, "first", Duration::days(2), &users, 0, 0).await;
+        let second = insert_mock_url(&ctx, "second", Duration::days(1), &users, 0, 0).await;
+        insert_mock_url(&ctx, "unsaved", Duration::days(1), &users, 0, 0).await;
+
+        second.save(&ctx).await.unwrap();
+        let second_saved_at = ctx.now();
+        ctx.set_now(ctx.now() + Duration::minutes(1));
+        first.save(&ctx).await.unwrap();
+        // saving twice is a no-op
+        first.save(&ctx).await.unwrap();
+        assert!(first.saved_by_viewer(&ctx).await.unwrap());
+
+        let order = UrlOrdering::Saved(users[0].id());
+        let (page, page_count) = Url::paginate(&ctx, order, 0, 10).await.unwrap();
+        let titles: Vec<&str> = page.iter().filter_map(|url| url.title()).collect();
+        assert_eq!(titles, vec!["first", "second"]);
+        assert_eq!(page_count, 1);
+
+        let ids = vec![first.id(), second.id()];
+        let saved_at = Url::saved_at_all(&ctx, users[0].id(), ids).await.unwrap();
+        assert_eq!(saved_at[&first.id()], ctx.now());
+        assert_eq!(saved_at[&second.id()], second_saved_at);
+
+        first.unsave(&ctx).await.unwrap();
+        assert!(!first.saved_by_viewer(&ctx).await.unwrap());
+        let (page, _) = Url::paginate(&ctx, order, 0, 10).await.unwrap();
+        let titles: Vec<&str> = page.iter().filter_map(|url| url.title()).collect();
+        assert_eq!(titles, vec!["second"]);
+    }
+
+    #[tokio::test]
+    async fn test_hide_and_mute() {
+        let mut ctx = mock_context().await;
+        let mut users = vec![];
+        for (name, email) in [("Viewer", "viewer@urls.fyi"), ("Other", "other@urls.fyi")] {
+            let input = NewUserInput {
+                name: name.into(),
+                email: email.into(),
+            };
+            users.push(User::create(&ctx, input).await.unwrap());
+        }
+        ctx.set_logged_in_user(users[0].id(), "session".into());
+
+        let hidden = insert_mock_url(&ctx, "hidden", Duration::hours(4), &users, 0, 0).await;
+        insert_mock_url(&ctx, "by other", Duration::hours(3), &users[1..], 0, 0).await;
+        let domain = insert_mock_url(&ctx, "domain", Duration::hours(2), &users, 0, 0).await;
+        insert_mock_url(&ctx, "visible", Duration::hours(1), &users, 0, 0).await;
+        let subdomain =
+            insert_mock_url(&ctx, "subdomain", Duration::minutes(40), &users, 0, 0).await;
+        let lookalike =
+            insert_mock_url(&ctx, "lookalike", Duration::minutes(20), &users, 0, 0).await;
+        for (url, domain) in [
+            (&domain, "example.com"),
+            (&subdomain, "blog.example.com"),
+            (&lookalike, "notexample.com"),
+        ] {
+            diesel::update(urls::table.find(url.id()))
+                .set(urls::dsl::domain.eq(domain))
+                .execute(&*ctx.conn().await.unwrap())
+                .unwrap();
+        }
+
+        hidden.hide(&ctx).await.unwrap();
+        users[1].mute(&ctx).await.unwrap();
+        let muted = Url::mute_domain(&ctx, "www.Example.com").await.unwrap();
+        assert_eq!(muted, "example.com");
+        assert!(users[0].mute(&ctx).await.is_err());
+
+        assert!(hidden.hidden_by_viewer(&ctx).await.unwrap());
+        assert!(users[1].muted_by_viewer(&ctx).await.unwrap());
+        assert!(Url::domain_muted_by_viewer(&ctx, "example.com")
+            .await
+            .unwrap());
+        assert_eq!(Url::hidden(&ctx).await.unwrap().len(), 1);
+        assert_eq!(User::muted(&ctx).await.unwrap().len(), 1);
+        assert_eq!(Url::muted_domains(&ctx).await.unwrap(), vec!["example.com"]);
+
+        for order in [UrlOrdering::Ranked, UrlOrdering::Recent, UrlOrdering::Best] {
+            let (page, page_count) = Url::paginate(&ctx, order, 0, 10).await.unwrap();
+            let titles: Vec<&str> = page.iter().filter_map(|url| url.title()).collect();
+            // muting a domain also mutes its subdomains
+            assert_eq!(titles, vec!["lookalike", "visible"]);
+            assert_eq!(page_count, 1);
+        }
+
+        // explicitly listing a muted user still shows their submissions
+        let order = UrlOrdering::User(users[1].id());
+        let (page, _) = Url::paginate(&ctx, order, 0, 10).await.unwrap();
+        assert_eq!(page.len(), 1);
+
+        hidden.unhide(&ctx).await.unwrap();
+        users[1].unmute(&ctx).await.unwrap();
+        Url::unmute_domain(&ctx, "example.com").await.unwrap();
+        let (page, _) = Url::paginate(&ctx, UrlOrdering::Recent, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_domain_listing() {
+        let ctx = mock_context().await;
+        let mut users = vec![];
+        for idx in 0..2 {
+            let input = NewUserInput {
+                name: format!("Test User {}", idx),
+                email: format!("test.user.{}@urls.fyi", idx),
+            };
+            users.push(User::create(&ctx, input).await.unwrap());
+        }
+
+        insert_mock_url(&ctx, "first", Duration::days(2), &users, 2, 0).await;
+        let mut dead = insert_mock_url(&ctx, "second", Duration::days(1), &users, 1, 0).await;
+        dead.dead_since = Some(ctx.now().naive_utc());
+        dead.store(&*ctx.conn().await.unwrap()).unwrap();
+
+        let (page, page_count) = Url::paginate(&ctx, UrlOrdering::Domain("urls.fyi"), 0, 10)
+            .await
+            .unwrap();
+        let titles: Vec<&str> = page.iter().filter_map(|url| url.title()).collect();
+        assert_eq!(titles, vec!["second", "first"]);
+        assert_eq!(page_count, 1);
+
+        let stats = Url::domain_stats(&ctx, "urls.fyi").await.unwrap();
+        assert_eq!(
+            stats,
+            DomainStats {
+                submissions: 2,
+                upvotes: 3,
+                dead: 1,
+            }
+        );
+        assert!((stats.dead_share() - 0.5).abs() < f64::EPSILON);
+
+        let (page, _) = Url::paginate(&ctx, UrlOrdering::Domain("example.com"), 0, 10)
+            .await
+            .unwrap();
+        assert!(page.is_empty());
+        assert_eq!(
+            Url::domain_stats(&ctx, "example.com")
+                .await
+                .unwrap()
+                .dead_share(),
+            0.0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_text_posts() {
+        let ctx = mock_context().await;
+        let input = NewUserInput {
+            name: "Test User".into(),
+            email: "test.user@urls.fyi".into(),
+        };
+        let user = User::create(&ctx, input).await.unwrap();
+
+        let input = NewTextPostInput {
+            title: "  Ask: favorite *databases*?  ".into(),
+            text: "Mine is **SQLite**.".into(),
+            tags: None,
+        };
+        let post = Url::create_text_post(&ctx, input, user.id()).await.unwrap();
+        assert!(post.is_text_post());
+        assert_eq!(post.title(), Some("Ask: favorite *databases*?"));
+        assert_eq!(
+            post.url_str(),
+            format!("http://localhost/comments/{}", post.id())
+        );
+        assert_eq!(post.domain(), "localhost");
+        assert_eq!(
+            post.html().unwrap(),
+            "<p>Mine is <strong>SQLite</strong>.</p>\n"
+        );
+
+        // text posts are listed, but never crawled
+        let (page, _) = Url::paginate(&ctx, UrlOrdering::Recent, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert!(Url::pending(&ctx).await.unwrap().is_empty());
+
+        let input = NewTextPostInput {
+            title: " ".into(),
+            text: "Some text".into(),
+            tags: None,
+        };
+        assert!(Url::create_text_post(&ctx, input, user.id()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_submission_quota() {
+        let mut ctx = mock_context().await;
+        let input = NewUserInput {
+            name: "Test User".into(),
+            email: "test.user@urls.fyi".into(),
+        };
+        let user = User::create(&ctx, input).await.unwrap();
+        let start = ctx.now();
+        let submit = |n: usize| NewUrlInput::new(format!("https://urls.fyi/quota/{}", n), vec![]);
+
+        // new accounts get the tighter quota
+        let new_account = ctx.rate_limits().submissions(true);
+        let per_hour = new_account.per_hour() as usize;
+        for n in 0..per_hour {
+            Url::create(&ctx, submit(n), user.id()).await.unwrap();
+        }
+        let err = Url::create(&ctx, submit(per_hour), user.id())
+            .await
+            .unwrap_err();
+        let quota = err.downcast_ref::<QuotaExceeded>().unwrap();
+        assert_eq!(quota.remaining_hour(), 0);
+        assert_eq!(
+            quota.remaining_day(),
+            new_account.per_day() - new_account.per_hour()
+        );
+        assert_eq!(quota.resets_at(), start + Duration::hours(1));
+        assert!(err.to_string().contains("for new accounts"));
+
+        // deleting a submission doesn't free up the quota
+        ctx.set_logged_in_user(user.id(), "session".into());
+        let first = Url::find_submitted(&ctx, &submit(0).url)
+            .await
+            .unwrap()
+            .unwrap();
+        first.delete(&ctx, None).await.unwrap();
+        let err = Url::create(&ctx, submit(per_hour), user.id())
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<QuotaExceeded>().is_some());
+
+        ctx.set_now(quota.resets_at());
+        Url::create(&ctx, submit(per_hour), user.id())
+            .await
+            .unwrap();
+
+        // older accounts get the regular quota
+        ctx.set_now(start + Duration::days(ctx.rate_limits().new_account_days()));
+        let per_hour = ctx.rate_limits().submissions(false).per_hour() as usize;
+        for n in 0..per_hour {
+            Url::create(&ctx, submit(100 + n), user.id()).await.unwrap();
+        }
+        let err = Url::create(&ctx, submit(200), user.id()).await.unwrap_err();
+        assert!(err.downcast_ref::<QuotaExceeded>().is_some());
+        assert!(!err.to_string().contains("for new accounts"));
+    }
+
+    #[tokio::test]
+    async fn test_cached_counts() {
+        let mut ctx = mock_context().await;
+        let input = NewUserInput {
+            name: "Test User".into(),
+            email: "test.user@urls.fyi".into(),
+        };
+        let users = [User::create(&ctx, input).await.unwrap()];
+        ctx.set_logged_in_user(users[0].id(), "session".into());
+
+        let mut url = insert_mock_url(&ctx, "counted", Duration::hours(1), &users, 0, 2).await;
+        url.upvote(&ctx).await.unwrap();
+        assert_eq!(url.upvote_count(), 1);
+        // upvoting twice fails, and leaves the counter as is
+        assert!(url.upvote(&ctx).await.is_err());
+        let url = Url::find(&ctx, url.id()).await.unwrap();
+        assert_eq!((url.upvote_count(), url.comment_count()), (1, 2));
+
+        diesel::update(urls::table.find(url.id()))
+            .set(urls::dsl::comment_count.eq(7))
+            .execute(&*ctx.conn().await.unwrap())
+            .unwrap();
+        assert_eq!(Url::check_counts(&ctx).await.unwrap(), 1);
+        assert_eq!(Url::check_counts(&ctx).await.unwrap(), 0);
+        let mut url = Url::find(&ctx, url.id()).await.unwrap();
+        assert_eq!(url.comment_count(), 2);
+
+        url.rescind_upvote(&ctx).await.unwrap();
+        url.rescind_upvote(&ctx).await.unwrap();
+        assert_eq!(url.upvote_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_loading() {
+        let mut ctx = mock_context().await;
+        let mut users = vec![];
+        for idx in 0..3 {
+            let input = NewUserInput {
+                name: format!("User {}", idx),
+                email: format!("user.{}@urls.fyi", idx),
+            };
+            users.push(User::create(&ctx, input).await.unwrap());
+        }
+        ctx.set_logged_in_user(users[1].id(), "session".into());
+
+        for idx in 0..10 {
+            let title = format!("url-{}", idx);
+            let mut users = users.clone();
+            users.rotate_left(idx % 3);
+            let url = insert_mock_url(&ctx, &title, Duration::hours(1), &users, 2, 1).await;
+            let conn = ctx.conn().await.unwrap();
+            Tag::replace_for_url(&ctx, &*conn, url.id, &["batched".into()]).unwrap();
+        }
+
+        let (page, _) = Url::paginate(&ctx, UrlOrdering::Recent, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 10);
+
+        let ctx = &ctx;
+        let load_page = || {
+            futures_util::future::try_join_all(page.iter().map(|url| async move {
+                tokio::try_join!(
+                    url.created_by(ctx),
+                    url.tags(ctx),
+                    url.upvoted_by_viewer(ctx),
+                    url.saved_by_viewer(ctx),
+                )
+            }))
+        };
+
+        let before = ctx.loaders().batch_count();
+        let rows = load_page().await.unwrap();
+        // one batch per loader, not one per URL
+        assert_eq!(ctx.loaders().batch_count() - before, 4);
+        for (url, (created_by, tags, upvoted, saved)) in page.iter().zip(&rows) {
+            assert_eq!(created_by.id(), url.created_by);
+            assert_eq!(tags.len(), 1);
+            // the first two users in the rotation upvoted
+            assert_eq!(*upvoted, url.created_by != users[2].id());
+            assert!(!saved);
+        }
+
+        // cached values are returned without fetching again
+        let before = ctx.loaders().batch_count();
+        load_page().await.unwrap();
+        assert_eq!(ctx.loaders().batch_count(), before);
+    }
+
+    #[tokio::test]
+    async fn test_edit_title() {
+        let mut ctx = mock_context().await;
+        let mut users = vec![];
+        for idx in 0..2 {
+            let input = NewUserInput {
+                name: format!("User {}", idx),
+                email: format!("user.{}@urls.fyi", idx),
+            };
+            users.push(User::create(&ctx, input).await.unwrap());
+        }
+        ctx.set_logged_in_user(users[0].id(), "session".into());
+
+        let title = "Home | Company Blog";
+        let mut url = insert_mock_url(&ctx, title, Duration::hours(1), &users, 0, 0).await;
+        let edit = |title: &str| UpdateUrlInput {
+            title: Some(title.into()),
+            description: None,
+        };
+        url.update(&ctx, edit("  A better title ")).await.unwrap();
+        assert_eq!(url.title(), Some("A better title"));
+        assert_eq!(url.crawled_title(), Some(title));
+
+        // crawling the page again keeps the edit
+        url.title = Some("Home | Company Blog (updated)".into());
+        url.store(&*ctx.conn().await.unwrap()).unwrap();
+        let mut url = Url::find(&ctx, url.id()).await.unwrap();
+        assert_eq!(url.title(), Some("A better title"));
+        assert_eq!(url.slug().unwrap(), "a-better-title");
+
+        let mut other = ctx.clone();
+        other.set_logged_in_user(users[1].id(), "other".into());
+        assert!(url.update(&other, edit("Spam")).await.is_err());
+
+        url.update(&ctx, edit("")).await.unwrap();
+        assert_eq!(url.title(), Some("Home | Company Blog (updated)"));
+
+        // edits which change nothing are not recorded
+        url.update(&ctx, edit("")).await.unwrap();
+        let unchanged = UpdateUrlInput {
+            title: None,
+            description: None,
+        };
+        url.update(&ctx, unchanged).await.unwrap();
+
+        let edits: i64 = url_edits::table
+            .filter(url_edits::dsl::url_id.eq(url.id()))
+            .select(diesel::dsl::count_star())
+            .get_result(&*ctx.conn().await.unwrap())
+            .unwrap();
+        assert_eq!(edits, 2);
+    }
 }
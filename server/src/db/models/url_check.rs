@@ -0,0 +1,78 @@
+use crate::db::id::UrlID;
+use crate::schema::url_checks;
+use crate::Context;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use std::convert::TryInto;
+use warp::http::StatusCode;
+
+/// Status code stored for checks which did
+/// not receive a response.
+const STATUS_UNREACHABLE: i32 = 0;
+
+/// The result of periodically checking if a
+/// submitted URL can still be reached.
+#[derive(Debug, Clone, Queryable, Insertable)]
+#[table_name = "url_checks"]
+pub struct UrlCheck {
+    url_id: UrlID,
+    checked_at: NaiveDateTime,
+    status_code: i32,
+}
+
+impl UrlCheck {
+    pub fn checked_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.checked_at, Utc)
+    }
+
+    /// The status code the URL responded with, or `None`
+    /// if the URL could not be reached.
+    pub fn status(&self) -> Option<StatusCode> {
+        if self.status_code == STATUS_UNREACHABLE {
+            None
+        } else {
+            self.status_code
+                .try_into()
+                .ok()
+                .and_then(|code| StatusCode::from_u16(code).ok())
+        }
+    }
+
+    /// If the URL responded with a successful
+    /// status code.
+    pub fn is_success(&self) -> bool {
+        self.status().map_or(false, |status| status.is_success())
+    }
+}
+
+impl UrlCheck {
+    /// The most recent checks of the given URL, newest
+    /// first.
+    pub async fn for_url(ctx: &Context, url_id: UrlID, limit: i64) -> Result<Vec<Self>> {
+        let checks = url_checks::table
+            .filter(url_checks::dsl::url_id.eq(url_id))
+            .order_by(url_checks::dsl::checked_at.desc())
+            .limit(limit)
+            .load(&*ctx.conn().await?)?;
+        Ok(checks)
+    }
+
+    /// Records a check of the given URL at the current
+    /// time.
+    pub(super) async fn insert(
+        ctx: &Context,
+        url_id: UrlID,
+        status: Option<StatusCode>,
+    ) -> Result<Self> {
+        let check = UrlCheck {
+            url_id,
+            checked_at: ctx.now().naive_utc(),
+            status_code: status.map_or(STATUS_UNREACHABLE, |status| status.as_u16().into()),
+        };
+        diesel::insert_into(url_checks::table)
+            .values(&check)
+            .execute(&*ctx.conn().await?)?;
+        Ok(check)
+    }
+}
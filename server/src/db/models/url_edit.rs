@@ -0,0 +1,84 @@
+use crate::db::id::{UrlEditID, UrlID, UserID};
+use crate::db::models::User;
+use crate::schema::url_edits;
+use crate::Context;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use diesel::prelude::*;
+
+/// A change to the title or description of a submission. The
+/// edit holds the values after the change, where `None` means
+/// the crawled value is shown again.
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+pub struct UrlEdit {
+    id: UrlEditID,
+    created_at: NaiveDateTime,
+    created_by: UserID,
+
+    url_id: UrlID,
+    title: Option<String>,
+    description: Option<String>,
+}
+
+impl UrlEdit {
+    pub fn id(&self) -> UrlEditID {
+        self.id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.created_at, Utc)
+    }
+
+    /// The user who made the edit.
+    pub async fn created_by(&self, ctx: &Context) -> Result<User> {
+        ctx.loaders().user(ctx, self.created_by).await
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+impl UrlEdit {
+    /// All edits of the given URL, newest first. Only
+    /// moderators can see the edit history.
+    pub async fn for_url(ctx: &Context, url_id: UrlID) -> Result<Vec<Self>> {
+        ctx.user()
+            .await?
+            .check_permissions(ctx, |perm| perm.view_url_edits())
+            .await?;
+        let edits = url_edits::table
+            .filter(url_edits::dsl::url_id.eq(url_id))
+            .order_by(url_edits::dsl::created_at.desc())
+            .load(&*ctx.conn().await?)?;
+        Ok(edits)
+    }
+
+    /// Records an edit made by the logged in user. This is
+    /// meant to run in the same transaction as the edit.
+    pub(super) fn record(
+        ctx: &Context,
+        conn: &SqliteConnection,
+        url_id: UrlID,
+        title: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<()> {
+        let edit = Self {
+            id: UrlEditID::new(),
+            created_at: ctx.now().naive_utc(),
+            created_by: ctx.user_id()?,
+
+            url_id,
+            title: title.map(Into::into),
+            description: description.map(Into::into),
+        };
+        diesel::insert_into(url_edits::table)
+            .values(&edit)
+            .execute(conn)?;
+        Ok(())
+    }
+}
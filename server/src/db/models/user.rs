@@ -1,6 +1,6 @@
 use crate::db::id::UserID;
-use crate::db::models::{Invite, Login, Permission, Role};
-use crate::schema::{invites, logins, roles, users};
+use crate::db::models::{Invite, Login, Permission, Role, Upvote};
+use crate::schema::{invites, logins, roles, user_mutes, users};
 use crate::Context;
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, NaiveDateTime, Utc};
@@ -8,6 +8,8 @@ use diesel::prelude::*;
 use juniper::GraphQLInputObject;
 use lettre::address::Address;
 use lettre::message::{Mailbox, Message};
+use nanoid::nanoid;
+use std::collections::HashMap;
 use std::str::FromStr;
 use validator::{Validate, ValidationError};
 
@@ -19,6 +21,8 @@ pub struct User {
 
     name: String,
     email: String,
+    feed_token: Option<String>,
+    public_upvotes: bool,
 }
 
 #[derive(Debug, Clone, Validate, GraphQLInputObject)]
@@ -47,6 +51,7 @@ pub struct UpdateUserInput {
         )
     )]
     email: Option<String>,
+    public_upvotes: Option<bool>,
 }
 
 fn disposable_email(email: &str) -> Result<(), ValidationError> {
@@ -84,6 +89,30 @@ impl User {
         DateTime::from_utc(self.updated_at, Utc)
     }
 
+    /// Secret token which grants access to the private
+    /// feed of this users saved URLs, if one was issued.
+    pub fn feed_token(&self) -> Option<&str> {
+        self.feed_token.as_deref()
+    }
+
+    /// If the user lets everyone see which URLs
+    /// they upvoted.
+    pub fn public_upvotes(&self) -> bool {
+        self.public_upvotes
+    }
+
+    /// If the current viewer can see which URLs this user
+    /// upvoted. This is the case for the user themselves,
+    /// for moderators, and for everyone if the user made
+    /// their upvotes public.
+    pub async fn upvotes_visible(&self, ctx: &Context) -> Result<bool> {
+        if self.public_upvotes || ctx.maybe_user_id() == Some(self.id) {
+            Ok(true)
+        } else {
+            Upvote::viewer_sees_all(ctx).await
+        }
+    }
+
     /// Return a list of all active permissions for this
     /// user.
     pub async fn permissions(&self, ctx: &Context) -> Result<Vec<Permission>> {
@@ -118,6 +147,21 @@ impl User {
             .optional()?;
         Ok(invite)
     }
+
+    /// If this user was muted by the current viewer.
+    pub async fn muted_by_viewer(&self, ctx: &Context) -> Result<bool> {
+        match ctx.maybe_user_id() {
+            Some(user_id) => {
+                let count: i64 = user_mutes::table
+                    .filter(user_mutes::dsl::user_id.eq(user_id))
+                    .filter(user_mutes::dsl::muted_id.eq(self.id))
+                    .select(diesel::dsl::count_star())
+                    .get_result(&*ctx.conn().await?)?;
+                Ok(count > 0)
+            }
+            None => Ok(false),
+        }
+    }
 }
 
 impl User {
@@ -127,6 +171,36 @@ impl User {
         Ok(user)
     }
 
+    /// Load all users with the given IDs, keyed by ID. IDs
+    /// which do not exist are omitted.
+    pub async fn find_all(ctx: &Context, ids: Vec<UserID>) -> Result<HashMap<UserID, Self>> {
+        let users: Vec<Self> = users::table
+            .filter(users::dsl::id.eq_any(ids))
+            .load(&*ctx.conn().await?)?;
+        Ok(users.into_iter().map(|user| (user.id, user)).collect())
+    }
+
+    /// Retrieve a user by the secret token of their
+    /// private feed.
+    pub async fn find_by_feed_token(ctx: &Context, token: &str) -> Result<Self> {
+        let user = users::table
+            .filter(users::dsl::feed_token.eq(token))
+            .get_result(&*ctx.conn().await?)?;
+        Ok(user)
+    }
+
+    /// Users muted by the logged in user, the most
+    /// recently muted first.
+    pub async fn muted(ctx: &Context) -> Result<Vec<Self>> {
+        let users = users::table
+            .inner_join(user_mutes::table.on(user_mutes::dsl::muted_id.eq(users::dsl::id)))
+            .filter(user_mutes::dsl::user_id.eq(ctx.user_id()?))
+            .order_by(user_mutes::dsl::created_at.desc())
+            .select(users::all_columns)
+            .load(&*ctx.conn().await?)?;
+        Ok(users)
+    }
+
     /// Retrieve a user by it's email address.
     pub async fn find_by_email(ctx: &Context, email: &str) -> Result<Self> {
         let conn = ctx.conn().await?;
@@ -154,6 +228,8 @@ impl User {
             id: UserID::new(),
             name,
             email,
+            feed_token: None,
+            public_upvotes: false,
 
             created_at: ctx.now().naive_utc(),
             updated_at: ctx.now().naive_utc(),
@@ -190,9 +266,14 @@ impl User {
         let input = UpdateUserInput {
             name: input.name.map(|name| name.trim().into()),
             email: input.email.map(|email| email.trim().to_ascii_lowercase()),
+            public_upvotes: input.public_upvotes,
         };
         input.validate()?;
-        let UpdateUserInput { name, email } = input;
+        let UpdateUserInput {
+            name,
+            email,
+            public_upvotes,
+        } = input;
 
         if let Some(name) = name {
             self.name = name;
@@ -204,10 +285,51 @@ impl User {
             self.updated_at = ctx.now().naive_utc();
         }
 
+        if let Some(public_upvotes) = public_upvotes {
+            self.public_upvotes = public_upvotes;
+            self.updated_at = ctx.now().naive_utc();
+        }
+
+        *self = self.save_changes(&*ctx.conn().await?)?;
+        ctx.loaders().forget_user(self.id);
+        Ok(())
+    }
+
+    /// Issues a new secret token for the private feed of saved
+    /// URLs. Any previously issued token stops working.
+    pub async fn reset_feed_token(&mut self, ctx: &Context) -> Result<()> {
+        self.feed_token = Some(nanoid!(64));
+        self.updated_at = ctx.now().naive_utc();
         *self = self.save_changes(&*ctx.conn().await?)?;
         Ok(())
     }
 
+    /// Mute this user for the logged in user, such that their
+    /// submissions are no longer listed.
+    pub async fn mute(&self, ctx: &Context) -> Result<()> {
+        let user_id = ctx.user_id()?;
+        if user_id == self.id {
+            return Err(anyhow!("You can not mute yourself"));
+        }
+        diesel::insert_or_ignore_into(user_mutes::table)
+            .values((
+                user_mutes::dsl::user_id.eq(user_id),
+                user_mutes::dsl::muted_id.eq(self.id),
+                user_mutes::dsl::created_at.eq(ctx.now().naive_utc()),
+            ))
+            .execute(&*ctx.conn().await?)?;
+        Ok(())
+    }
+
+    /// Unmute this user for the logged in user.
+    pub async fn unmute(&self, ctx: &Context) -> Result<()> {
+        let mute = user_mutes::table
+            .filter(user_mutes::dsl::user_id.eq(ctx.user_id()?))
+            .filter(user_mutes::dsl::muted_id.eq(self.id));
+        diesel::delete(mute).execute(&*ctx.conn().await?)?;
+        Ok(())
+    }
+
     /// Creates a login and sends an email to the user, containing the
     /// login token.
     pub async fn request_login(&self, ctx: &Context) -> Result<()> {
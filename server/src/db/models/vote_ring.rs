@@ -0,0 +1,685 @@
+use super::flag::paginate_queue;
+use crate::db::id::{UrlID, UserID, VoteRingID};
+use crate::db::models::{AuditAction, AuditEntry, Url, User};
+use crate::schema::{invites, logins, url_upvotes, urls, vote_ring_members, vote_rings};
+use crate::Context;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use futures_util::future::try_join_all;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+
+/// Only upvotes from this many days back are considered
+/// when looking for voting rings.
+const WINDOW_DAYS: i64 = 30;
+/// The number of upvotes two accounts need to have in common,
+/// or one account needs to have given to one submitter, before
+/// they are linked.
+const MIN_RING_UPVOTES: usize = 5;
+/// The share of the upvotes of the less active of two accounts
+/// which both accounts need to have in common.
+const MIN_SHARED_SHARE: f64 = 0.5;
+/// The share of the upvotes of an account which need to go to
+/// a single submitter.
+const MIN_SUBMITTER_SHARE: f64 = 0.8;
+/// Submissions with more upvotes than this are popular, and
+/// upvoting them does not link accounts. This also bounds the
+/// number of pairs counted for a single submission.
+const MAX_SHARED_VOTERS: usize = 50;
+
+/// A suspicious cluster of accounts, which repeatedly upvoted
+/// the same submissions and are linked by shared IP addresses
+/// or invites, or which upvoted almost only one submitter.
+/// Voting rings are found by a background job, see
+/// [`VoteRing::detect`], and reviewed by administrators.
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable, AsChangeset)]
+pub struct VoteRing {
+    id: VoteRingID,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+
+    member_key: String,
+    ring_upvotes: i64,
+    shared_ip: bool,
+    invite_tree: bool,
+    single_submitter: bool,
+    discounted_at: Option<NaiveDateTime>,
+    dismissed_at: Option<NaiveDateTime>,
+}
+
+/// An upvote which is considered when looking
+/// for voting rings.
+#[derive(Debug, Clone, Copy)]
+struct Vote {
+    url_id: UrlID,
+    user_id: UserID,
+    submitter: UserID,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    SharedIp,
+    InviteTree,
+    SingleSubmitter,
+}
+
+#[derive(Debug, Default)]
+struct Cluster {
+    members: HashSet<UserID>,
+    shared_ip: bool,
+    invite_tree: bool,
+    single_submitter: bool,
+}
+
+impl VoteRing {
+    pub fn id(&self) -> VoteRingID {
+        self.id
+    }
+
+    /// When the ring was first found.
+    pub fn created_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.created_at, Utc)
+    }
+
+    /// When the ring was last found.
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        DateTime::from_utc(self.updated_at, Utc)
+    }
+
+    /// The number of counted upvotes members of the ring gave
+    /// to each other, or to submissions upvoted by other members.
+    pub fn ring_upvotes(&self) -> i64 {
+        self.ring_upvotes
+    }
+
+    /// If members of the ring logged in from the same IP
+    /// address.
+    pub fn shared_ip(&self) -> bool {
+        self.shared_ip
+    }
+
+    /// If members of the ring invited each other, or were
+    /// invited by the same user.
+    pub fn invite_tree(&self) -> bool {
+        self.invite_tree
+    }
+
+    /// If members of the ring upvoted almost only submissions
+    /// from another member.
+    pub fn single_submitter(&self) -> bool {
+        self.single_submitter
+    }
+
+    /// A human readable list of the signals which link the
+    /// members of the ring.
+    pub fn signals(&self) -> String {
+        let mut signals = vec![];
+        if self.shared_ip {
+            signals.push("Shared IP addresses");
+        }
+        if self.invite_tree {
+            signals.push("Linked by invites");
+        }
+        if self.single_submitter {
+            signals.push("Upvotes for a single submitter");
+        }
+        signals.join(", ")
+    }
+
+    /// When the upvotes of the ring were last discounted, if
+    /// they were.
+    pub fn discounted_at(&self) -> Option<DateTime<Utc>> {
+        self.discounted_at
+            .map(|discounted_at| DateTime::from_utc(discounted_at, Utc))
+    }
+
+    /// When an administrator dismissed the ring, if they did.
+    /// Dismissed rings are not reported again.
+    pub fn dismissed_at(&self) -> Option<DateTime<Utc>> {
+        self.dismissed_at
+            .map(|dismissed_at| DateTime::from_utc(dismissed_at, Utc))
+    }
+
+    /// The accounts which form the ring.
+    pub async fn members(&self, ctx: &Context) -> Result<Vec<User>> {
+        let ids = self.member_ids(ctx).await?;
+        try_join_all(ids.into_iter().map(|id| ctx.loaders().user(ctx, id))).await
+    }
+
+    async fn member_ids(&self, ctx: &Context) -> Result<Vec<UserID>> {
+        let ids = vote_ring_members::table
+            .filter(vote_ring_members::dsl::ring_id.eq(self.id))
+            .select(vote_ring_members::dsl::user_id)
+            .load(&*ctx.conn().await?)?;
+        Ok(ids)
+    }
+}
+
+impl VoteRing {
+    /// Load the ring with the given ID. Only administrators
+    /// can view voting rings.
+    pub async fn find(ctx: &Context, id: VoteRingID) -> Result<Self> {
+        check_view_permissions(ctx).await?;
+        let ring = vote_rings::table.find(id).get_result(&*ctx.conn().await?)?;
+        Ok(ring)
+    }
+
+    /// Rings which are awaiting review, the ring with the most
+    /// counted upvotes first. The queue can be paginated using
+    /// the IDs of earlier or later rings as cursors. Only
+    /// administrators can view voting rings.
+    pub async fn pending(
+        ctx: &Context,
+        after: Option<VoteRingID>,
+        before: Option<VoteRingID>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Self>> {
+        check_view_permissions(ctx).await?;
+        let conn = ctx.conn().await?;
+        let ids: Vec<VoteRingID> = vote_rings::table
+            .filter(vote_rings::dsl::dismissed_at.is_null())
+            .filter(vote_rings::dsl::ring_upvotes.gt(0))
+            .order_by(vote_rings::dsl::ring_upvotes.desc())
+            .then_order_by(vote_rings::dsl::updated_at.desc())
+            .select(vote_rings::dsl::id)
+            .load(&*conn)?;
+        let ids = paginate_queue(ids, after, before, limit);
+
+        let mut rings: Vec<Self> = vote_rings::table
+            .filter(vote_rings::dsl::id.eq_any(ids.clone()))
+            .load(&*conn)?;
+        rings.sort_by_key(|ring| ids.iter().position(|id| *id == ring.id));
+        Ok(rings)
+    }
+
+    /// Looks for voting rings among recent upvotes, and records
+    /// the rings which were found. Rings which are not found again
+    /// are removed, unless an administrator reviewed them already.
+    /// Returns the number of rings found.
+    pub async fn detect(ctx: &Context) -> Result<usize> {
+        let since = ctx.now() - Duration::days(WINDOW_DAYS);
+        let conn = ctx.conn().await?;
+        let votes = load_votes(&*conn, Some(since.naive_utc()), None)?;
+
+        let mut ips: HashMap<UserID, HashSet<String>> = HashMap::new();
+        let logins: Vec<(UserID, Option<String>)> = logins::table
+            .filter(logins::dsl::last_remote_ip.is_not_null())
+            .select((logins::dsl::user_id, logins::dsl::last_remote_ip))
+            .load(&*conn)?;
+        for (user_id, ip) in logins {
+            ips.entry(user_id).or_default().extend(ip);
+        }
+
+        let invites: Vec<(Option<UserID>, UserID)> = invites::table
+            .filter(invites::dsl::claimed_by.is_not_null())
+            .select((invites::dsl::claimed_by, invites::dsl::created_by))
+            .load(&*conn)?;
+        let inviters: HashMap<UserID, UserID> = invites
+            .into_iter()
+            .filter_map(|(claimed_by, created_by)| Some((claimed_by?, created_by)))
+            .collect();
+
+        let clusters = find_clusters(&votes, &ips, &inviters);
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            let mut found = vec![];
+            for cluster in &clusters {
+                let member_key = cluster.key();
+                let ring_upvotes = ring_votes(&votes, &cluster.members).len() as i64;
+                let existing: Option<Self> = vote_rings::table
+                    .filter(vote_rings::dsl::member_key.eq(&member_key))
+                    .get_result(&*conn)
+                    .optional()?;
+                let ring = match existing {
+                    Some(mut ring) => {
+                        // discounted upvotes are not counted, so members
+                        // of a discounted ring upvoted each other again
+                        if ring_upvotes > 0 {
+                            ring.discounted_at = None;
+                        }
+                        ring.updated_at = ctx.now().naive_utc();
+                        ring.ring_upvotes = ring_upvotes;
+                        ring.shared_ip = cluster.shared_ip;
+                        ring.invite_tree = cluster.invite_tree;
+                        ring.single_submitter = cluster.single_submitter;
+                        ring.save_changes::<Self>(&*conn)?
+                    }
+                    None => {
+                        let ring = Self {
+                            id: VoteRingID::new(),
+                            created_at: ctx.now().naive_utc(),
+                            updated_at: ctx.now().naive_utc(),
+
+                            member_key,
+                            ring_upvotes,
+                            shared_ip: cluster.shared_ip,
+                            invite_tree: cluster.invite_tree,
+                            single_submitter: cluster.single_submitter,
+                            discounted_at: None,
+                            dismissed_at: None,
+                        };
+                        diesel::insert_into(vote_rings::table)
+                            .values(&ring)
+                            .execute(&*conn)?;
+                        for user_id in &cluster.members {
+                            diesel::insert_into(vote_ring_members::table)
+                                .values((
+                                    vote_ring_members::dsl::ring_id.eq(ring.id),
+                                    vote_ring_members::dsl::user_id.eq(*user_id),
+                                ))
+                                .execute(&*conn)?;
+                        }
+                        ring
+                    }
+                };
+                found.push(ring.id);
+            }
+
+            let stale: Vec<VoteRingID> = vote_rings::table
+                .filter(vote_rings::dsl::id.ne_all(found))
+                .filter(vote_rings::dsl::discounted_at.is_null())
+                .filter(vote_rings::dsl::dismissed_at.is_null())
+                .select(vote_rings::dsl::id)
+                .load(&*conn)?;
+            diesel::delete(
+                vote_ring_members::table
+                    .filter(vote_ring_members::dsl::ring_id.eq_any(stale.clone())),
+            )
+            .execute(&*conn)?;
+            diesel::delete(vote_rings::table.filter(vote_rings::dsl::id.eq_any(stale)))
+                .execute(&*conn)?;
+            Ok(())
+        })?;
+        Ok(clusters.len())
+    }
+
+    /// Discounts the upvotes the members of this ring gave to each
+    /// other, or to submissions upvoted by other members. Discounted
+    /// upvotes are kept, but no longer count towards the ranking.
+    /// Only administrators can discount upvotes. This is recorded
+    /// in the audit log, together with the optional reason. Returns
+    /// the number of discounted upvotes.
+    pub async fn discount(&mut self, ctx: &Context, reason: Option<&str>) -> Result<usize> {
+        check_discount_permissions(ctx).await?;
+        if self.discounted_at.is_some() {
+            return Err(anyhow!("The voting ring was already discounted"));
+        }
+        let members = self.member_ids(ctx).await?;
+        let conn = ctx.conn().await?;
+        let discounted = conn.transaction::<_, anyhow::Error, _>(|| {
+            let votes = load_votes(&*conn, None, Some(members.clone()))?;
+            let discounted = ring_votes(&votes, &members.iter().copied().collect());
+            for (url_id, user_id) in &discounted {
+                diesel::update(url_upvotes::table.find((*url_id, *user_id)))
+                    .set(url_upvotes::dsl::discounted_at.eq(ctx.now().naive_utc()))
+                    .execute(&*conn)?;
+            }
+            let url_ids: HashSet<UrlID> = discounted.iter().map(|(url_id, _)| *url_id).collect();
+            for url_id in &url_ids {
+                Url::recount(&*conn, *url_id)?;
+            }
+
+            self.ring_upvotes = 0;
+            self.discounted_at = Some(ctx.now().naive_utc());
+            *self = self.save_changes(&*conn)?;
+
+            let snapshot = json!({
+                "members": members.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "upvotes": discounted
+                    .iter()
+                    .map(|(url_id, user_id)| json!({
+                        "url_id": url_id.to_string(),
+                        "user_id": user_id.to_string(),
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::DiscountVotes,
+                &self.id.to_string(),
+                reason,
+                snapshot,
+            )?;
+            Ok(discounted)
+        })?;
+        drop(conn);
+        for (url_id, _) in &discounted {
+            ctx.loaders().forget_url(*url_id);
+        }
+        Ok(discounted.len())
+    }
+
+    /// Marks the ring as reviewed without discounting its upvotes,
+    /// such that it is no longer reported. Only administrators can
+    /// dismiss voting rings. This is recorded in the audit log,
+    /// together with the optional reason.
+    pub async fn dismiss(&mut self, ctx: &Context, reason: Option<&str>) -> Result<()> {
+        check_discount_permissions(ctx).await?;
+        if self.dismissed_at.is_some() {
+            return Err(anyhow!("The voting ring was already dismissed"));
+        }
+        let members = self.member_ids(ctx).await?;
+        let conn = ctx.conn().await?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            self.dismissed_at = Some(ctx.now().naive_utc());
+            *self = self.save_changes(&*conn)?;
+            let snapshot = json!({
+                "members": members.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "ring_upvotes": self.ring_upvotes,
+                "signals": self.signals(),
+            });
+            AuditEntry::record(
+                ctx,
+                &*conn,
+                AuditAction::DismissVoteRing,
+                &self.id.to_string(),
+                reason,
+                snapshot,
+            )?;
+            Ok(())
+        })
+    }
+}
+
+impl Cluster {
+    /// Identifies the cluster by its members, such that the
+    /// same ring is recognized when it is found again.
+    fn key(&self) -> String {
+        let mut ids: Vec<String> = self.members.iter().map(ToString::to_string).collect();
+        ids.sort();
+        ids.join(",")
+    }
+
+    fn merge(&mut self, other: Cluster) {
+        self.members.extend(other.members);
+        self.shared_ip |= other.shared_ip;
+        self.invite_tree |= other.invite_tree;
+        self.single_submitter |= other.single_submitter;
+    }
+
+    fn add_signal(&mut self, signal: Signal) {
+        match signal {
+            Signal::SharedIp => self.shared_ip = true,
+            Signal::InviteTree => self.invite_tree = true,
+            Signal::SingleSubmitter => self.single_submitter = true,
+        }
+    }
+}
+
+/// Loads counted upvotes, optionally only those given after
+/// `since`, or by the given users.
+fn load_votes(
+    conn: &SqliteConnection,
+    since: Option<NaiveDateTime>,
+    users: Option<Vec<UserID>>,
+) -> Result<Vec<Vote>> {
+    let mut query = url_upvotes::table
+        .inner_join(urls::table)
+        .filter(url_upvotes::dsl::discounted_at.is_null())
+        .select((
+            url_upvotes::dsl::url_id,
+            url_upvotes::dsl::user_id,
+            urls::dsl::created_by,
+        ))
+        .into_boxed();
+    if let Some(since) = since {
+        query = query.filter(url_upvotes::dsl::created_at.gt(since));
+    }
+    if let Some(users) = users {
+        query = query.filter(url_upvotes::dsl::user_id.eq_any(users));
+    }
+    let votes: Vec<(UrlID, UserID, UserID)> = query.load(conn)?;
+    Ok(votes
+        .into_iter()
+        .map(|(url_id, user_id, submitter)| Vote {
+            url_id,
+            user_id,
+            submitter,
+        })
+        .collect())
+}
+
+/// Links accounts which upvoted many of the same submissions and
+/// share an IP address or invite, and accounts which upvoted almost
+/// only one submitter. Linked accounts are grouped into clusters.
+fn find_clusters(
+    votes: &[Vote],
+    ips: &HashMap<UserID, HashSet<String>>,
+    inviters: &HashMap<UserID, UserID>,
+) -> Vec<Cluster> {
+    let mut by_user: HashMap<UserID, usize> = HashMap::new();
+    let mut by_url: HashMap<UrlID, Vec<UserID>> = HashMap::new();
+    let mut by_submitter: HashMap<(UserID, UserID), usize> = HashMap::new();
+    for vote in votes {
+        *by_user.entry(vote.user_id).or_default() += 1;
+        by_url.entry(vote.url_id).or_default().push(vote.user_id);
+        if vote.user_id != vote.submitter {
+            *by_submitter
+                .entry((vote.user_id, vote.submitter))
+                .or_default() += 1;
+        }
+    }
+
+    let mut shared: HashMap<(UserID, UserID), usize> = HashMap::new();
+    for voters in by_url.values() {
+        if voters.len() > MAX_SHARED_VOTERS {
+            continue;
+        }
+        for (idx, a) in voters.iter().enumerate() {
+            for b in &voters[idx + 1..] {
+                let pair = if a.to_string() < b.to_string() {
+                    (*a, *b)
+                } else {
+                    (*b, *a)
+                };
+                *shared.entry(pair).or_default() += 1;
+            }
+        }
+    }
+
+    let mut edges = vec![];
+    for ((a, b), count) in shared {
+        let fewer = by_user[&a].min(by_user[&b]);
+        if count < MIN_RING_UPVOTES || (count as f64) < MIN_SHARED_SHARE * fewer as f64 {
+            continue;
+        }
+        let shared_ip = match (ips.get(&a), ips.get(&b)) {
+            (Some(a), Some(b)) => !a.is_disjoint(b),
+            _ => false,
+        };
+        if shared_ip {
+            edges.push((a, b, Signal::SharedIp));
+        }
+        let invited = inviters.get(&a) == Some(&b)
+            || inviters.get(&b) == Some(&a)
+            || (inviters.contains_key(&a) && inviters.get(&a) == inviters.get(&b));
+        if invited {
+            edges.push((a, b, Signal::InviteTree));
+        }
+    }
+    for ((voter, submitter), count) in by_submitter {
+        let total = by_user[&voter];
+        if count >= MIN_RING_UPVOTES && count as f64 >= MIN_SUBMITTER_SHARE * total as f64 {
+            edges.push((voter, submitter, Signal::SingleSubmitter));
+        }
+    }
+
+    let mut clusters: Vec<Cluster> = vec![];
+    for (a, b, signal) in edges {
+        let linked: Vec<usize> = clusters
+            .iter()
+            .enumerate()
+            .filter(|(_, cluster)| cluster.members.contains(&a) || cluster.members.contains(&b))
+            .map(|(idx, _)| idx)
+            .collect();
+        let mut cluster = Cluster::default();
+        for idx in linked.into_iter().rev() {
+            cluster.merge(clusters.swap_remove(idx));
+        }
+        cluster.members.insert(a);
+        cluster.members.insert(b);
+        cluster.add_signal(signal);
+        clusters.push(cluster);
+    }
+    clusters
+}
+
+/// The upvotes members gave to submissions from other members,
+/// or to submissions which other members upvoted as well.
+fn ring_votes(votes: &[Vote], members: &HashSet<UserID>) -> Vec<(UrlID, UserID)> {
+    let member_votes: Vec<&Vote> = votes
+        .iter()
+        .filter(|vote| members.contains(&vote.user_id))
+        .collect();
+    let mut voters: HashMap<UrlID, usize> = HashMap::new();
+    for vote in &member_votes {
+        *voters.entry(vote.url_id).or_default() += 1;
+    }
+    member_votes
+        .into_iter()
+        .filter(|vote| members.contains(&vote.submitter) || voters[&vote.url_id] > 1)
+        .map(|vote| (vote.url_id, vote.user_id))
+        .collect()
+}
+
+async fn check_view_permissions(ctx: &Context) -> Result<()> {
+    ctx.user()
+        .await?
+        .check_permissions(ctx, |perm| perm.view_vote_rings())
+        .await
+}
+
+async fn check_discount_permissions(ctx: &Context) -> Result<()> {
+    ctx.user()
+        .await?
+        .check_permissions(ctx, |perm| perm.discount_votes())
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ids: &[UserID]) -> HashSet<UserID> {
+        ids.iter().copied().collect()
+    }
+
+    fn votes(url_ids: &[UrlID], users: &[UserID], submitter: UserID) -> Vec<Vote> {
+        let mut votes = vec![];
+        for url_id in url_ids {
+            for user_id in users {
+                votes.push(Vote {
+                    url_id: *url_id,
+                    user_id: *user_id,
+                    submitter,
+                });
+            }
+        }
+        votes
+    }
+
+    #[test]
+    fn test_find_clusters() {
+        let users: Vec<UserID> = (0..6).map(|_| UserID::new()).collect();
+        let url_ids: Vec<UrlID> = (0..MIN_RING_UPVOTES).map(|_| UrlID::new()).collect();
+
+        // four users upvote the same submissions, which are
+        // spread across two submitters
+        let other_url_ids: Vec<UrlID> = (0..MIN_RING_UPVOTES).map(|_| UrlID::new()).collect();
+        let mut all = votes(&url_ids, &users[0..4], users[5]);
+        all.extend(votes(&other_url_ids, &users[0..4], users[4]));
+
+        // without a shared IP or invite, upvoting the
+        // same submissions is not suspicious
+        let mut ips = HashMap::new();
+        let inviters = HashMap::new();
+        assert!(find_clusters(&all, &ips, &inviters).is_empty());
+
+        let ip = |ip: &str| -> HashSet<String> { vec![ip.to_string()].into_iter().collect() };
+        ips.insert(users[0], ip("10.0.0.1"));
+        ips.insert(users[1], ip("10.0.0.1"));
+        ips.insert(users[2], ip("10.0.0.2"));
+        let clusters = find_clusters(&all, &ips, &inviters);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, set(&users[0..2]));
+        assert!(clusters[0].shared_ip);
+        assert!(!clusters[0].invite_tree);
+        assert!(!clusters[0].single_submitter);
+
+        // clusters which are linked by different signals are merged
+        let inviters = vec![(users[2], users[1])].into_iter().collect();
+        let clusters = find_clusters(&all, &ips, &inviters);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, set(&users[0..3]));
+        assert!(clusters[0].shared_ip);
+        assert!(clusters[0].invite_tree);
+
+        // upvoting popular submissions does not link accounts
+        let crowd: Vec<UserID> = (0..MAX_SHARED_VOTERS).map(|_| UserID::new()).collect();
+        let mut popular = vec![];
+        for url_id in &url_ids {
+            let submitter = UserID::new();
+            popular.extend(votes(&[*url_id], &users[0..2], submitter));
+            popular.extend(votes(&[*url_id], &crowd, submitter));
+        }
+        assert!(find_clusters(&popular, &ips, &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_find_single_submitter() {
+        let voter = UserID::new();
+        let submitter = UserID::new();
+        let url_ids: Vec<UrlID> = (0..MIN_RING_UPVOTES).map(|_| UrlID::new()).collect();
+        let all = votes(&url_ids, &[voter], submitter);
+
+        let clusters = find_clusters(&all, &HashMap::new(), &HashMap::new());
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, set(&[voter, submitter]));
+        assert!(clusters[0].single_submitter);
+        assert_eq!(
+            ring_votes(&all, &clusters[0].members).len(),
+            MIN_RING_UPVOTES
+        );
+
+        // too few upvotes are not suspicious
+        let clusters = find_clusters(&all[1..], &HashMap::new(), &HashMap::new());
+        assert!(clusters.is_empty());
+    }
+
+    #[test]
+    fn test_ring_votes() {
+        let members: Vec<UserID> = (0..2).map(|_| UserID::new()).collect();
+        let outsider = UserID::new();
+        let shared = UrlID::new();
+        let single = UrlID::new();
+        let all = vec![
+            Vote {
+                url_id: shared,
+                user_id: members[0],
+                submitter: outsider,
+            },
+            Vote {
+                url_id: shared,
+                user_id: members[1],
+                submitter: outsider,
+            },
+            Vote {
+                url_id: shared,
+                user_id: outsider,
+                submitter: outsider,
+            },
+            Vote {
+                url_id: single,
+                user_id: members[0],
+                submitter: outsider,
+            },
+        ];
+        // only the upvotes for the submission which both
+        // members upvoted are ring upvotes
+        let found = ring_votes(&all, &set(&members));
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&(shared, members[0])));
+        assert!(found.contains(&(shared, members[1])));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::config::RankingConfig;
+use chrono::NaiveDateTime;
+use diesel::sql_types::{BigInt, Double, Timestamp};
+use diesel::{QueryResult, SqliteConnection};
+
+sql_function! {
+    /// Time-decayed ranking score of a submission. This needs to be
+    /// registered on every connection, see [`register`](register).
+    fn rank_score(upvotes: BigInt, comments: BigInt, created_at: Timestamp, now: Timestamp) -> Double;
+}
+
+/// Compute the ranking score for a submission, given it's
+/// number of upvotes, comments, and age in hours.
+pub fn score(conf: &RankingConfig, upvotes: i64, comments: i64, age_hours: f64) -> f64 {
+    let points = upvotes as f64 + conf.comment_weight() * comments as f64 + 1.0;
+    let age = age_hours.max(0.0) + conf.age_offset_hours();
+    points / age.powf(conf.gravity())
+}
+
+/// Register the `rank_score` SQL function on the given
+/// connection.
+pub fn register(conn: &SqliteConnection, conf: RankingConfig) -> QueryResult<()> {
+    rank_score::register_impl(
+        conn,
+        move |upvotes: i64, comments: i64, created_at: NaiveDateTime, now: NaiveDateTime| {
+            let age_hours = (now - created_at).num_seconds() as f64 / 3600.0;
+            score(&conf, upvotes, comments, age_hours)
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_decays_with_age() {
+        let conf = RankingConfig::default();
+        let fresh = score(&conf, 2, 0, 2.0);
+        let old = score(&conf, 3, 0, 72.0);
+        assert!(fresh > old);
+
+        let older = score(&conf, 3, 0, 24.0 * 8.0);
+        assert!(old > older);
+        assert!(older > 0.0);
+    }
+
+    #[test]
+    fn test_score_counts_comments() {
+        let conf = RankingConfig::default();
+        let quiet = score(&conf, 2, 0, 5.0);
+        let discussed = score(&conf, 2, 4, 5.0);
+        let upvoted = score(&conf, 4, 0, 5.0);
+        assert!(discussed > quiet);
+        assert!(upvoted >= discussed);
+    }
+}
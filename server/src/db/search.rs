@@ -67,7 +67,7 @@ impl SearchIndex {
                 writer.add_document(doc! {
                     self.f_id => url.id().as_str().as_bytes(),
                     self.f_title => url.title().unwrap_or(""),
-                    self.f_description => url.description().unwrap_or(""),
+                    self.f_description => url.description().or_else(|| url.text()).unwrap_or(""),
                 });
             }
             writer.commit()?;
@@ -0,0 +1,172 @@
+use crate::db::id::UrlID;
+use crate::Config;
+use anyhow::{anyhow, Result};
+use image::io::Reader;
+use image::{DynamicImage, ImageFormat, ImageOutputFormat};
+use std::collections::HashMap;
+use std::io::{Cursor, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Images with more pixels are refused. Decoded images
+/// take up to four bytes per pixel, i.e. 64 MB at most.
+const MAX_SOURCE_PIXELS: u64 = 16_000_000;
+const THUMBNAIL_SIZE: u32 = 800;
+const THUMBNAIL_QUALITY: u8 = 80;
+const SOURCE_FORMATS: &[ImageFormat] = &[
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::Gif,
+    ImageFormat::WebP,
+];
+
+#[derive(Clone)]
+enum Storage {
+    Dir(PathBuf),
+    Memory(Arc<Mutex<HashMap<UrlID, Vec<u8>>>>),
+}
+
+/// Caches resized copies of the images linked pages
+/// declare, such that they can be served without
+/// loading them from third parties.
+#[derive(Clone)]
+pub struct Thumbnails {
+    storage: Storage,
+}
+
+impl Thumbnails {
+    /// Opens the thumbnail cache. If no directory is
+    /// configured, thumbnails are kept in memory.
+    pub async fn new(conf: &Config) -> Result<Self> {
+        let storage = if let Some(path) = conf.thumbnails() {
+            tokio::fs::create_dir_all(path).await?;
+            Storage::Dir(path.to_path_buf())
+        } else {
+            Storage::Memory(Default::default())
+        };
+        Ok(Self { storage })
+    }
+
+    /// Creates a thumbnail for the given URL from the downloaded
+    /// image, replacing any existing thumbnail. Thumbnails are
+    /// JPEGs which fit into `THUMBNAIL_SIZE` pixels squared.
+    pub async fn store(&self, url_id: UrlID, image: Vec<u8>) -> Result<()> {
+        let thumbnail = tokio::task::spawn_blocking(move || resize(&image)).await??;
+        match &self.storage {
+            Storage::Dir(dir) => tokio::fs::write(thumbnail_path(dir, url_id), thumbnail).await?,
+            Storage::Memory(map) => {
+                map.lock().await.insert(url_id, thumbnail);
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the thumbnail of the given URL, if
+    /// there is one.
+    pub async fn load(&self, url_id: UrlID) -> Result<Option<Vec<u8>>> {
+        match &self.storage {
+            Storage::Dir(dir) => match tokio::fs::read(thumbnail_path(dir, url_id)).await {
+                Ok(thumbnail) => Ok(Some(thumbnail)),
+                Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err.into()),
+            },
+            Storage::Memory(map) => Ok(map.lock().await.get(&url_id).cloned()),
+        }
+    }
+
+    /// Removes the thumbnail of the given URL.
+    pub async fn delete(&self, url_id: UrlID) -> Result<()> {
+        match &self.storage {
+            Storage::Dir(dir) => match tokio::fs::remove_file(thumbnail_path(dir, url_id)).await {
+                Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+                _ => Ok(()),
+            },
+            Storage::Memory(map) => {
+                map.lock().await.remove(&url_id);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn resize(image: &[u8]) -> Result<Vec<u8>> {
+    let reader = Reader::new(Cursor::new(image)).with_guessed_format()?;
+    match reader.format() {
+        Some(format) if SOURCE_FORMATS.contains(&format) => (),
+        _ => return Err(anyhow!("Unsupported image format")),
+    }
+    // check the size before decoding, to avoid
+    // allocating huge buffers
+    let (width, height) = reader.into_dimensions()?;
+    if u64::from(width) * u64::from(height) > MAX_SOURCE_PIXELS {
+        return Err(anyhow!("Image dimensions are too large"));
+    }
+
+    let image = Reader::new(Cursor::new(image))
+        .with_guessed_format()?
+        .decode()?;
+    let thumbnail = if width > THUMBNAIL_SIZE || height > THUMBNAIL_SIZE {
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    } else {
+        image
+    };
+    // JPEGs can not store transparency
+    let thumbnail = DynamicImage::ImageRgb8(thumbnail.into_rgb8());
+
+    let mut jpeg = vec![];
+    thumbnail.write_to(&mut jpeg, ImageOutputFormat::Jpeg(THUMBNAIL_QUALITY))?;
+    Ok(jpeg)
+}
+
+fn thumbnail_path(dir: &Path, url_id: UrlID) -> PathBuf {
+    // ids are url-safe, and can be used as file names
+    dir.join(format!("{}.jpg", url_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, GrayImage, RgbaImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(width, height));
+        let mut png = vec![];
+        image.write_to(&mut png, ImageOutputFormat::Png).unwrap();
+        png
+    }
+
+    #[test]
+    fn test_resize() {
+        let thumbnail = resize(&png(1600, 400)).unwrap();
+        let thumbnail = image::load_from_memory_with_format(&thumbnail, ImageFormat::Jpeg).unwrap();
+        assert_eq!(thumbnail.dimensions(), (800, 200));
+
+        let thumbnail = resize(&png(100, 50)).unwrap();
+        let thumbnail = image::load_from_memory_with_format(&thumbnail, ImageFormat::Jpeg).unwrap();
+        assert_eq!(thumbnail.dimensions(), (100, 50));
+
+        // too large images are refused before decoding
+        let image = DynamicImage::ImageLuma8(GrayImage::new(4001, 4000));
+        let mut large = vec![];
+        image.write_to(&mut large, ImageOutputFormat::Png).unwrap();
+        assert!(resize(&large).is_err());
+
+        assert!(resize(b"<svg></svg>").is_err());
+        assert!(resize(&[0u8; 2048]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_thumbnails() {
+        let thumbnails = Thumbnails::new(&Config::test()).await.unwrap();
+        let url_id = UrlID::new();
+        assert_eq!(thumbnails.load(url_id).await.unwrap(), None);
+
+        thumbnails.store(url_id, png(10, 10)).await.unwrap();
+        assert!(thumbnails.load(url_id).await.unwrap().is_some());
+        assert!(thumbnails.store(url_id, vec![0u8; 16]).await.is_err());
+
+        thumbnails.delete(url_id).await.unwrap();
+        assert_eq!(thumbnails.load(url_id).await.unwrap(), None);
+    }
+}
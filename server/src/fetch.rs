@@ -0,0 +1,551 @@
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use reqwest::{Client, Response, StatusCode, Url};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; Urlsbot/0.1.0; +https://urls.fyi/bot.html)";
+const MAX_REDIRECTS: usize = 10;
+const MAX_BODY_BYTES: usize = 512 * 1024;
+const MAX_IMAGE_BYTES: usize = 4 * 1024 * 1024;
+const HTML_CONTENT_TYPES: &[&str] = &["text/html", "application/xhtml+xml"];
+const IMAGE_CONTENT_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// A page fetched from a user submitted URL.
+#[derive(Debug)]
+pub struct Page {
+    url: Url,
+    status: StatusCode,
+    redirects: Vec<(Url, StatusCode)>,
+    html: Option<Vec<u8>>,
+}
+
+impl Page {
+    /// The URL the page was loaded from, after
+    /// following any redirects.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The redirects which were followed to reach the page,
+    /// in order. Each entry is a URL which was requested and
+    /// the redirect status it responded with.
+    pub fn redirects(&self) -> &[(Url, StatusCode)] {
+        &self.redirects
+    }
+
+    /// The (possibly truncated) body of the page. This is
+    /// only available for successful responses with an
+    /// HTML content type.
+    pub fn html(&self) -> Option<&[u8]> {
+        self.html.as_deref()
+    }
+}
+
+/// HTTP client for loading user submitted URLs. In contrast
+/// to [`Context::http_client`](crate::Context::http_client), the
+/// fetcher refuses to connect to private and loopback addresses
+/// (also when redirected there), and limits the size of the
+/// response bodies it reads.
+#[derive(Debug, Clone)]
+pub struct Fetcher {
+    client: Client,
+    max_body_bytes: usize,
+    max_image_bytes: usize,
+    allowed_addrs: Vec<SocketAddr>,
+}
+
+impl Fetcher {
+    pub fn new() -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(30))
+            // redirects are followed manually, such that
+            // every target can be checked
+            .redirect(reqwest::redirect::Policy::none())
+            // a proxy would resolve the host itself
+            .no_proxy()
+            .dns_resolver(Arc::new(PublicResolver))
+            .gzip(true)
+            .brotli(true)
+            .build()?;
+        Ok(Self {
+            client,
+            max_body_bytes: MAX_BODY_BYTES,
+            max_image_bytes: MAX_IMAGE_BYTES,
+            allowed_addrs: vec![],
+        })
+    }
+
+    /// Limit the number of bytes read from a response body.
+    pub fn max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Limit the size of images which are loaded. Larger
+    /// images are refused, instead of being truncated.
+    pub fn max_image_bytes(mut self, max_image_bytes: usize) -> Self {
+        self.max_image_bytes = max_image_bytes;
+        self
+    }
+
+    /// Allow connecting to the given address, even if it is not
+    /// public. This exists to test against a local server, and is
+    /// probably not what you want.
+    pub fn allow_addr(mut self, addr: SocketAddr) -> Self {
+        self.allowed_addrs.push(addr);
+        self
+    }
+
+    /// Load the page at the given URL, following redirects.
+    pub async fn get(&self, url: &str) -> Result<Page> {
+        let (url, redirects, resp) = self.follow(url).await?;
+        self.read_page(url, redirects, resp).await
+    }
+
+    /// Load the image at the given URL, following redirects. This
+    /// fails if the response is not an image of a supported type,
+    /// or if the image is too large.
+    pub async fn get_image(&self, url: &str) -> Result<Vec<u8>> {
+        let (_, _, resp) = self.follow(url).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("Image responded with {}", resp.status()));
+        }
+        let is_image = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(is_image_content_type)
+            .unwrap_or(false);
+        if !is_image {
+            return Err(anyhow!("Unsupported image content type"));
+        }
+        let too_large = |len: usize| len > self.max_image_bytes;
+        if resp
+            .content_length()
+            .map_or(false, |len| too_large(len as usize))
+        {
+            return Err(anyhow!("Image is too large"));
+        }
+
+        let mut body = vec![];
+        let mut stream = resp.bytes_stream();
+        while let Some(part) = stream.next().await {
+            body.extend_from_slice(&part?);
+            if too_large(body.len()) {
+                return Err(anyhow!("Image is too large"));
+            }
+        }
+        Ok(body)
+    }
+
+    /// Send a request to the given URL, following redirects. Returns the
+    /// final URL, the redirects which were followed, and the response.
+    async fn follow(&self, url: &str) -> Result<(Url, Vec<(Url, StatusCode)>, Response)> {
+        let mut url = Url::parse(url)?;
+        let mut redirects = vec![];
+        for _ in 0..=MAX_REDIRECTS {
+            self.check_url(&url)?;
+            let resp = self.client.get(url.clone()).send().await?;
+            if let Some(addr) = resp.remote_addr() {
+                self.check_addr(addr)?;
+            }
+
+            let location = resp
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok());
+            match location {
+                Some(location) if resp.status().is_redirection() => {
+                    let next = url.join(location)?;
+                    redirects.push((url, resp.status()));
+                    url = next;
+                }
+                _ => return Ok((url, redirects, resp)),
+            }
+        }
+        Err(anyhow!("Too many redirects"))
+    }
+
+    async fn read_page(
+        &self,
+        url: Url,
+        redirects: Vec<(Url, StatusCode)>,
+        resp: Response,
+    ) -> Result<Page> {
+        let status = resp.status();
+        let is_html = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(is_html_content_type)
+            .unwrap_or(false);
+
+        let html = if status.is_success() && is_html {
+            let mut body = vec![];
+            let mut stream = resp.bytes_stream();
+            while let Some(part) = stream.next().await {
+                let part = part?;
+                let remaining = self.max_body_bytes - body.len();
+                if part.len() >= remaining {
+                    body.extend_from_slice(&part[..remaining]);
+                    break;
+                }
+                body.extend_from_slice(&part);
+            }
+            Some(body)
+        } else {
+            None
+        };
+
+        Ok(Page {
+            url,
+            status,
+            redirects,
+            html,
+        })
+    }
+
+    /// Checks that the URL may be requested. Hosts which are IP
+    /// addresses are checked here, host names are checked when
+    /// they are resolved, see [`PublicResolver`].
+    fn check_url(&self, url: &Url) -> Result<()> {
+        match url.scheme() {
+            "http" | "https" => (),
+            scheme => return Err(anyhow!("Unsupported URL scheme {}", scheme)),
+        }
+        let host = url.host_str().ok_or_else(|| anyhow!("Malformed URL"))?;
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| anyhow!("Malformed URL"))?;
+
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        match host.parse::<IpAddr>() {
+            Ok(ip) => self.check_addr(SocketAddr::new(ip, port)),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn check_addr(&self, addr: SocketAddr) -> Result<()> {
+        if is_public_ip(addr.ip()) || self.allowed_addrs.contains(&addr) {
+            Ok(())
+        } else {
+            Err(anyhow!("Refusing to connect to {}", addr.ip()))
+        }
+    }
+}
+
+/// Resolves host names for the [`Fetcher`], refusing hosts which
+/// resolve to any address that is not public. The client connects
+/// to exactly the addresses checked here, such that a host can not
+/// resolve to a private address between the check and the request
+/// (DNS rebinding).
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            resolve_public(name.as_str())
+                .await
+                .map_err(|err| -> Box<dyn std::error::Error + Send + Sync> { err.into() })
+        })
+    }
+}
+
+async fn resolve_public(host: &str) -> Result<Addrs> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(anyhow!("Refusing to connect to {}", addr.ip()));
+    }
+    Ok(Box::new(addrs.into_iter()))
+}
+
+fn is_html_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    HTML_CONTENT_TYPES
+        .iter()
+        .any(|html| mime.eq_ignore_ascii_case(html))
+}
+
+fn is_image_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    IMAGE_CONTENT_TYPES
+        .iter()
+        .any(|image| mime.eq_ignore_ascii_case(image))
+}
+
+/// Determine if the address is reachable on the public
+/// internet.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // this network (0.0.0.0/8)
+        || a == 0
+        // shared address space (100.64.0.0/10)
+        || (a == 100 && b & 0xc0 == 64)
+        // protocol assignments (192.0.0.0/24)
+        || (a == 192 && b == 0 && c == 0)
+        // benchmarking (198.18.0.0/15)
+        || (a == 198 && b & 0xfe == 18)
+        // reserved (240.0.0.0/4)
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    let ipv4 = |high: u16, low: u16| {
+        let [a, b] = high.to_be_bytes();
+        let [c, d] = low.to_be_bytes();
+        Ipv4Addr::new(a, b, c, d)
+    };
+    let embeds_ipv4 =
+        // IPv4-mapped (::ffff:0:0/96) and IPv4-compatible (::/96) addresses
+        (segments[..5].iter().all(|seg| *seg == 0) && matches!(segments[5], 0 | 0xffff))
+        // IPv4-translated (::ffff:0:0:0/96)
+        || (segments[..4].iter().all(|seg| *seg == 0) && segments[4..6] == [0xffff, 0])
+        // NAT64 (64:ff9b::/96)
+        || (segments[..2] == [0x64, 0xff9b] && segments[2..6].iter().all(|seg| *seg == 0));
+    if embeds_ipv4 {
+        return is_public_ipv4(ipv4(segments[6], segments[7]));
+    }
+    // 6to4 (2002::/16) embeds the IPv4 address of the relay
+    if segments[0] == 0x2002 && !is_public_ipv4(ipv4(segments[1], segments[2])) {
+        return false;
+    }
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // unique local (fc00::/7)
+        || segments[0] & 0xfe00 == 0xfc00
+        // link local (fe80::/10)
+        || segments[0] & 0xffc0 == 0xfe80
+        // deprecated site local (fec0::/10)
+        || segments[0] & 0xffc0 == 0xfec0
+        // documentation (2001:db8::/32)
+        || (segments[0] == 0x2001 && segments[1] == 0xdb8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Filter;
+
+    /// Serve some pages from a local stand-in server.
+    fn stand_in_server() -> SocketAddr {
+        let html = warp::path("page").map(|| {
+            warp::reply::with_header(
+                "<html><head><title>Hello</title></head></html>",
+                "content-type",
+                "text/html; charset=utf-8",
+            )
+        });
+        let large = warp::path("large")
+            .map(|| warp::reply::with_header("x".repeat(4096), "content-type", "text/html"));
+        let json = warp::path("json").map(|| warp::reply::json(&"<title>Hello</title>"));
+        let redirect = warp::path("redirect")
+            .map(|| warp::redirect::temporary(warp::http::Uri::from_static("/page")));
+        let private = warp::path("private").map(|| {
+            warp::redirect::temporary(warp::http::Uri::from_static(
+                "http://169.254.169.254/latest/meta-data",
+            ))
+        });
+        let loop_ = warp::path("loop")
+            .map(|| warp::redirect::temporary(warp::http::Uri::from_static("/loop")));
+
+        let image = warp::path("image")
+            .map(|| warp::reply::with_header(vec![0u8; 2048], "content-type", "image/png"));
+
+        let routes = html
+            .or(large)
+            .or(json)
+            .or(image)
+            .or(redirect)
+            .or(private)
+            .or(loop_);
+        let (addr, server) = warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        addr
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        let public = [
+            "93.184.216.34",
+            "1.1.1.1",
+            "2606:4700:4700::1111",
+            "64:ff9b::5db8:d822",
+            "2002:5db8:d822::1",
+        ];
+        for ip in public {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+
+        let private = [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.5.4",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:10.0.0.1",
+            "::127.0.0.1",
+            "::10.0.0.1",
+            "64:ff9b::127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+            "::ffff:0:127.0.0.1",
+            "::ffff:0:a00:1",
+            "2002:7f00:1::1",
+            "2002:c0a8:101::1",
+            "fec0::1",
+        ];
+        for ip in private {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_is_html_content_type() {
+        assert!(is_html_content_type("text/html"));
+        assert!(is_html_content_type("text/HTML; charset=utf-8"));
+        assert!(is_html_content_type("application/xhtml+xml"));
+        assert!(!is_html_content_type("application/json"));
+        assert!(!is_html_content_type("image/png"));
+    }
+
+    #[test]
+    fn test_is_image_content_type() {
+        assert!(is_image_content_type("image/png"));
+        assert!(is_image_content_type("IMAGE/JPEG"));
+        assert!(!is_image_content_type("image/svg+xml"));
+        assert!(!is_image_content_type("text/html"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_image() {
+        let addr = stand_in_server();
+        let fetcher = Fetcher::new().unwrap().allow_addr(addr);
+        let image = fetcher
+            .get_image(&format!("http://{}/image", addr))
+            .await
+            .unwrap();
+        assert_eq!(image.len(), 2048);
+        assert!(fetcher
+            .get_image(&format!("http://{}/page", addr))
+            .await
+            .is_err());
+        assert!(fetcher
+            .get_image(&format!("http://{}/missing", addr))
+            .await
+            .is_err());
+
+        let fetcher = fetcher.max_image_bytes(1000);
+        assert!(fetcher
+            .get_image(&format!("http://{}/image", addr))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refuses_private_hosts() {
+        let addr = stand_in_server();
+        let fetcher = Fetcher::new().unwrap();
+        assert!(fetcher.get(&format!("http://{}/page", addr)).await.is_err());
+        assert!(fetcher.get("http://localhost/").await.is_err());
+        assert!(fetcher.get("http://[::1]/").await.is_err());
+        assert!(fetcher.get("file:///etc/passwd").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_page() {
+        let addr = stand_in_server();
+        let fetcher = Fetcher::new().unwrap().allow_addr(addr);
+
+        let page = fetcher.get(&format!("http://{}/page", addr)).await.unwrap();
+        assert_eq!(page.status(), StatusCode::OK);
+        assert!(page.redirects().is_empty());
+        assert_eq!(
+            page.html().unwrap(),
+            b"<html><head><title>Hello</title></head></html>"
+        );
+
+        let page = fetcher.get(&format!("http://{}/json", addr)).await.unwrap();
+        assert_eq!(page.status(), StatusCode::OK);
+        assert!(page.html().is_none());
+
+        let page = fetcher
+            .get(&format!("http://{}/missing", addr))
+            .await
+            .unwrap();
+        assert_eq!(page.status(), StatusCode::NOT_FOUND);
+        assert!(page.html().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_limits_body_size() {
+        let addr = stand_in_server();
+        let fetcher = Fetcher::new()
+            .unwrap()
+            .allow_addr(addr)
+            .max_body_bytes(1000);
+        let page = fetcher
+            .get(&format!("http://{}/large", addr))
+            .await
+            .unwrap();
+        assert_eq!(page.html().unwrap().len(), 1000);
+    }
+
+    #[tokio::test]
+    async fn test_checks_redirects() {
+        let addr = stand_in_server();
+        let fetcher = Fetcher::new().unwrap().allow_addr(addr);
+
+        let page = fetcher
+            .get(&format!("http://{}/redirect", addr))
+            .await
+            .unwrap();
+        assert_eq!(page.url().as_str(), format!("http://{}/page", addr));
+        assert_eq!(
+            page.redirects(),
+            &[(
+                Url::parse(&format!("http://{}/redirect", addr)).unwrap(),
+                StatusCode::TEMPORARY_REDIRECT
+            )]
+        );
+        assert!(page.html().is_some());
+
+        assert!(fetcher
+            .get(&format!("http://{}/private", addr))
+            .await
+            .is_err());
+        assert!(fetcher.get(&format!("http://{}/loop", addr)).await.is_err());
+    }
+}
@@ -1,15 +1,34 @@
 use super::viewer::Viewer;
-use crate::db::id::{CommentID, LoginID, UrlID};
+use crate::db::id::{CommentID, LoginID, UrlID, UserID, VoteRingID};
 use crate::db::models::{
-    Comment, Invite, Login, NewCommentInput, NewUrlInput, NewUserInput, Permission, Role,
-    UpdateUserInput, Url, User,
+    Comment, DomainAction, DomainRule, Flag, FlagReason, Invite, Login, NewCommentInput,
+    NewTextPostInput, NewUrlInput, NewUserInput, Permission, QuotaExceeded, Role, Tag,
+    UpdateUrlInput, UpdateUserInput, Url, User, VoteRing,
 };
 use crate::Context;
-use juniper::{graphql_object, FieldResult, GraphQLObject};
+use juniper::{graphql_object, graphql_value, FieldError, FieldResult, GraphQLObject};
 use validator::Validate;
 
 pub struct Mutation;
 
+/// Reports an exceeded quota with the remaining quota and
+/// the time it resets as error extensions, so clients can
+/// tell users when to try again.
+fn quota_error(err: anyhow::Error) -> FieldError {
+    match err.downcast_ref::<QuotaExceeded>() {
+        Some(quota) => FieldError::new(
+            quota,
+            graphql_value!({
+                "code": "QUOTA_EXCEEDED",
+                "remainingHour": (quota.remaining_hour() as i32),
+                "remainingDay": (quota.remaining_day() as i32),
+                "resetsAt": (quota.resets_at().to_rfc3339()),
+            }),
+        ),
+        None => err.into(),
+    }
+}
+
 /// A value mutations can return, if there
 /// is no other interesting object that could
 /// be returned.
@@ -49,13 +68,10 @@ impl Mutation {
         ctx: &Context,
         permission: Permission,
         email: String,
+        reason: Option<String>,
     ) -> FieldResult<User> {
-        ctx.user()
-            .await?
-            .check_permissions(ctx, |perm| perm.modify_user_roles())
-            .await?;
         let user = User::find_by_email(ctx, &email).await?;
-        Role::create(ctx, user.id(), permission).await?;
+        Role::grant(ctx, &user, permission, reason.as_deref()).await?;
         Ok(user)
     }
 
@@ -65,13 +81,10 @@ impl Mutation {
         ctx: &Context,
         permission: Permission,
         email: String,
+        reason: Option<String>,
     ) -> FieldResult<User> {
-        ctx.user()
-            .await?
-            .check_permissions(ctx, |perm| perm.modify_user_roles())
-            .await?;
         let user = User::find_by_email(ctx, &email).await?;
-        Role::delete_by_permission(ctx, user.id(), permission).await?;
+        Role::revoke(ctx, &user, permission, reason.as_deref()).await?;
         Ok(user)
     }
 
@@ -105,44 +118,262 @@ impl Mutation {
         Ok(Invite::create(ctx, &user).await?)
     }
 
-    /// Create a new URL and crawls the associated HTML page for
-    /// meta data.
+    /// Submit a new URL. The associated HTML page is crawled for meta
+    /// data in the background, see the `crawlState` field on `Url`.
     async fn submit_url(ctx: &Context, input: NewUrlInput) -> FieldResult<Url> {
-        Ok(Url::create(ctx, input, ctx.user_id()?).await?)
+        Url::create(ctx, input, ctx.user_id()?)
+            .await
+            .map_err(quota_error)
+    }
+
+    /// Submit a new text post, i.e. a discussion which does not link
+    /// to an external page. The body is rendered as markdown.
+    async fn submit_text_post(ctx: &Context, input: NewTextPostInput) -> FieldResult<Url> {
+        Url::create_text_post(ctx, input, ctx.user_id()?)
+            .await
+            .map_err(quota_error)
     }
 
     /// Deletes a submitted URL. URLs can only be deleted by moderators
-    /// or the user who originally submitted them.
-    async fn delete_url(ctx: &Context, url: UrlID) -> FieldResult<Url> {
+    /// or the user who originally submitted them. The optional reason
+    /// is recorded in the audit log.
+    async fn delete_url(ctx: &Context, url: UrlID, reason: Option<String>) -> FieldResult<Url> {
         let url = Url::find(ctx, url).await?;
-        url.delete(ctx).await?;
+        url.delete(ctx, reason.as_deref()).await?;
+        Ok(url)
+    }
+
+    /// Merge the duplicate submission `from` into the submission `into`.
+    /// Upvotes and comments are moved to `into`, and `from` is deleted.
+    /// Only moderators can merge submissions. The optional reason is
+    /// recorded in the audit log.
+    async fn merge_urls(
+        ctx: &Context,
+        from: UrlID,
+        into: UrlID,
+        reason: Option<String>,
+    ) -> FieldResult<Url> {
+        let from = Url::find(ctx, from).await?;
+        let into = Url::find(ctx, into).await?;
+        from.merge_into(ctx, &into, reason.as_deref()).await?;
+        Ok(Url::find(ctx, into.id()).await?)
+    }
+
+    /// Approve a submission which was held for moderation because
+    /// of a domain rule. Only moderators can approve submissions.
+    async fn approve_url(ctx: &Context, url: UrlID) -> FieldResult<Url> {
+        let mut url = Url::find(ctx, url).await?;
+        url.approve(ctx).await?;
         Ok(url)
     }
 
+    /// Decide how submissions from the given domain, and all its
+    /// subdomains, are treated. Replaces any existing rule for the
+    /// domain. Only administrators can manage domain rules.
+    async fn set_domain_rule(
+        ctx: &Context,
+        domain: String,
+        action: DomainAction,
+    ) -> FieldResult<DomainRule> {
+        Ok(DomainRule::set(ctx, &domain, action).await?)
+    }
+
+    /// Remove the rule for the given domain. Only administrators
+    /// can manage domain rules.
+    async fn delete_domain_rule(ctx: &Context, domain: String) -> FieldResult<DomainRule> {
+        let rule = DomainRule::find_by_domain(ctx, &domain).await?;
+        rule.delete(ctx).await?;
+        Ok(rule)
+    }
+
+    /// Edit the title or description of a submitted URL, e.g. to
+    /// replace an unhelpful crawled title. Omitted fields are kept,
+    /// and empty values restore the crawled values. URLs can only
+    /// be edited by moderators or the user who submitted them.
+    async fn update_url(ctx: &Context, url: UrlID, input: UpdateUrlInput) -> FieldResult<Url> {
+        let mut url = Url::find(ctx, url).await?;
+        url.update(ctx, input).await?;
+        Ok(url)
+    }
+
+    /// Replace the tags of a submitted URL. Tags can only be changed
+    /// by moderators or the user who originally submitted the URL.
+    async fn retag_url(ctx: &Context, url: UrlID, tags: Vec<String>) -> FieldResult<Url> {
+        let url = Url::find(ctx, url).await?;
+        url.set_tags(ctx, &tags).await?;
+        Ok(url)
+    }
+
+    /// Merge the tag `from` into the tag `into`. All URLs labeled
+    /// with `from` will be labeled with `into` instead, and `from`
+    /// is deleted. Only moderators can merge tags.
+    async fn merge_tags(ctx: &Context, from: String, into: String) -> FieldResult<Tag> {
+        let from = Tag::find_by_name(ctx, &from).await?;
+        let into = Tag::find_by_name(ctx, &into).await?;
+        from.merge(ctx, &into).await?;
+        Ok(into)
+    }
+
     /// Upvote the given URL as the viewer.
     async fn upvote_url(ctx: &Context, url: UrlID) -> FieldResult<Url> {
-        let url = Url::find(ctx, url).await?;
+        let mut url = Url::find(ctx, url).await?;
         url.upvote(ctx).await?;
         Ok(url)
     }
 
     /// Rescind a previous upvote for the given URL.
     async fn rescind_url_upvote(ctx: &Context, url: UrlID) -> FieldResult<Url> {
-        let url = Url::find(ctx, url).await?;
+        let mut url = Url::find(ctx, url).await?;
         url.rescind_upvote(ctx).await?;
         Ok(url)
     }
 
+    /// Save the given URL to the reading list of the viewer. Saved
+    /// URLs are private, and do not affect the ranking.
+    async fn save_url(ctx: &Context, url: UrlID) -> FieldResult<Url> {
+        let url = Url::find(ctx, url).await?;
+        url.save(ctx).await?;
+        Ok(url)
+    }
+
+    /// Remove the given URL from the reading list of the viewer.
+    async fn unsave_url(ctx: &Context, url: UrlID) -> FieldResult<Url> {
+        let url = Url::find(ctx, url).await?;
+        url.unsave(ctx).await?;
+        Ok(url)
+    }
+
+    /// Hide the given URL from the listings and search results of
+    /// the viewer.
+    async fn hide_url(ctx: &Context, url: UrlID) -> FieldResult<Url> {
+        let url = Url::find(ctx, url).await?;
+        url.hide(ctx).await?;
+        Ok(url)
+    }
+
+    /// Show a previously hidden URL to the viewer again.
+    async fn unhide_url(ctx: &Context, url: UrlID) -> FieldResult<Url> {
+        let url = Url::find(ctx, url).await?;
+        url.unhide(ctx).await?;
+        Ok(url)
+    }
+
+    /// Mute the given domain, such that submissions from it are no
+    /// longer listed for the viewer.
+    async fn mute_domain(ctx: &Context, domain: String) -> FieldResult<Viewer> {
+        Url::mute_domain(ctx, &domain).await?;
+        Ok(Viewer)
+    }
+
+    /// Unmute the given domain for the viewer.
+    async fn unmute_domain(ctx: &Context, domain: String) -> FieldResult<Viewer> {
+        Url::unmute_domain(ctx, &domain).await?;
+        Ok(Viewer)
+    }
+
+    /// Mute the given user, such that their submissions are no
+    /// longer listed for the viewer.
+    async fn mute_user(ctx: &Context, user: UserID) -> FieldResult<User> {
+        let user = User::find(ctx, user).await?;
+        user.mute(ctx).await?;
+        Ok(user)
+    }
+
+    /// Unmute the given user for the viewer.
+    async fn unmute_user(ctx: &Context, user: UserID) -> FieldResult<User> {
+        let user = User::find(ctx, user).await?;
+        user.unmute(ctx).await?;
+        Ok(user)
+    }
+
+    /// Issue a new secret token for the viewers private feed of saved
+    /// URLs. Feed links using the previous token stop working.
+    async fn reset_saved_feed_token(ctx: &Context) -> FieldResult<Viewer> {
+        let mut user = ctx.user().await?;
+        user.reset_feed_token(ctx).await?;
+        Ok(Viewer)
+    }
+
+    /// Flag the given URL for review by moderators, e.g.
+    /// because it is spam.
+    async fn flag_url(ctx: &Context, url: UrlID, reason: FlagReason) -> FieldResult<Url> {
+        let url = Url::find(ctx, url).await?;
+        Flag::create_for_url(ctx, url.id(), reason).await?;
+        Ok(url)
+    }
+
+    /// Dismiss all pending flags of the given URL, keeping the URL
+    /// listed. Only moderators can dismiss flags. To remove the URL
+    /// instead, use `deleteUrl`.
+    async fn dismiss_url_flags(ctx: &Context, url: UrlID) -> FieldResult<Url> {
+        let url = Url::find(ctx, url).await?;
+        Flag::dismiss_for_url(ctx, url.id()).await?;
+        Ok(url)
+    }
+
+    /// Discount the upvotes the members of a suspected voting ring
+    /// gave to each other, such that they no longer count towards
+    /// the ranking. Only administrators can discount upvotes. The
+    /// optional reason is recorded in the audit log.
+    async fn discount_vote_ring(
+        ctx: &Context,
+        ring: VoteRingID,
+        reason: Option<String>,
+    ) -> FieldResult<VoteRing> {
+        let mut ring = VoteRing::find(ctx, ring).await?;
+        ring.discount(ctx, reason.as_deref()).await?;
+        Ok(ring)
+    }
+
+    /// Dismiss a suspected voting ring, keeping its upvotes. Only
+    /// administrators can dismiss voting rings. The optional reason
+    /// is recorded in the audit log.
+    async fn dismiss_vote_ring(
+        ctx: &Context,
+        ring: VoteRingID,
+        reason: Option<String>,
+    ) -> FieldResult<VoteRing> {
+        let mut ring = VoteRing::find(ctx, ring).await?;
+        ring.dismiss(ctx, reason.as_deref()).await?;
+        Ok(ring)
+    }
+
     /// Comment on the given URL as the viewer.
     async fn comment(ctx: &Context, input: NewCommentInput) -> FieldResult<Comment> {
-        Ok(Comment::create(ctx, input).await?)
+        Comment::create(ctx, input).await.map_err(quota_error)
+    }
+
+    /// Flag the given comment for review by moderators, e.g.
+    /// because it is abusive.
+    async fn flag_comment(
+        ctx: &Context,
+        comment: CommentID,
+        reason: FlagReason,
+    ) -> FieldResult<Comment> {
+        let comment = Comment::find(ctx, comment).await?;
+        Flag::create_for_comment(ctx, comment.id(), reason).await?;
+        Ok(comment)
+    }
+
+    /// Dismiss all pending flags of the given comment, keeping the
+    /// comment. Only moderators can dismiss flags. To remove the
+    /// comment instead, use `deleteComment`.
+    async fn dismiss_comment_flags(ctx: &Context, comment: CommentID) -> FieldResult<Comment> {
+        let comment = Comment::find(ctx, comment).await?;
+        Flag::dismiss_for_comment(ctx, comment.id()).await?;
+        Ok(comment)
     }
 
     /// Delete the given comment. Only the original author, or a moderator
-    /// is allowed to delete comments.
-    async fn delete_comment(ctx: &Context, comment: CommentID) -> FieldResult<Comment> {
+    /// is allowed to delete comments. The optional reason is recorded in
+    /// the audit log.
+    async fn delete_comment(
+        ctx: &Context,
+        comment: CommentID,
+        reason: Option<String>,
+    ) -> FieldResult<Comment> {
         let mut comment = Comment::find(ctx, comment).await?;
-        comment.delete(ctx).await?;
+        comment.delete(ctx, reason.as_deref()).await?;
         Ok(comment)
     }
 }
@@ -0,0 +1,63 @@
+use crate::db::id::AuditEntryID;
+use crate::db::models::{AuditAction, AuditEntry, User};
+use crate::Context;
+use chrono::{DateTime, Utc};
+use juniper::{graphql_object, FieldResult};
+use juniper_relay_connection::RelayConnectionNode;
+
+impl RelayConnectionNode for AuditEntry {
+    type Cursor = AuditEntryID;
+
+    fn cursor(&self) -> Self::Cursor {
+        self.id()
+    }
+
+    fn connection_type_name() -> &'static str {
+        "AuditEntryConnection"
+    }
+
+    fn edge_type_name() -> &'static str {
+        "AuditEntryConnectionEdge"
+    }
+}
+
+#[graphql_object(context = Context)]
+impl AuditEntry {
+    /// A globally unique identifier for this
+    /// audit log entry.
+    fn id(&self) -> AuditEntryID {
+        self.id()
+    }
+
+    /// The time the action was performed.
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at()
+    }
+
+    /// The user who performed the action.
+    async fn actor(&self, ctx: &Context) -> FieldResult<User> {
+        Ok(self.actor(ctx).await?)
+    }
+
+    /// The kind of action which was performed.
+    fn action(&self) -> AuditAction {
+        self.action()
+    }
+
+    /// The ID of the object the action was performed
+    /// on. Note that the object may no longer exist.
+    fn target_id(&self) -> &str {
+        self.target_id()
+    }
+
+    /// The reason given for the action, if any.
+    fn reason(&self) -> Option<&str> {
+        self.reason()
+    }
+
+    /// A JSON encoded snapshot of the affected
+    /// content, taken before the action.
+    fn snapshot(&self) -> Option<&str> {
+        self.snapshot()
+    }
+}
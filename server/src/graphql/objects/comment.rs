@@ -1,5 +1,5 @@
 use crate::db::id::CommentID;
-use crate::db::models::{Comment, Url, User};
+use crate::db::models::{Comment, Flag, Url, User};
 use crate::schema::comments;
 use crate::Context;
 use chrono::{DateTime, Utc};
@@ -64,6 +64,17 @@ impl Comment {
         Ok(self.replies_to(ctx).await?)
     }
 
+    /// If the comment was flagged by the current viewer.
+    async fn flagged_by_viewer(&self, ctx: &Context) -> FieldResult<bool> {
+        Ok(Flag::comment_flagged_by_viewer(ctx, self.id()).await?)
+    }
+
+    /// Flags of this comment which were not yet dismissed,
+    /// newest first. Only moderators can see flags.
+    async fn flags(&self, ctx: &Context) -> FieldResult<Vec<Flag>> {
+        Ok(Flag::pending_for_comment(ctx, self.id()).await?)
+    }
+
     /// Comments which directly reply to this comment.
     async fn replies(
         &self,
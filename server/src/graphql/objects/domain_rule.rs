@@ -0,0 +1,87 @@
+use crate::db::id::DomainRuleID;
+use crate::db::models::{DomainAction, DomainRule, Url, User};
+use crate::Context;
+use chrono::{DateTime, Utc};
+use juniper::{graphql_object, FieldResult};
+use juniper_relay_connection::{RelayConnection, RelayConnectionNode};
+use std::convert::TryInto;
+
+impl RelayConnectionNode for DomainRule {
+    type Cursor = DomainRuleID;
+
+    fn cursor(&self) -> Self::Cursor {
+        self.id()
+    }
+
+    fn connection_type_name() -> &'static str {
+        "DomainRuleConnection"
+    }
+
+    fn edge_type_name() -> &'static str {
+        "DomainRuleConnectionEdge"
+    }
+}
+
+#[graphql_object(context = Context)]
+impl DomainRule {
+    /// A globally unique identifier for this
+    /// domain rule.
+    fn id(&self) -> DomainRuleID {
+        self.id()
+    }
+
+    /// The domain this rule applies to. The rule
+    /// also applies to all subdomains.
+    fn domain(&self) -> &str {
+        self.domain()
+    }
+
+    /// What happens to submissions from the domain.
+    fn action(&self) -> DomainAction {
+        self.action()
+    }
+
+    /// The time the rule was created.
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at()
+    }
+
+    /// The time the rule was last changed.
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at()
+    }
+
+    /// The administrator who created the rule.
+    async fn created_by(&self, ctx: &Context) -> FieldResult<User> {
+        Ok(self.created_by(ctx).await?)
+    }
+
+    /// The number of existing submissions from the domain,
+    /// or any of its subdomains.
+    async fn matching_url_count(&self, ctx: &Context) -> FieldResult<i32> {
+        Ok(self.matching_url_count(ctx).await?.try_into()?)
+    }
+
+    /// Existing submissions from the domain, or any of its
+    /// subdomains, newest first. Use this to review submissions
+    /// which were made before the domain was blocked.
+    async fn matching_urls(
+        &self,
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<Url>> {
+        RelayConnection::new_async(
+            first,
+            after,
+            last,
+            before,
+            |after, before, limit| async move {
+                Ok(self.matching_urls(ctx, after, before, limit).await?)
+            },
+        )
+        .await
+    }
+}
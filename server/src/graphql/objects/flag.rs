@@ -0,0 +1,29 @@
+use crate::db::id::FlagID;
+use crate::db::models::{Flag, FlagReason, User};
+use crate::Context;
+use chrono::{DateTime, Utc};
+use juniper::{graphql_object, FieldResult};
+
+#[graphql_object(context = Context)]
+impl Flag {
+    /// A globally unique identifier for this
+    /// flag.
+    fn id(&self) -> FlagID {
+        self.id()
+    }
+
+    /// The reason given for flagging.
+    fn reason(&self) -> FlagReason {
+        self.reason()
+    }
+
+    /// The time the item was flagged.
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at()
+    }
+
+    /// The user who flagged the item.
+    async fn created_by(&self, ctx: &Context) -> FieldResult<User> {
+        Ok(self.created_by(ctx).await?)
+    }
+}
@@ -1,5 +1,13 @@
+mod audit_entry;
 mod comment;
+mod domain_rule;
+mod flag;
 mod invite;
 mod login;
+mod redirect;
+mod tag;
+mod upvote;
 mod url;
+mod url_edit;
 mod user;
+mod vote_ring;
@@ -0,0 +1,18 @@
+use crate::db::models::Redirect;
+use crate::Context;
+use juniper::graphql_object;
+
+#[graphql_object(context = Context)]
+impl Redirect {
+    /// The url which responded with the
+    /// redirect.
+    fn url(&self) -> &str {
+        self.location()
+    }
+
+    /// The HTTP status code of the redirect,
+    /// e.g. `301`.
+    fn status(&self) -> i32 {
+        self.status().as_u16().into()
+    }
+}
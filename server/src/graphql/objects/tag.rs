@@ -0,0 +1,24 @@
+use crate::db::id::TagID;
+use crate::db::models::Tag;
+use crate::Context;
+use juniper::{graphql_object, FieldResult};
+use std::convert::TryInto;
+
+#[graphql_object(context = Context)]
+impl Tag {
+    /// A globally unique identifier for this
+    /// tag.
+    fn id(&self) -> TagID {
+        self.id()
+    }
+
+    /// The name of this tag, e.g. `rust`.
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    /// The number of urls labeled with this tag.
+    async fn url_count(&self, ctx: &Context) -> FieldResult<i32> {
+        Ok(self.url_count(ctx).await?.try_into()?)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::db::id::UserID;
+use crate::db::models::{Upvote, Url, User};
+use crate::Context;
+use chrono::{DateTime, Utc};
+use juniper::{graphql_object, FieldResult};
+use juniper_relay_connection::RelayConnectionNode;
+
+impl RelayConnectionNode for Upvote {
+    type Cursor = UserID;
+
+    fn cursor(&self) -> Self::Cursor {
+        self.user_id()
+    }
+
+    fn connection_type_name() -> &'static str {
+        "UpvoteConnection"
+    }
+
+    fn edge_type_name() -> &'static str {
+        "UpvoteConnectionEdge"
+    }
+}
+
+#[graphql_object(context = Context)]
+impl Upvote {
+    /// The user who upvoted.
+    async fn user(&self, ctx: &Context) -> FieldResult<User> {
+        Ok(self.user(ctx).await?)
+    }
+
+    /// The upvoted url.
+    async fn url(&self, ctx: &Context) -> FieldResult<Url> {
+        Ok(self.url(ctx).await?)
+    }
+
+    /// The time of the upvote.
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at()
+    }
+
+    /// When the upvote was discounted as part of a voting
+    /// ring. Discounted upvotes don't count towards the
+    /// ranking.
+    fn discounted_at(&self) -> Option<DateTime<Utc>> {
+        self.discounted_at()
+    }
+}
@@ -1,6 +1,6 @@
 use crate::db::id::{CommentID, UrlID};
-use crate::db::models::{Comment, Url, User};
-use crate::schema::comments;
+use crate::db::models::{Comment, CrawlState, Flag, Redirect, Tag, Upvote, Url, UrlEdit, User};
+use crate::schema::{comments, url_upvotes, users};
 use crate::Context;
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
@@ -32,35 +32,138 @@ impl Url {
         self.id()
     }
 
-    /// The URL that was submitted.
+    /// The URL that was submitted. For text posts this is
+    /// the path of their comments page.
     fn url(&self) -> FieldResult<String> {
         Ok(self.url()?.to_string())
     }
 
+    /// The raw markdown body, if this is a text post
+    /// rather than a link to an external page.
+    fn text(&self) -> Option<&str> {
+        self.text()
+    }
+
+    /// An html rendered version of the body of a text
+    /// post, see the `html` field on `Comment`.
+    fn html(&self) -> Option<String> {
+        self.html()
+    }
+
+    /// If this is a text post, rather than a link to an
+    /// external page. Text posts are never crawled.
+    fn is_text_post(&self) -> bool {
+        self.is_text_post()
+    }
+
+    /// The domain the URL is listed under, i.e. its
+    /// host without a leading `www.`.
+    fn domain(&self) -> &str {
+        self.domain()
+    }
+
+    /// The time the submission was held for moderation, if
+    /// it is waiting to be approved by a moderator.
+    fn held_at(&self) -> Option<DateTime<Utc>> {
+        self.held_at()
+    }
+
     /// The HTTP status code returned when
     /// attempting to load this url.
     fn status(&self) -> i32 {
         self.status().as_u16().into()
     }
 
+    /// Whether the linked page was fetched yet. Newly
+    /// submitted urls are `PENDING` until the page was
+    /// crawled, and only have a title and description
+    /// afterwards.
+    fn crawl_state(&self) -> CrawlState {
+        self.crawl_state()
+    }
+
+    /// The url the submitted url resolved to after
+    /// following any redirects. This is only known
+    /// once the page was crawled.
+    fn final_url(&self) -> Option<&str> {
+        self.final_url()
+    }
+
+    /// The url the linked page declares as its
+    /// canonical location, if any.
+    fn canonical_url(&self) -> Option<&str> {
+        self.canonical_url()
+    }
+
+    /// If this url links to the same page as an earlier
+    /// submission, this is the earlier submission.
+    /// Duplicates are not included in any listings.
+    async fn duplicate_of(&self, ctx: &Context) -> FieldResult<Option<Url>> {
+        match self.duplicate_of() {
+            Some(id) => Ok(Some(Url::find(ctx, id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The redirects which were followed when
+    /// crawling this url, in order.
+    async fn redirects(&self, ctx: &Context) -> FieldResult<Vec<Redirect>> {
+        Ok(self.redirects(ctx).await?)
+    }
+
+    /// The time a copy of the linked page was saved to
+    /// the archive, if there is one. The copy is served
+    /// at `/archive/<id>`.
+    fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at()
+    }
+
+    /// If the url failed to load on several consecutive
+    /// checks, this is the time of the first failed check.
+    fn dead_since(&self) -> Option<DateTime<Utc>> {
+        self.dead_since()
+    }
+
     /// The title of the linked page. This is parsed
-    /// from the page when the url is submitted.
+    /// from the page when the url is crawled, unless
+    /// it was edited, see `updateUrl`.
     fn title(&self) -> Option<&str> {
         self.title()
     }
 
     /// A description of the linked page. This is parsed
-    /// from the page when the url is submitted.
+    /// from the page when the url is crawled, unless
+    /// it was edited, see `updateUrl`.
     fn description(&self) -> Option<&str> {
         self.description()
     }
 
-    /// The image url of the linked page. This is the
+    /// The title parsed from the linked page, regardless
+    /// of any edits.
+    fn crawled_title(&self) -> Option<&str> {
+        self.crawled_title()
+    }
+
+    /// The description parsed from the linked page,
+    /// regardless of any edits.
+    fn crawled_description(&self) -> Option<&str> {
+        self.crawled_description()
+    }
+
+    /// Edits of the title and description, newest
+    /// first. Only moderators can see the edit
+    /// history.
+    async fn edits(&self, ctx: &Context) -> FieldResult<Vec<UrlEdit>> {
+        Ok(self.edits(ctx).await?)
+    }
+
+    /// The image of the linked page. This is the
     /// image that would e.g. be displayed in a Twitter
     /// timeline. These images typically have a 2:1 aspect
-    /// ratio.
-    fn image(&self) -> FieldResult<Option<String>> {
-        Ok(self.image()?.map(|uri| uri.to_string()))
+    /// ratio. The image is served as a resized copy from
+    /// this server, under `/img/<id>`.
+    fn image(&self) -> Option<String> {
+        self.thumbnail()
     }
 
     /// A slug for this URL, which is derived from the
@@ -70,6 +173,12 @@ impl Url {
         self.slug()
     }
 
+    /// The tags this url is labeled with, in
+    /// alphabetical order.
+    async fn tags(&self, ctx: &Context) -> FieldResult<Vec<Tag>> {
+        Ok(self.tags(ctx).await?)
+    }
+
     /// The time this url was submitted.
     fn created_at(&self) -> DateTime<Utc> {
         self.created_at()
@@ -81,8 +190,13 @@ impl Url {
     }
 
     /// The total number of upvotes this submission has received.
-    async fn upvote_count(&self, ctx: &Context) -> FieldResult<i32> {
-        Ok(self.upvote_count(ctx).await?.try_into()?)
+    fn upvote_count(&self) -> FieldResult<i32> {
+        Ok(self.upvote_count().try_into()?)
+    }
+
+    /// The total number of comments on this submission.
+    fn comment_count(&self) -> FieldResult<i32> {
+        Ok(self.comment_count().try_into()?)
     }
 
     /// If the URL was upvoted by the current viewer.
@@ -90,6 +204,27 @@ impl Url {
         Ok(self.upvoted_by_viewer(ctx).await?)
     }
 
+    /// If the URL was saved to the reading list of the current viewer.
+    async fn saved_by_viewer(&self, ctx: &Context) -> FieldResult<bool> {
+        Ok(self.saved_by_viewer(ctx).await?)
+    }
+
+    /// If the URL was hidden from the listings of the current viewer.
+    async fn hidden_by_viewer(&self, ctx: &Context) -> FieldResult<bool> {
+        Ok(self.hidden_by_viewer(ctx).await?)
+    }
+
+    /// If the URL was flagged by the current viewer.
+    async fn flagged_by_viewer(&self, ctx: &Context) -> FieldResult<bool> {
+        Ok(Flag::url_flagged_by_viewer(ctx, self.id()).await?)
+    }
+
+    /// Flags of this URL which were not yet dismissed, newest
+    /// first. Only moderators can see flags.
+    async fn flags(&self, ctx: &Context) -> FieldResult<Vec<Flag>> {
+        Ok(Flag::pending_for_url(ctx, self.id()).await?)
+    }
+
     /// List comments and optionally filter by `repliesTo`
     /// thread. If `repliesTo` is explicitly provided as
     /// `null`, it will filter for all comments which do not
@@ -135,4 +270,75 @@ impl Url {
             Ok(query.load(&*conn)?)
         })
     }
+
+    /// Users who upvoted this url, the most recent upvote
+    /// first. Moderators see all upvotes, everyone else only
+    /// sees the upvotes of users who made their upvotes public.
+    async fn upvotes(
+        &self,
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<Upvote>> {
+        if !self.visible_to_viewer(ctx).await? {
+            return Err("The submission is held for moderation".into());
+        }
+        let sees_all = Upvote::viewer_sees_all(ctx).await?;
+        let viewer = ctx.maybe_user_id();
+        let conn = ctx.conn().await?;
+        RelayConnection::new(first, after, last, before, |after, before, limit| {
+            let mut query = url_upvotes::table
+                .inner_join(users::table)
+                .filter(url_upvotes::dsl::url_id.eq(self.id()))
+                .order_by(url_upvotes::dsl::created_at.desc())
+                .then_order_by(url_upvotes::dsl::user_id.desc())
+                .select(url_upvotes::all_columns)
+                .into_boxed();
+
+            if !sees_all {
+                query = query.filter(
+                    users::dsl::public_upvotes
+                        .eq(true)
+                        .or(users::dsl::id.nullable().eq(viewer)),
+                );
+            }
+
+            // upvotes made at the same time are ordered by user
+            if let Some(after) = after {
+                let upvote: Upvote = url_upvotes::table
+                    .find((self.id(), after))
+                    .get_result(&*conn)?;
+                let created_at = upvote.created_at().naive_utc();
+                query = query.filter(
+                    url_upvotes::dsl::created_at
+                        .lt(created_at)
+                        .or(url_upvotes::dsl::created_at
+                            .eq(created_at)
+                            .and(url_upvotes::dsl::user_id.lt(after))),
+                );
+            }
+
+            if let Some(before) = before {
+                let upvote: Upvote = url_upvotes::table
+                    .find((self.id(), before))
+                    .get_result(&*conn)?;
+                let created_at = upvote.created_at().naive_utc();
+                query = query.filter(
+                    url_upvotes::dsl::created_at
+                        .gt(created_at)
+                        .or(url_upvotes::dsl::created_at
+                            .eq(created_at)
+                            .and(url_upvotes::dsl::user_id.gt(before))),
+                );
+            }
+
+            if let Some(limit) = limit {
+                query = query.limit(limit);
+            }
+
+            Ok(query.load(&*conn)?)
+        })
+    }
 }
@@ -0,0 +1,36 @@
+use crate::db::id::UrlEditID;
+use crate::db::models::{UrlEdit, User};
+use crate::Context;
+use chrono::{DateTime, Utc};
+use juniper::{graphql_object, FieldResult};
+
+#[graphql_object(context = Context)]
+impl UrlEdit {
+    /// A globally unique identifier for this
+    /// edit.
+    fn id(&self) -> UrlEditID {
+        self.id()
+    }
+
+    /// The title after the edit, or `null` if the
+    /// crawled title was restored.
+    fn title(&self) -> Option<&str> {
+        self.title()
+    }
+
+    /// The description after the edit, or `null` if
+    /// the crawled description was restored.
+    fn description(&self) -> Option<&str> {
+        self.description()
+    }
+
+    /// The time the edit was made.
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at()
+    }
+
+    /// The user who made the edit.
+    async fn created_by(&self, ctx: &Context) -> FieldResult<User> {
+        Ok(self.created_by(ctx).await?)
+    }
+}
@@ -1,8 +1,8 @@
 use crate::db::id::UserID;
 use crate::db::models::{Invite, Permission, Url, User};
-use crate::schema::urls;
+use crate::schema::{url_upvotes, urls};
 use crate::Context;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
 use juniper::{graphql_object, FieldResult};
 use juniper_relay_connection::RelayConnection;
@@ -67,6 +67,82 @@ impl User {
         })
     }
 
+    /// If this user made their upvotes
+    /// public.
+    fn public_upvotes(&self) -> bool {
+        self.public_upvotes()
+    }
+
+    /// Urls upvoted by this user, the most recently
+    /// upvoted first. This is only visible if the user
+    /// made their upvotes public, to the user themselves,
+    /// and to moderators.
+    async fn upvoted_urls(
+        &self,
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<Url>> {
+        if !self.upvotes_visible(ctx).await? {
+            return Err("Upvotes of this user are private".into());
+        }
+        let conn = ctx.conn().await?;
+        RelayConnection::new(first, after, last, before, |after, before, limit| {
+            let mut query = urls::table
+                .inner_join(url_upvotes::table)
+                .filter(url_upvotes::dsl::user_id.eq(self.id()))
+                .filter(urls::dsl::held_at.is_null())
+                .order_by(url_upvotes::dsl::created_at.desc())
+                .then_order_by(urls::dsl::id.desc())
+                .select(urls::all_columns)
+                .into_boxed();
+
+            let upvoted_at = |url_id| {
+                url_upvotes::table
+                    .find((url_id, self.id()))
+                    .select(url_upvotes::dsl::created_at)
+                    .get_result::<NaiveDateTime>(&*conn)
+            };
+
+            // upvotes made at the same time are ordered by URL
+            if let Some(after) = after {
+                let upvoted_at = upvoted_at(after)?;
+                query = query.filter(
+                    url_upvotes::dsl::created_at
+                        .lt(upvoted_at)
+                        .or(url_upvotes::dsl::created_at
+                            .eq(upvoted_at)
+                            .and(urls::dsl::id.lt(after))),
+                );
+            }
+
+            if let Some(before) = before {
+                let upvoted_at = upvoted_at(before)?;
+                query = query.filter(
+                    url_upvotes::dsl::created_at
+                        .gt(upvoted_at)
+                        .or(url_upvotes::dsl::created_at
+                            .eq(upvoted_at)
+                            .and(urls::dsl::id.gt(before))),
+                );
+            }
+
+            if let Some(limit) = limit {
+                query = query.limit(limit);
+            }
+
+            Ok(query.load(&*conn)?)
+        })
+    }
+
+    /// If this user was muted by the current
+    /// viewer.
+    async fn muted_by_viewer(&self, ctx: &Context) -> FieldResult<bool> {
+        Ok(self.muted_by_viewer(ctx).await?)
+    }
+
     /// List of active permissions for this
     /// user.
     async fn permissions(&self, ctx: &Context) -> FieldResult<Vec<Permission>> {
@@ -0,0 +1,83 @@
+use crate::db::id::VoteRingID;
+use crate::db::models::{User, VoteRing};
+use crate::Context;
+use chrono::{DateTime, Utc};
+use juniper::{graphql_object, FieldResult};
+use juniper_relay_connection::RelayConnectionNode;
+use std::convert::TryInto;
+
+impl RelayConnectionNode for VoteRing {
+    type Cursor = VoteRingID;
+
+    fn cursor(&self) -> Self::Cursor {
+        self.id()
+    }
+
+    fn connection_type_name() -> &'static str {
+        "VoteRingConnection"
+    }
+
+    fn edge_type_name() -> &'static str {
+        "VoteRingConnectionEdge"
+    }
+}
+
+#[graphql_object(context = Context)]
+impl VoteRing {
+    /// A globally unique identifier for this
+    /// voting ring.
+    fn id(&self) -> VoteRingID {
+        self.id()
+    }
+
+    /// The accounts which form the ring.
+    async fn members(&self, ctx: &Context) -> FieldResult<Vec<User>> {
+        Ok(self.members(ctx).await?)
+    }
+
+    /// The number of counted upvotes members of the ring
+    /// gave to each other, or to submissions upvoted by
+    /// other members.
+    fn ring_upvotes(&self) -> FieldResult<i32> {
+        Ok(self.ring_upvotes().try_into()?)
+    }
+
+    /// If members of the ring logged in from the
+    /// same IP address.
+    fn shared_ip(&self) -> bool {
+        self.shared_ip()
+    }
+
+    /// If members of the ring invited each other, or
+    /// were invited by the same user.
+    fn invite_tree(&self) -> bool {
+        self.invite_tree()
+    }
+
+    /// If members of the ring upvoted almost only
+    /// submissions from another member.
+    fn single_submitter(&self) -> bool {
+        self.single_submitter()
+    }
+
+    /// The time the ring was first found.
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at()
+    }
+
+    /// The time the ring was last found.
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at()
+    }
+
+    /// The time the upvotes of the ring were last
+    /// discounted, if they were.
+    fn discounted_at(&self) -> Option<DateTime<Utc>> {
+        self.discounted_at()
+    }
+
+    /// The time the ring was dismissed, if it was.
+    fn dismissed_at(&self) -> Option<DateTime<Utc>> {
+        self.dismissed_at()
+    }
+}
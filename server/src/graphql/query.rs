@@ -1,5 +1,5 @@
-use crate::db::id::{CommentID, UrlID, UserID};
-use crate::db::models::{Comment, Url, User};
+use crate::db::id::{CommentID, TagID, UrlID, UserID};
+use crate::db::models::{AuditEntry, Comment, DomainRule, Flag, Tag, Url, User, VoteRing};
 use crate::graphql::{search::Search, viewer::Viewer};
 use crate::Context;
 use juniper::{graphql_object, FieldResult};
@@ -22,6 +22,11 @@ impl Query {
         Search::new(query)
     }
 
+    /// Find a tag by name.
+    async fn tag(ctx: &Context, name: String) -> FieldResult<Tag> {
+        Ok(Tag::find_by_name(ctx, &name).await?)
+    }
+
     /// All submitted urls in reverse
     /// chronological order.
     async fn submissions(
@@ -43,9 +48,139 @@ impl Query {
         .await
     }
 
+    /// Submitted urls with pending flags, the most flagged
+    /// first. Only moderators can access the moderation queue.
+    async fn flagged_urls(
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<Url>> {
+        RelayConnection::new_async(
+            first,
+            after,
+            last,
+            before,
+            |after, before, limit| async move {
+                Ok(Flag::flagged_urls(ctx, after, before, limit).await?)
+            },
+        )
+        .await
+    }
+
+    /// Comments with pending flags, the most flagged first. Only
+    /// moderators can access the moderation queue.
+    async fn flagged_comments(
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<Comment>> {
+        RelayConnection::new_async(
+            first,
+            after,
+            last,
+            before,
+            |after, before, limit| async move {
+                Ok(Flag::flagged_comments(ctx, after, before, limit).await?)
+            },
+        )
+        .await
+    }
+
+    /// Submissions held for moderation because of a domain rule,
+    /// oldest first. Only moderators can see held submissions.
+    async fn held_urls(
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<Url>> {
+        RelayConnection::new_async(
+            first,
+            after,
+            last,
+            before,
+            |after, before, limit| async move { Ok(Url::held(ctx, after, before, limit).await?) },
+        )
+        .await
+    }
+
+    /// Rules deciding how submissions from a domain are treated,
+    /// in alphabetical order. Only administrators can view domain
+    /// rules.
+    async fn domain_rules(
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<DomainRule>> {
+        RelayConnection::new_async(
+            first,
+            after,
+            last,
+            before,
+            |after, before, limit| async move {
+                Ok(DomainRule::all(ctx, after, before, limit).await?)
+            },
+        )
+        .await
+    }
+
+    /// Moderation and administration actions, newest first.
+    /// Only administrators can access the audit log.
+    async fn audit_log(
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<AuditEntry>> {
+        RelayConnection::new_async(
+            first,
+            after,
+            last,
+            before,
+            |after, before, limit| async move {
+                Ok(AuditEntry::all(ctx, after, before, limit).await?)
+            },
+        )
+        .await
+    }
+
+    /// Suspected voting rings awaiting review, the ring with the
+    /// most counted upvotes first. Only administrators can access
+    /// the voting ring report.
+    async fn vote_rings(
+        ctx: &Context,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<RelayConnection<VoteRing>> {
+        RelayConnection::new_async(
+            first,
+            after,
+            last,
+            before,
+            |after, before, limit| async move {
+                Ok(VoteRing::pending(ctx, after, before, limit).await?)
+            },
+        )
+        .await
+    }
+
     #[graphql(name = "fetch__Url")]
     async fn fetch_url(ctx: &Context, id: UrlID) -> FieldResult<Url> {
-        Ok(Url::find(ctx, id).await?)
+        let url = Url::find(ctx, id).await?;
+        if !url.visible_to_viewer(ctx).await? {
+            return Err("Url not found".into());
+        }
+        Ok(url)
     }
 
     #[graphql(name = "fetch__Comment")]
@@ -53,6 +188,11 @@ impl Query {
         Ok(Comment::find(ctx, id).await?)
     }
 
+    #[graphql(name = "fetch__Tag")]
+    async fn fetch_tag(ctx: &Context, id: TagID) -> FieldResult<Tag> {
+        Ok(Tag::find(ctx, id).await?)
+    }
+
     #[graphql(name = "fetch__User")]
     async fn fetch_user(ctx: &Context, id: UserID) -> FieldResult<User> {
         Ok(User::find(ctx, id).await?)
@@ -1,8 +1,9 @@
 use crate::db::id::UrlID;
-use crate::db::models::Url;
-use crate::schema::urls;
+use crate::db::models::{Url, NOT_MUTED_DOMAIN};
+use crate::schema::{url_hides, urls, user_mutes};
 use crate::Context;
 use diesel::prelude::*;
+use diesel::sql_types::{Bool, Nullable, Text};
 use juniper::{graphql_object, FieldResult, ID};
 use juniper_relay_connection::RelayConnection;
 use std::collections::HashMap;
@@ -22,7 +23,9 @@ impl Search {
         format!("search-{}", base64::encode(self.0.as_bytes())).into()
     }
 
-    /// The list of results returned by this search.
+    /// The list of results returned by this search. Submissions the
+    /// viewer hid, or which are from domains or users the viewer
+    /// muted, are left out.
     pub async fn results(
         &self,
         ctx: &Context,
@@ -33,10 +36,28 @@ impl Search {
     ) -> FieldResult<RelayConnection<Url>> {
         // TODO(dyedgreen): Use the offset/ limit as cursors ...
         let results = ctx.search().find(&self.0)?;
+        let viewer = ctx.maybe_user_id();
         let conn = ctx.conn().await?;
         let urls = RelayConnection::new(first, after, last, before, |after, before, _| {
             let mut urls: HashMap<UrlID, Url> = urls::table
                 .filter(urls::id.eq_any(&results))
+                .filter(
+                    urls::id.ne_all(
+                        url_hides::table
+                            .filter(url_hides::user_id.nullable().eq(viewer))
+                            .select(url_hides::url_id),
+                    ),
+                )
+                .filter(
+                    diesel::dsl::sql::<Bool>(NOT_MUTED_DOMAIN).bind::<Nullable<Text>, _>(viewer),
+                )
+                .filter(
+                    urls::created_by.ne_all(
+                        user_mutes::table
+                            .filter(user_mutes::user_id.nullable().eq(viewer))
+                            .select(user_mutes::muted_id),
+                    ),
+                )
                 .load::<Url>(&*conn)?
                 .into_iter()
                 .map(|url| (url.id(), url))
@@ -1,4 +1,5 @@
-use crate::db::models::{Invite, Login, User};
+use crate::db::models::{Invite, Login, Url, User};
+use crate::pages::feed::saved_feed_path;
 use crate::schema::{invites, logins};
 use crate::Context;
 use diesel::prelude::*;
@@ -38,6 +39,47 @@ impl Viewer {
         Ok(email)
     }
 
+    /// Path of the private RSS feed of URLs saved by the currently
+    /// logged in user, if a feed token was issued. The path contains
+    /// a secret token, and should not be shared.
+    async fn saved_feed_path(ctx: &Context) -> FieldResult<Option<String>> {
+        let path = ctx
+            .maybe_user()
+            .await?
+            .and_then(|user| user.feed_token().map(saved_feed_path));
+        Ok(path)
+    }
+
+    /// URLs the currently logged in user hid from their listings,
+    /// the most recently hidden first.
+    async fn hidden_urls(ctx: &Context) -> FieldResult<Vec<Url>> {
+        if ctx.is_logged_in() {
+            Ok(Url::hidden(ctx).await?)
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// Domains muted by the currently logged in user. Submissions
+    /// from these domains are not listed for the user.
+    async fn muted_domains(ctx: &Context) -> FieldResult<Vec<String>> {
+        if ctx.is_logged_in() {
+            Ok(Url::muted_domains(ctx).await?)
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// Users muted by the currently logged in user. Submissions
+    /// by these users are not listed for the user.
+    async fn muted_users(ctx: &Context) -> FieldResult<Vec<User>> {
+        if ctx.is_logged_in() {
+            Ok(User::muted(ctx).await?)
+        } else {
+            Ok(vec![])
+        }
+    }
+
     /// Invitations issued by the currently logged in user. If no
     /// user is logged in, the connection will be empty. The invitations
     /// can optionally be filtered by claimed or available.
@@ -0,0 +1,13 @@
+use crate::db::models::Url;
+use crate::Context;
+use anyhow::Result;
+
+/// Repair the cached upvote and comment counters
+/// of submissions, should they ever drift.
+pub async fn job(ctx: Context) -> Result<()> {
+    let repaired = Url::check_counts(&ctx).await?;
+    if repaired > 0 {
+        log::warn!("Repaired inconsistent counters of {} urls", repaired);
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::db::models::Url;
+use crate::db::models::{CrawlState, Url};
 use crate::schema::urls;
 use crate::Context;
 use anyhow::Result;
@@ -6,20 +6,46 @@ use chrono::Duration;
 use diesel::prelude::*;
 
 const DAYS_BETWEEN_CHECKS: i64 = 30;
+const DAYS_BETWEEN_FAILED_CHECKS: i64 = 7;
 
 /// Update the URL meta information and status for
-/// old submissions.
+/// old submissions, and record if they could still be
+/// reached. URLs which failed to load are checked more
+/// often, so dead links are noticed sooner. So are URLs
+/// with an image but no thumbnail, which includes URLs
+/// submitted before thumbnails were introduced. Text posts
+/// don't link to a page and are never checked.
 pub async fn job(ctx: Context) -> Result<()> {
     let update_before = ctx.now() - Duration::days(DAYS_BETWEEN_CHECKS);
+    let retry_before = ctx.now() - Duration::days(DAYS_BETWEEN_FAILED_CHECKS);
     let old_urls: Vec<Url> = urls::table
-        .filter(urls::dsl::updated_at.lt(update_before.naive_utc()))
+        .filter(urls::dsl::crawl_state.ne(CrawlState::Pending))
+        .filter(urls::dsl::text.is_null())
+        .filter(
+            urls::dsl::updated_at
+                .lt(update_before.naive_utc())
+                .or(urls::dsl::crawl_state
+                    .eq(CrawlState::Failed)
+                    .and(urls::dsl::updated_at.lt(retry_before.naive_utc())))
+                .or(urls::dsl::image
+                    .is_not_null()
+                    .and(urls::dsl::thumbnail_at.is_null())
+                    .and(urls::dsl::updated_at.lt(retry_before.naive_utc()))),
+        )
         .load(&*ctx.conn().await?)?;
 
     log::info!("Updating meta information for {} urls", old_urls.len());
     for mut url in old_urls {
-        url.update_url_meta(&ctx)
+        let status = match url.update_url_meta(&ctx).await {
+            Ok(()) => url.fetched_status(),
+            Err(err) => {
+                log::error!("Failed to update url meta: {}", err);
+                None
+            }
+        };
+        url.record_check(&ctx, status)
             .await
-            .map_err(|err| log::error!("Failed to update url meta: {}", err))
+            .map_err(|err| log::error!("Failed to record url check: {}", err))
             .ok();
     }
     Ok(())
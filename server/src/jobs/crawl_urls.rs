@@ -0,0 +1,50 @@
+use crate::db::models::Url;
+use crate::Context;
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static IS_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Clears [`IS_RUNNING`] when dropped, such that the job
+/// runs again even if crawling panicked.
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        IS_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Fetch newly submitted urls and fill in their
+/// meta information.
+pub async fn job(ctx: Context) -> Result<()> {
+    // crawling can take longer than the job interval
+    if IS_RUNNING.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let _guard = RunningGuard;
+    crawl_pending(&ctx).await
+}
+
+async fn crawl_pending(ctx: &Context) -> Result<()> {
+    let pending = Url::pending(ctx).await?;
+    if !pending.is_empty() {
+        log::info!("Crawling {} submitted urls", pending.len());
+    }
+
+    for mut url in pending {
+        if let Err(err) = url.crawl(ctx).await {
+            log::warn!("Failed to crawl {}: {}", url.url_str(), err);
+        }
+        let reindexed = ctx.search().delete_url(&url).and_then(|()| {
+            if url.duplicate_of().is_none() {
+                ctx.search().index_url(&url)?;
+            }
+            Ok(())
+        });
+        if let Err(err) = reindexed {
+            log::error!("Failed to re-index {}: {}", url.url_str(), err);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use crate::db::models::VoteRing;
+use crate::Context;
+use anyhow::Result;
+
+/// Look for voting rings among recent upvotes, such
+/// that administrators can review them.
+pub async fn job(ctx: Context) -> Result<()> {
+    let found = VoteRing::detect(&ctx).await?;
+    if found > 0 {
+        log::info!("Found {} suspected voting rings", found);
+    }
+    Ok(())
+}
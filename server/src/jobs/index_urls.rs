@@ -13,6 +13,8 @@ pub async fn job(ctx: Context) -> Result<()> {
     let updated_after = ctx.now() - Duration::seconds(SECONDS_BETWEEN_CHECKS + 10);
     let urls: Vec<Url> = urls::table
         .filter(urls::dsl::updated_at.gt(updated_after.naive_utc()))
+        .filter(urls::dsl::duplicate_of.is_null())
+        .filter(urls::dsl::held_at.is_null())
         .load(&*ctx.conn().await?)?;
 
     if !urls.is_empty() {
@@ -3,7 +3,10 @@ use clokwerk::{Interval, ScheduleHandle, Scheduler};
 use std::time::Duration;
 use tokio::runtime::Handle;
 
+mod check_counts;
 mod check_old_urls;
+mod crawl_urls;
+mod detect_vote_rings;
 mod index_urls;
 
 fn schedule<J, F>(
@@ -44,6 +47,33 @@ pub fn watch_thread(
         check_old_urls::job,
     );
 
+    schedule(
+        &mut scheduler,
+        Interval::Hours(1),
+        &pool,
+        &mailer,
+        &async_runtime,
+        check_counts::job,
+    );
+
+    schedule(
+        &mut scheduler,
+        Interval::Hours(1),
+        &pool,
+        &mailer,
+        &async_runtime,
+        detect_vote_rings::job,
+    );
+
+    schedule(
+        &mut scheduler,
+        Interval::Seconds(10),
+        &pool,
+        &mailer,
+        &async_runtime,
+        crawl_urls::job,
+    );
+
     schedule(
         &mut scheduler,
         Interval::Minutes(1),
@@ -7,10 +7,12 @@ extern crate diesel_migrations;
 use std::convert::Infallible;
 use warp::{Filter, Reply};
 
+pub mod canonical;
 pub mod config;
 pub mod context;
 pub mod db;
 pub mod email;
+pub mod fetch;
 pub mod graphql;
 pub mod jobs;
 pub mod pages;
@@ -47,14 +49,35 @@ pub fn global_routes(
     let mine = ctx.clone().with(warp::wrap_fn(pages::url_lists::mine));
     let mine = warp::path("mine").and(mine);
 
+    let saved = ctx.clone().with(warp::wrap_fn(pages::url_lists::saved));
+    let saved = warp::path("saved").and(saved);
+
+    let upvoted = ctx.clone().with(warp::wrap_fn(pages::url_lists::upvoted));
+    let upvoted = warp::path("user").and(upvoted);
+
     let user = ctx.clone().with(warp::wrap_fn(pages::url_lists::user));
     let user = warp::path("user").and(user);
 
+    let tag = ctx.clone().with(warp::wrap_fn(pages::url_lists::tag));
+    let tag = warp::path("tag").and(tag);
+
+    let domain = ctx.clone().with(warp::wrap_fn(pages::url_lists::domain));
+    let domain = warp::path("domain").and(domain);
+
     let feed = ctx.clone().with(warp::wrap_fn(pages::feed::page));
 
+    let saved_feed = ctx.clone().with(warp::wrap_fn(pages::feed::saved));
+    let saved_feed = warp::path("saved").and(saved_feed);
+
     let comments = ctx.clone().with(warp::wrap_fn(pages::comments::page));
     let comments = warp::path("comments").and(comments);
 
+    let archive = ctx.clone().with(warp::wrap_fn(pages::archive::page));
+    let archive = warp::path("archive").and(archive);
+
+    let thumbnail = ctx.clone().with(warp::wrap_fn(pages::thumbnail::page));
+    let thumbnail = warp::path("img").and(thumbnail);
+
     let login = ctx.clone().with(warp::wrap_fn(pages::login::page));
     let login = warp::path("login").and(login);
 
@@ -69,9 +92,24 @@ pub fn global_routes(
     let search = ctx.clone().with(warp::wrap_fn(pages::search::page));
     let search = warp::path("search").and(search);
 
+    let submit = ctx.clone().with(warp::wrap_fn(pages::submit::page));
+    let submit = warp::path("submit").and(submit);
+
     let admin = ctx.clone().with(warp::wrap_fn(pages::admin::backup));
     let admin = warp::path!("admin" / "backup").and(admin);
 
+    let moderation = ctx.clone().with(warp::wrap_fn(pages::moderation::page));
+    let moderation = warp::path("moderation").and(moderation);
+
+    let dead = ctx.clone().with(warp::wrap_fn(pages::url_lists::dead));
+    let dead = warp::path!("admin" / "dead" / ..).and(dead);
+
+    let audit_log = ctx.clone().with(warp::wrap_fn(pages::audit_log::page));
+    let audit_log = warp::path!("admin" / "audit" / ..).and(audit_log);
+
+    let vote_rings = ctx.clone().with(warp::wrap_fn(pages::vote_rings::page));
+    let vote_rings = warp::path!("admin" / "rings" / ..).and(vote_rings);
+
     let api = ctx.clone().with(warp::wrap_fn(graphql::api));
     let api = warp::path("graphql").and(api);
 
@@ -84,15 +122,27 @@ pub fn global_routes(
         .or(recent)
         .or(best)
         .or(mine)
+        .or(saved)
+        .or(upvoted)
         .or(user)
+        .or(tag)
+        .or(domain)
         .or(feed)
+        .or(saved_feed)
         .or(comments)
+        .or(archive)
+        .or(thumbnail)
         .or(login)
         .or(register)
         .or(logout)
         .or(account)
         .or(search)
+        .or(submit)
+        .or(moderation)
         .or(admin)
+        .or(dead)
+        .or(audit_log)
+        .or(vote_rings)
         .or(api)
         .or(graphiql)
         .or(www);
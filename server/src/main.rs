@@ -4,6 +4,10 @@ pub use server::*;
 async fn main() {
     env_logger::Builder::from_env(env_logger::Env::default().filter_or("LOG", "info")).init();
 
+    canonical::init(Config::env())
+        .map_err(|err| log::error!("Failed to load canonicalization rules: {}", err))
+        .unwrap();
+
     let pool = db::connect(Config::env())
         .await
         .map_err(|err| log::error!("Failed to connect to database: {}", err))
@@ -0,0 +1,47 @@
+use crate::db::id::UrlID;
+use crate::db::models::Url;
+use crate::pages::{error, ContextFilter};
+use crate::Context;
+use askama::Template;
+use chrono::{DateTime, Utc};
+use warp::{filters::BoxedFilter, reply::Response, Filter, Reply};
+
+/// Snapshots are arbitrary HTML; they may only load inline
+/// styles and images, and run in a sandbox without scripts.
+const ARCHIVE_CSP: &str =
+    "default-src 'none'; style-src 'unsafe-inline'; img-src data:; form-action 'none'; sandbox";
+
+#[derive(Template)]
+#[template(path = "pages/archive.html")]
+struct Page {
+    url: Url,
+    archived_at: DateTime<Utc>,
+    snapshot: String,
+}
+
+async fn handle(ctx: &Context, url_id: UrlID) -> Result<Response, error::ServerError> {
+    let url = Url::find(ctx, url_id).await.map_err(error::not_found)?;
+    let archived_at = url.archived_at().ok_or(error::ServerError::NotFound)?;
+    let snapshot = ctx
+        .archive()
+        .load(url_id)
+        .await?
+        .ok_or(error::ServerError::NotFound)?;
+
+    let page = Page {
+        url,
+        archived_at,
+        snapshot: String::from_utf8_lossy(&snapshot).into_owned(),
+    };
+    Ok(warp::reply::with_header(page, "Content-Security-Policy", ARCHIVE_CSP).into_response())
+}
+
+pub fn page(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    warp::path::param()
+        .and(warp::path::end())
+        .and(ctx)
+        .and_then(|url_id: UrlID, ctx: Context| async move {
+            error::reply(&ctx, handle(&ctx, url_id).await)
+        })
+        .boxed()
+}
@@ -0,0 +1,60 @@
+use crate::db::models::{AuditEntry, User};
+use crate::pages::url_lists::{paginate, PaginatePartial};
+use crate::pages::{error, ContextFilter};
+use crate::Context;
+use askama::Template;
+use std::convert::TryInto;
+use warp::{filters::BoxedFilter, reply::Response, Filter, Reply};
+
+const PAGE_SIZE: i64 = 50;
+
+#[derive(Template)]
+#[template(path = "pages/audit_log.html")]
+struct Page<'a> {
+    entry_list: &'a [Entry],
+    pagination: PaginatePartial<'a>,
+    xsrf_token: &'a str,
+}
+
+struct Entry {
+    entry: AuditEntry,
+    actor: User,
+}
+
+async fn handle(ctx: &Context, page: u32) -> Result<Response, error::ServerError> {
+    ctx.user()
+        .await
+        .map_err(error::not_found)?
+        .check_permissions(ctx, |perm| perm.view_audit_log())
+        .await
+        .map_err(error::not_found)?;
+
+    let (entries, page_count) = AuditEntry::paginate(ctx, page.into(), PAGE_SIZE).await?;
+    let mut entry_list = vec![];
+    for entry in entries {
+        entry_list.push(Entry {
+            actor: entry.actor(ctx).await?,
+            entry,
+        });
+    }
+
+    let page = Page {
+        entry_list: &entry_list,
+        pagination: PaginatePartial {
+            route: "/admin/audit",
+            page,
+            page_count: page_count.try_into()?,
+        },
+        xsrf_token: ctx.xsrf_token(),
+    };
+    Ok(page.into_response())
+}
+
+pub fn page(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    paginate()
+        .and(ctx)
+        .and_then(
+            |page: u32, ctx: Context| async move { error::reply(&ctx, handle(&ctx, page).await) },
+        )
+        .boxed()
+}
@@ -1,14 +1,17 @@
 use crate::db::id::UrlID;
-use crate::db::models::{Comment, Url, User};
+use crate::db::models::{Comment, Redirect, Tag, Url, User};
 use crate::pages::{error, ContextFilter};
 use crate::Context;
 use askama::Template;
-use warp::{filters::BoxedFilter, reply::Response, Filter, Reply};
+use futures_util::future::try_join_all;
+use warp::{filters::BoxedFilter, http::Uri, reply::Response, Filter, Reply};
 
 #[derive(Template)]
 #[template(path = "pages/comments.html")]
 struct Page<'a> {
     url_partial: UrlPartial,
+    redirects: &'a [Redirect],
+    show_archive: bool,
     comment_list: &'a [CommentPartial],
     xsrf_token: &'a str,
     is_logged_in: bool,
@@ -19,8 +22,11 @@ struct Page<'a> {
 struct UrlPartial {
     url: Url,
     created_by: User,
+    tags: Vec<Tag>,
     upvote_count: i64,
     is_upvoted_by_viewer: bool,
+    is_saved_by_viewer: bool,
+    is_hidden_by_viewer: bool,
     comment_count: i64,
     is_logged_in: bool,
 }
@@ -32,6 +38,15 @@ struct CommentPartial {
     created_by: User,
 }
 
+impl CommentPartial {
+    async fn load(ctx: &Context, comment: Comment) -> anyhow::Result<Self> {
+        Ok(Self {
+            created_by: comment.created_by(ctx).await?,
+            comment,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct IgnoreSlug {}
 
@@ -43,26 +58,51 @@ impl std::str::FromStr for IgnoreSlug {
 }
 
 async fn handle(ctx: &Context, url_id: UrlID) -> Result<Response, error::ServerError> {
-    let url = Url::find(ctx, url_id).await.map_err(error::not_found)?;
+    let url = match Url::find(ctx, url_id).await {
+        Ok(url) => url,
+        Err(err) => match Url::merged_into(ctx, url_id).await? {
+            Some(into) => {
+                let location: Uri = format!("/comments/{}", into).parse()?;
+                return Ok(warp::redirect::permanent(location).into_response());
+            }
+            None => return Err(error::not_found(err)),
+        },
+    };
+    if !url.visible_to_viewer(ctx).await? {
+        return Err(error::not_found("Submission is held for moderation"));
+    }
+    if let Some(original) = url.duplicate_of() {
+        let location: Uri = format!("/comments/{}", original).parse()?;
+        return Ok(warp::redirect::permanent(location).into_response());
+    }
 
     let comments = url.comments(ctx, 1024 /* some sane limit ... */).await?;
-    let mut comment_list = vec![];
-    for comment in comments {
-        comment_list.push(CommentPartial {
-            created_by: comment.created_by(ctx).await?,
-            comment,
-        });
-    }
+    // loaded concurrently, such that the authors are batched
+    let comment_list = try_join_all(
+        comments
+            .into_iter()
+            .map(|comment| CommentPartial::load(ctx, comment)),
+    )
+    .await?;
+
+    let redirects = url.redirects(ctx).await?;
+    // link the archived copy once the live page is gone
+    let show_archive = url.archived_at().is_some() && !url.status().is_success();
 
     let page = Page {
         url_partial: UrlPartial {
             created_by: url.created_by(ctx).await?,
-            upvote_count: url.upvote_count(ctx).await?,
+            tags: url.tags(ctx).await?,
+            upvote_count: url.upvote_count(),
             is_upvoted_by_viewer: url.upvoted_by_viewer(ctx).await?,
-            comment_count: url.comment_count(ctx).await?,
+            is_saved_by_viewer: url.saved_by_viewer(ctx).await?,
+            is_hidden_by_viewer: url.hidden_by_viewer(ctx).await?,
+            comment_count: url.comment_count(),
             is_logged_in: ctx.is_logged_in(),
             url,
         },
+        redirects: &redirects,
+        show_archive,
         comment_list: &comment_list,
         xsrf_token: ctx.xsrf_token(),
         is_logged_in: ctx.is_logged_in(),
@@ -1,8 +1,9 @@
-use crate::db::models::{Url, UrlOrdering};
+use crate::db::models::{Url, UrlOrdering, User};
 use crate::pages::{error, ContextFilter};
 use crate::Context;
 use askama::Template;
 use chrono::{DateTime, Utc};
+use serde::Deserialize;
 use warp::{filters::BoxedFilter, reply::Response, Filter};
 
 const FEED_SIZE: i64 = 32;
@@ -10,17 +11,77 @@ const FEED_SIZE: i64 = 32;
 #[derive(Template)]
 #[template(path = "pages/feed.xml")]
 struct Page {
+    title: &'static str,
+    description: &'static str,
     pub_date: DateTime<Utc>,
-    urls: Vec<Url>,
+    items: Vec<Item>,
+}
+
+struct Item {
+    url: Url,
+    pub_date: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct SavedQuery {
+    token: String,
+}
+
+/// Path of the private feed of saved URLs, for
+/// the given feed token.
+pub fn saved_feed_path(token: &str) -> String {
+    format!("/saved/feed.xml?token={}", token)
 }
 
 async fn handle(ctx: &Context) -> Result<Page, error::ServerError> {
     let (urls, _) = Url::paginate(ctx, UrlOrdering::Recent, 0, FEED_SIZE).await?;
-    let pub_date = urls
+    let items: Vec<Item> = urls
+        .into_iter()
+        .map(|url| Item {
+            pub_date: url.created_at(),
+            url,
+        })
+        .collect();
+    Ok(Page {
+        title: "urls.fyi",
+        description: "A tech link aggregator.",
+        pub_date: latest_pub_date(ctx, &items),
+        items,
+    })
+}
+
+async fn handle_saved(ctx: &Context, token: &str) -> Result<Page, error::ServerError> {
+    let user = User::find_by_feed_token(ctx, token)
+        .await
+        .map_err(error::not_found)?;
+    let (urls, _) = Url::paginate(ctx, UrlOrdering::Saved(user.id()), 0, FEED_SIZE).await?;
+    let ids = urls.iter().map(|url| url.id()).collect();
+    let saved_at = Url::saved_at_all(ctx, user.id(), ids).await?;
+    // saved URLs are published when they are saved
+    let items: Vec<Item> = urls
+        .into_iter()
+        .map(|url| Item {
+            pub_date: saved_at
+                .get(&url.id())
+                .copied()
+                .unwrap_or_else(|| ctx.now()),
+            url,
+        })
+        .collect();
+    Ok(Page {
+        title: "urls.fyi - saved",
+        description: "Your saved links.",
+        pub_date: latest_pub_date(ctx, &items),
+        items,
+    })
+}
+
+/// The feed was last updated when its newest item was published.
+fn latest_pub_date(ctx: &Context, items: &[Item]) -> DateTime<Utc> {
+    items
         .get(0)
-        .map(|url| url.created_at())
-        .unwrap_or_else(|| ctx.now());
-    Ok(Page { pub_date, urls })
+        .map(|item| item.pub_date)
+        .unwrap_or_else(|| ctx.now())
 }
 
 pub fn page(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
@@ -30,3 +91,17 @@ pub fn page(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
         .and_then(|ctx: Context| async move { error::reply(&ctx, handle(&ctx).await) })
         .boxed()
 }
+
+/// Private feed of the URLs a user saved. The feed is
+/// authenticated by a secret token, since feed readers
+/// can not log in.
+pub fn saved(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    warp::path("feed.xml")
+        .and(warp::path::end())
+        .and(warp::query::<SavedQuery>())
+        .and(ctx)
+        .and_then(|query: SavedQuery, ctx: Context| async move {
+            error::reply(&ctx, handle_saved(&ctx, &query.token).await)
+        })
+        .boxed()
+}
@@ -5,16 +5,22 @@ use warp::Filter;
 
 pub mod account;
 pub mod admin;
+pub mod archive;
+pub mod audit_log;
 pub mod comments;
 pub mod error;
 pub mod feed;
 pub mod graphiql;
 pub mod login;
 pub mod logout;
+pub mod moderation;
 pub mod register;
 pub mod search;
 pub mod session;
+pub mod submit;
+pub mod thumbnail;
 pub mod url_lists;
+pub mod vote_rings;
 pub mod xsrf;
 
 const XSRF_COOKIE_NAME: &str = "xsrf";
@@ -0,0 +1,121 @@
+use crate::db::models::{Comment, Flag, Url, User};
+use crate::pages::{error, ContextFilter};
+use crate::Context;
+use askama::Template;
+use warp::{filters::BoxedFilter, reply::Response, Filter, Reply};
+
+const QUEUE_SIZE: i64 = 50;
+
+#[derive(Template)]
+#[template(path = "pages/moderation.html")]
+struct Page<'a> {
+    held_list: &'a [HeldUrl],
+    url_list: &'a [FlaggedUrl],
+    comment_list: &'a [FlaggedComment],
+    xsrf_token: &'a str,
+}
+
+struct HeldUrl {
+    url: Url,
+    created_by: User,
+}
+
+struct FlaggedUrl {
+    url: Url,
+    created_by: User,
+    flags: Vec<Flag>,
+}
+
+struct FlaggedComment {
+    comment: Comment,
+    created_by: User,
+    url: Url,
+    flags: Vec<Flag>,
+}
+
+impl FlaggedUrl {
+    fn reasons(&self) -> String {
+        summarize_reasons(&self.flags)
+    }
+}
+
+impl FlaggedComment {
+    fn reasons(&self) -> String {
+        summarize_reasons(&self.flags)
+    }
+}
+
+/// Lists the distinct reasons given for the flags,
+/// with their count, e.g. `Spam (2), Other`.
+fn summarize_reasons(flags: &[Flag]) -> String {
+    let mut reasons: Vec<(&str, usize)> = vec![];
+    for flag in flags {
+        let label = flag.reason().label();
+        match reasons.iter_mut().find(|(reason, _)| *reason == label) {
+            Some((_, count)) => *count += 1,
+            None => reasons.push((label, 1)),
+        }
+    }
+    reasons
+        .into_iter()
+        .map(|(reason, count)| {
+            if count > 1 {
+                format!("{} ({})", reason, count)
+            } else {
+                reason.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn handle(ctx: &Context) -> Result<Response, error::ServerError> {
+    ctx.user()
+        .await
+        .map_err(error::not_found)?
+        .check_permissions(ctx, |perm| perm.view_flags())
+        .await
+        .map_err(error::not_found)?;
+
+    let mut held_list = vec![];
+    for url in Url::held(ctx, None, None, Some(QUEUE_SIZE)).await? {
+        held_list.push(HeldUrl {
+            created_by: url.created_by(ctx).await?,
+            url,
+        });
+    }
+
+    let mut url_list = vec![];
+    for url in Flag::flagged_urls(ctx, None, None, Some(QUEUE_SIZE)).await? {
+        url_list.push(FlaggedUrl {
+            created_by: url.created_by(ctx).await?,
+            flags: Flag::pending_for_url(ctx, url.id()).await?,
+            url,
+        });
+    }
+
+    let mut comment_list = vec![];
+    for comment in Flag::flagged_comments(ctx, None, None, Some(QUEUE_SIZE)).await? {
+        comment_list.push(FlaggedComment {
+            created_by: comment.created_by(ctx).await?,
+            url: comment.url(ctx).await?,
+            flags: Flag::pending_for_comment(ctx, comment.id()).await?,
+            comment,
+        });
+    }
+
+    let page = Page {
+        held_list: &held_list,
+        url_list: &url_list,
+        comment_list: &comment_list,
+        xsrf_token: ctx.xsrf_token(),
+    };
+    Ok(page.into_response())
+}
+
+pub fn page(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    warp::path::end()
+        .and(ctx)
+        .and_then(|ctx: Context| async move { error::reply(&ctx, handle(&ctx).await) })
+        .boxed()
+}
@@ -0,0 +1,156 @@
+use crate::db::models::{NewUrlInput, Url};
+use crate::pages::{error, ContextFilter};
+use crate::Context;
+use askama::Template;
+use serde::Deserialize;
+use warp::{filters::BoxedFilter, http::Uri, reply::Response, Filter, Reply};
+
+const FORM_SIZE_LIMIT: u64 = 16 * 1024;
+
+#[derive(Template)]
+#[template(path = "pages/submit.html")]
+struct Page<'a> {
+    url: &'a str,
+    title: &'a str,
+    tags: &'a str,
+    existing: Option<Url>,
+    error: Option<String>,
+    bookmarklet: String,
+    xsrf_token: &'a str,
+    is_logged_in: bool,
+}
+
+/// Parameters of the submit page. Share targets may
+/// pass the link as part of the shared `text`, rather
+/// than as the `url`.
+#[derive(Deserialize)]
+struct SubmitQuery {
+    url: Option<String>,
+    title: Option<String>,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SubmitForm {
+    xsrf_token: String,
+    url: String,
+    tags: Option<String>,
+}
+
+impl SubmitQuery {
+    fn url(&self) -> &str {
+        let in_text = self.text.as_deref().and_then(|text| {
+            text.split_whitespace()
+                .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        });
+        self.url
+            .as_deref()
+            .filter(|url| !url.trim().is_empty())
+            .or(in_text)
+            .unwrap_or("")
+            .trim()
+    }
+}
+
+/// A link which opens the submit page for the page
+/// the user is currently viewing. This uses the configured
+/// host name, rather than the one the request was made to.
+fn bookmarklet(hostname: &str) -> String {
+    format!(
+        "javascript:location.href='//{}/submit?url='+encodeURIComponent(location.href)+'&title='+encodeURIComponent(document.title)",
+        hostname
+    )
+}
+
+async fn render(
+    ctx: &Context,
+    url: &str,
+    title: &str,
+    tags: &str,
+    error: Option<String>,
+) -> Result<Response, error::ServerError> {
+    let existing = if url.is_empty() {
+        None
+    } else {
+        Url::find_submitted(ctx, url).await?
+    };
+
+    let page = Page {
+        url,
+        title,
+        tags,
+        existing,
+        error,
+        bookmarklet: bookmarklet(ctx.hostname()),
+        xsrf_token: ctx.xsrf_token(),
+        is_logged_in: ctx.is_logged_in(),
+    };
+    Ok(page.into_response())
+}
+
+async fn handle(ctx: &Context, query: SubmitQuery) -> Result<Response, error::ServerError> {
+    let title = query.title.as_deref().unwrap_or("").trim();
+    render(ctx, query.url(), title, "", None).await
+}
+
+async fn handle_submit(ctx: &Context, form: SubmitForm) -> Result<Response, error::ServerError> {
+    if !ctx.check_xsrf_token(&form.xsrf_token) {
+        return Err(error::request("Invalid XSRF token"));
+    }
+
+    let tags = form
+        .tags
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect();
+    let input = NewUrlInput::new(form.url.trim().into(), tags);
+
+    let error = match ctx.user_id() {
+        Ok(user_id) => match Url::create(ctx, input, user_id).await {
+            Ok(url) => {
+                let location: Uri = format!("/comments/{}", url.id()).parse()?;
+                return Ok(warp::redirect::see_other(location).into_response());
+            }
+            Err(err) => format!("Failed to submit: {}", err),
+        },
+        Err(_) => "You need to log in to submit links".into(),
+    };
+    render(
+        ctx,
+        form.url.trim(),
+        "",
+        form.tags.as_deref().unwrap_or(""),
+        Some(error),
+    )
+    .await
+}
+
+/// Page to submit a link without using the GraphQL API, e.g.
+/// from a bookmarklet or as a share target. The link can be
+/// passed as `/submit?url=...&title=...`, and is submitted
+/// once the user confirms the prefilled form. The title is
+/// only shown, the submission uses the crawled title.
+pub fn page(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    let show = warp::get()
+        .and(warp::path::end())
+        .and(warp::query::<SubmitQuery>())
+        .and(ctx.clone())
+        .and_then(|query: SubmitQuery, ctx: Context| async move {
+            error::reply(&ctx, handle(&ctx, query).await)
+        });
+
+    let submit = warp::post()
+        .and(warp::path::end())
+        .and(warp::body::content_length_limit(FORM_SIZE_LIMIT))
+        .and(warp::body::form::<SubmitForm>())
+        .and(ctx)
+        .and_then(|form: SubmitForm, ctx: Context| async move {
+            error::reply(&ctx, handle_submit(&ctx, form).await)
+        });
+
+    show.or(submit).unify().boxed()
+}
@@ -0,0 +1,35 @@
+use crate::db::id::UrlID;
+use crate::db::models::Url;
+use crate::pages::{error, ContextFilter};
+use crate::Context;
+use warp::{filters::BoxedFilter, reply::Response, Filter, Reply};
+
+/// Thumbnail links change when the thumbnail
+/// is replaced, so they can be cached for long.
+const THUMBNAIL_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+async fn handle(ctx: &Context, url_id: UrlID) -> Result<Response, error::ServerError> {
+    let url = Url::find(ctx, url_id).await.map_err(error::not_found)?;
+    if url.thumbnail().is_none() {
+        return Err(error::ServerError::NotFound);
+    }
+    let thumbnail = ctx
+        .thumbnails()
+        .load(url_id)
+        .await?
+        .ok_or(error::ServerError::NotFound)?;
+
+    let reply = warp::reply::with_header(thumbnail, "Content-Type", "image/jpeg");
+    let reply = warp::reply::with_header(reply, "Cache-Control", THUMBNAIL_CACHE_CONTROL);
+    Ok(reply.into_response())
+}
+
+pub fn page(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    warp::path::param()
+        .and(warp::path::end())
+        .and(ctx)
+        .and_then(|url_id: UrlID, ctx: Context| async move {
+            error::reply(&ctx, handle(&ctx, url_id).await)
+        })
+        .boxed()
+}
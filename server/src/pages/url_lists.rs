@@ -1,8 +1,10 @@
+use crate::canonical;
 use crate::db::id::UserID;
-use crate::db::models::{Url, UrlOrdering, User};
+use crate::db::models::{Tag, Url, UrlOrdering, User};
 use crate::pages::{error, ContextFilter};
 use crate::Context;
 use askama::Template;
+use futures_util::future::try_join_all;
 use std::convert::TryInto;
 use warp::{filters::BoxedFilter, http::Uri, reply::Response, Filter, Rejection, Reply};
 
@@ -26,6 +28,15 @@ struct Page<'a> {
 struct ListHeader<'a> {
     heading: &'a str,
     sub_heading: &'a str,
+    mute: Option<MuteButton>,
+}
+
+/// Lets the viewer mute the user or domain
+/// a list is showing.
+struct MuteButton {
+    kind: &'static str,
+    target: String,
+    is_muted: bool,
 }
 
 #[derive(Template)]
@@ -33,18 +44,44 @@ struct ListHeader<'a> {
 struct UrlPartial {
     url: Url,
     created_by: User,
+    tags: Vec<Tag>,
     upvote_count: i64,
     is_upvoted_by_viewer: bool,
+    is_saved_by_viewer: bool,
+    is_hidden_by_viewer: bool,
     comment_count: i64,
     is_logged_in: bool,
 }
 
+impl UrlPartial {
+    async fn load(ctx: &Context, url: Url) -> anyhow::Result<Self> {
+        let (created_by, tags, upvoted, saved, hidden) = tokio::try_join!(
+            url.created_by(ctx),
+            url.tags(ctx),
+            url.upvoted_by_viewer(ctx),
+            url.saved_by_viewer(ctx),
+            url.hidden_by_viewer(ctx),
+        )?;
+        Ok(Self {
+            created_by,
+            tags,
+            upvote_count: url.upvote_count(),
+            is_upvoted_by_viewer: upvoted,
+            is_saved_by_viewer: saved,
+            is_hidden_by_viewer: hidden,
+            comment_count: url.comment_count(),
+            url,
+            is_logged_in: ctx.is_logged_in(),
+        })
+    }
+}
+
 #[derive(Template)]
 #[template(path = "partials/paginate.html")]
-struct PaginatePartial<'a> {
-    route: &'a str,
-    page: u32,
-    page_count: u32,
+pub(super) struct PaginatePartial<'a> {
+    pub(super) route: &'a str,
+    pub(super) page: u32,
+    pub(super) page_count: u32,
 }
 
 impl PaginatePartial<'_> {
@@ -83,42 +120,103 @@ impl PaginatePartial<'_> {
 
 async fn handle(
     ctx: &Context,
-    order: UrlOrdering,
+    order: UrlOrdering<'_>,
     page: u32,
     route: &str,
     title: &str,
 ) -> Result<Response, error::ServerError> {
     let (urls, page_count) = Url::paginate(ctx, order, page.into(), PAGE_SIZE).await?;
 
-    let mut url_list = vec![];
-    for url in urls {
-        url_list.push(UrlPartial {
-            created_by: url.created_by(ctx).await?,
-            upvote_count: url.upvote_count(ctx).await?,
-            is_upvoted_by_viewer: url.upvoted_by_viewer(ctx).await?,
-            comment_count: url.comment_count(ctx).await?,
-            url,
-            is_logged_in: ctx.is_logged_in(),
-        });
-    }
+    // loaded concurrently, such that lookups are batched
+    let url_list = try_join_all(urls.into_iter().map(|url| UrlPartial::load(ctx, url))).await?;
 
     let user_heading;
+    let upvoted_heading;
+    let tag_heading;
+    let domain_sub_heading;
     let list_header = match order {
         UrlOrdering::Ranked => None,
         UrlOrdering::Best => Some(ListHeader {
             heading: "Best",
             sub_heading: "All time best submissions",
+            mute: None,
         }),
         UrlOrdering::Recent => Some(ListHeader {
             heading: "Recent",
             sub_heading: "The most recent submissions",
+            mute: None,
         }),
         UrlOrdering::User(user_id) => {
             let user = User::find(ctx, user_id).await?;
             user_heading = format!("By {}", user.name());
+            let mute = match ctx.maybe_user_id() {
+                Some(viewer_id) if viewer_id != user_id => Some(MuteButton {
+                    kind: "user",
+                    target: user_id.to_string(),
+                    is_muted: user.muted_by_viewer(ctx).await?,
+                }),
+                Some(_) | None => None,
+            };
             Some(ListHeader {
                 heading: &user_heading,
                 sub_heading: "Recent submissions",
+                mute,
+            })
+        }
+        UrlOrdering::Upvoted(user_id) => {
+            let user = User::find(ctx, user_id).await?;
+            upvoted_heading = format!("Upvoted by {}", user.name());
+            Some(ListHeader {
+                heading: &upvoted_heading,
+                sub_heading: "Favorite submissions, the most recently upvoted first",
+                mute: None,
+            })
+        }
+        UrlOrdering::Tag(tag_id) => {
+            let tag = Tag::find(ctx, tag_id).await?;
+            tag_heading = format!("#{}", tag.name());
+            Some(ListHeader {
+                heading: &tag_heading,
+                sub_heading: "Recent submissions with this tag",
+                mute: None,
+            })
+        }
+        UrlOrdering::Dead => Some(ListHeader {
+            heading: "Dead links",
+            sub_heading: "Submissions which repeatedly failed to load",
+            mute: None,
+        }),
+        UrlOrdering::Saved(_) => Some(ListHeader {
+            heading: "Saved",
+            sub_heading: "Your reading list, only visible to you",
+            mute: None,
+        }),
+        UrlOrdering::Domain(domain) => {
+            let stats = Url::domain_stats(ctx, domain).await?;
+            if stats.submissions == 0 {
+                return Err(error::not_found("No submissions from this domain"));
+            }
+            domain_sub_heading = format!(
+                "{} submission{} · {} upvote{} · {:.0}% dead links",
+                stats.submissions,
+                if stats.submissions != 1 { "s" } else { "" },
+                stats.upvotes,
+                if stats.upvotes != 1 { "s" } else { "" },
+                stats.dead_share() * 100.0,
+            );
+            let mute = if ctx.is_logged_in() {
+                Some(MuteButton {
+                    kind: "domain",
+                    target: domain.to_string(),
+                    is_muted: Url::domain_muted_by_viewer(ctx, domain).await?,
+                })
+            } else {
+                None
+            };
+            Some(ListHeader {
+                heading: domain,
+                sub_heading: &domain_sub_heading,
+                mute,
             })
         }
     };
@@ -142,7 +240,7 @@ async fn handle(
     Ok(page.into_response())
 }
 
-fn paginate() -> impl Filter<Extract = (u32,), Error = Rejection> + Clone + Copy {
+pub(super) fn paginate() -> impl Filter<Extract = (u32,), Error = Rejection> + Clone + Copy {
     warp::path::end()
         .and(warp::any().map(|| 1))
         .or(warp::path!("page" / u32))
@@ -206,6 +304,29 @@ pub fn user(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
         .boxed()
 }
 
+pub fn upvoted(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    warp::path::param()
+        .and(warp::path("upvoted"))
+        .and(paginate())
+        .and(ctx)
+        .and_then(|user_id: UserID, page: u32, ctx: Context| async move {
+            let visible = match User::find(&ctx, user_id).await {
+                Ok(user) => user.upvotes_visible(&ctx).await,
+                Err(err) => Err(err),
+            };
+            let result = match visible {
+                Ok(true) => {
+                    let route = format!("/user/{}/upvoted", user_id);
+                    handle(&ctx, UrlOrdering::Upvoted(user_id), page, &route, "upvoted").await
+                }
+                Ok(false) => Err(error::not_found("Upvotes of this user are private")),
+                Err(err) => Err(error::not_found(err)),
+            };
+            error::reply(&ctx, result)
+        })
+        .boxed()
+}
+
 pub fn mine(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
     paginate()
         .and(ctx)
@@ -220,3 +341,69 @@ pub fn mine(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
         })
         .boxed()
 }
+
+pub fn saved(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    paginate()
+        .and(ctx)
+        .and_then(|page: u32, ctx: Context| async move {
+            match ctx.maybe_user_id() {
+                Some(user_id) => error::reply(
+                    &ctx,
+                    handle(&ctx, UrlOrdering::Saved(user_id), page, "/saved", "saved").await,
+                ),
+                None => Ok(warp::redirect::temporary(Uri::from_static("/login")).into_response()),
+            }
+        })
+        .boxed()
+}
+
+pub fn dead(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    paginate()
+        .and(ctx)
+        .and_then(|page: u32, ctx: Context| async move {
+            let result = match ctx.user().await {
+                Ok(user) => match user
+                    .check_permissions(&ctx, |perm| perm.view_dead_urls())
+                    .await
+                {
+                    Ok(()) => handle(&ctx, UrlOrdering::Dead, page, "/admin/dead", "dead").await,
+                    Err(err) => Err(error::not_found(err)),
+                },
+                Err(err) => Err(error::not_found(err)),
+            };
+            error::reply(&ctx, result)
+        })
+        .boxed()
+}
+
+pub fn tag(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    warp::path::param()
+        .and(paginate())
+        .and(ctx)
+        .and_then(|name: String, page: u32, ctx: Context| async move {
+            let result = match Tag::find_by_name(&ctx, &name).await {
+                Ok(tag) => {
+                    let route = format!("/tag/{}", tag.name());
+                    handle(&ctx, UrlOrdering::Tag(tag.id()), page, &route, "tag").await
+                }
+                Err(err) => Err(error::not_found(err)),
+            };
+            error::reply(&ctx, result)
+        })
+        .boxed()
+}
+
+pub fn domain(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    warp::path::param()
+        .and(paginate())
+        .and(ctx)
+        .and_then(|host: String, page: u32, ctx: Context| async move {
+            let domain = canonical::normalize_domain(&host);
+            let route = format!("/domain/{}", domain);
+            error::reply(
+                &ctx,
+                handle(&ctx, UrlOrdering::Domain(&domain), page, &route, "domain").await,
+            )
+        })
+        .boxed()
+}
@@ -0,0 +1,49 @@
+use crate::db::models::{User, VoteRing};
+use crate::pages::{error, ContextFilter};
+use crate::Context;
+use askama::Template;
+use warp::{filters::BoxedFilter, reply::Response, Filter, Reply};
+
+const QUEUE_SIZE: i64 = 50;
+
+#[derive(Template)]
+#[template(path = "pages/vote_rings.html")]
+struct Page<'a> {
+    ring_list: &'a [Ring],
+    xsrf_token: &'a str,
+}
+
+struct Ring {
+    ring: VoteRing,
+    members: Vec<User>,
+}
+
+async fn handle(ctx: &Context) -> Result<Response, error::ServerError> {
+    ctx.user()
+        .await
+        .map_err(error::not_found)?
+        .check_permissions(ctx, |perm| perm.view_vote_rings())
+        .await
+        .map_err(error::not_found)?;
+
+    let mut ring_list = vec![];
+    for ring in VoteRing::pending(ctx, None, None, Some(QUEUE_SIZE)).await? {
+        ring_list.push(Ring {
+            members: ring.members(ctx).await?,
+            ring,
+        });
+    }
+
+    let page = Page {
+        ring_list: &ring_list,
+        xsrf_token: ctx.xsrf_token(),
+    };
+    Ok(page.into_response())
+}
+
+pub fn page(ctx: impl ContextFilter + 'static) -> BoxedFilter<(Response,)> {
+    warp::path::end()
+        .and(ctx)
+        .and_then(|ctx: Context| async move { error::reply(&ctx, handle(&ctx).await) })
+        .boxed()
+}
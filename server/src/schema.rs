@@ -1,3 +1,15 @@
+table! {
+    audit_log (id) {
+        id -> Text,
+        created_at -> Timestamp,
+        actor -> Text,
+        action -> Text,
+        target_id -> Text,
+        reason -> Nullable<Text>,
+        snapshot -> Nullable<Text>,
+    }
+}
+
 table! {
     comments (id) {
         id -> Text,
@@ -10,6 +22,37 @@ table! {
     }
 }
 
+table! {
+    domain_mutes (user_id, domain) {
+        user_id -> Text,
+        domain -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    domain_rules (id) {
+        id -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        created_by -> Text,
+        domain -> Text,
+        action -> Text,
+    }
+}
+
+table! {
+    flags (id) {
+        id -> Text,
+        created_at -> Timestamp,
+        created_by -> Text,
+        url_id -> Nullable<Text>,
+        comment_id -> Nullable<Text>,
+        reason -> Text,
+        dismissed_at -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     invites (id) {
         id -> Text,
@@ -38,6 +81,15 @@ table! {
     }
 }
 
+table! {
+    quota_usage (id) {
+        id -> Text,
+        user_id -> Text,
+        action -> Text,
+        used_at -> Timestamp,
+    }
+}
+
 table! {
     roles (id) {
         id -> Text,
@@ -48,11 +100,82 @@ table! {
     }
 }
 
+table! {
+    tags (id) {
+        id -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        name -> Text,
+    }
+}
+
+table! {
+    url_checks (url_id, checked_at) {
+        url_id -> Text,
+        checked_at -> Timestamp,
+        status_code -> Integer,
+    }
+}
+
+table! {
+    url_edits (id) {
+        id -> Text,
+        created_at -> Timestamp,
+        created_by -> Text,
+        url_id -> Text,
+        title -> Nullable<Text>,
+        description -> Nullable<Text>,
+    }
+}
+
+table! {
+    url_hides (url_id, user_id) {
+        url_id -> Text,
+        user_id -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    url_merges (from_id) {
+        from_id -> Text,
+        into_id -> Text,
+        merged_at -> Timestamp,
+        merged_by -> Text,
+    }
+}
+
+table! {
+    url_redirects (url_id, position) {
+        url_id -> Text,
+        position -> Integer,
+        location -> Text,
+        status_code -> Integer,
+    }
+}
+
+table! {
+    url_saves (url_id, user_id) {
+        url_id -> Text,
+        user_id -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    url_tags (url_id, tag_id) {
+        url_id -> Text,
+        tag_id -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     url_upvotes (url_id, user_id) {
         url_id -> Text,
         user_id -> Text,
         created_at -> Timestamp,
+        discounted_at -> Nullable<Timestamp>,
     }
 }
 
@@ -67,6 +190,28 @@ table! {
         description -> Nullable<Text>,
         image -> Nullable<Text>,
         created_by -> Text,
+        crawl_state -> Text,
+        final_url -> Nullable<Text>,
+        canonical_url -> Nullable<Text>,
+        duplicate_of -> Nullable<Text>,
+        archived_at -> Nullable<Timestamp>,
+        dead_since -> Nullable<Timestamp>,
+        thumbnail_at -> Nullable<Timestamp>,
+        domain -> Text,
+        held_at -> Nullable<Timestamp>,
+        text -> Nullable<Text>,
+        upvote_count -> BigInt,
+        comment_count -> BigInt,
+        title_override -> Nullable<Text>,
+        description_override -> Nullable<Text>,
+    }
+}
+
+table! {
+    user_mutes (user_id, muted_id) {
+        user_id -> Text,
+        muted_id -> Text,
+        created_at -> Timestamp,
     }
 }
 
@@ -77,15 +222,85 @@ table! {
         updated_at -> Timestamp,
         name -> Text,
         email -> Text,
+        feed_token -> Nullable<Text>,
+        public_upvotes -> Bool,
+    }
+}
+
+table! {
+    vote_ring_members (ring_id, user_id) {
+        ring_id -> Text,
+        user_id -> Text,
+    }
+}
+
+table! {
+    vote_rings (id) {
+        id -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        member_key -> Text,
+        ring_upvotes -> BigInt,
+        shared_ip -> Bool,
+        invite_tree -> Bool,
+        single_submitter -> Bool,
+        discounted_at -> Nullable<Timestamp>,
+        dismissed_at -> Nullable<Timestamp>,
     }
 }
 
+joinable!(audit_log -> users (actor));
 joinable!(comments -> urls (url_id));
 joinable!(comments -> users (created_by));
+joinable!(domain_mutes -> users (user_id));
+joinable!(domain_rules -> users (created_by));
+joinable!(flags -> comments (comment_id));
+joinable!(flags -> urls (url_id));
+joinable!(flags -> users (created_by));
 joinable!(logins -> users (user_id));
+joinable!(quota_usage -> users (user_id));
 joinable!(roles -> users (user_id));
+joinable!(url_checks -> urls (url_id));
+joinable!(url_edits -> urls (url_id));
+joinable!(url_edits -> users (created_by));
+joinable!(url_hides -> urls (url_id));
+joinable!(url_hides -> users (user_id));
+joinable!(url_merges -> urls (into_id));
+joinable!(url_merges -> users (merged_by));
+joinable!(url_redirects -> urls (url_id));
+joinable!(url_saves -> urls (url_id));
+joinable!(url_saves -> users (user_id));
+joinable!(url_tags -> tags (tag_id));
+joinable!(url_tags -> urls (url_id));
 joinable!(url_upvotes -> urls (url_id));
 joinable!(url_upvotes -> users (user_id));
 joinable!(urls -> users (created_by));
+joinable!(user_mutes -> users (user_id));
+joinable!(vote_ring_members -> users (user_id));
+joinable!(vote_ring_members -> vote_rings (ring_id));
 
-allow_tables_to_appear_in_same_query!(comments, invites, logins, roles, url_upvotes, urls, users,);
+allow_tables_to_appear_in_same_query!(
+    audit_log,
+    comments,
+    domain_mutes,
+    domain_rules,
+    flags,
+    invites,
+    logins,
+    quota_usage,
+    roles,
+    tags,
+    url_checks,
+    url_edits,
+    url_hides,
+    url_merges,
+    url_redirects,
+    url_saves,
+    url_tags,
+    url_upvotes,
+    urls,
+    user_mutes,
+    users,
+    vote_ring_members,
+    vote_rings,
+);
@@ -0,0 +1,128 @@
+use serde_json::{json, Value};
+mod setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_delete_url_is_audited() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let query = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": "https://example.com/audited" });
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let url_id = body["data"]["submitUrl"]["id"].clone();
+
+    let query = "
+        mutation DeleteUrl($url: ID!) {
+            deleteUrl(url: $url, reason: \"Spam\") {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body, json!({ "data": { "deleteUrl": { "id": url_id } } }));
+
+    let query_log = "
+        query AuditLog {
+            auditLog(first: 10) {
+                edges {
+                    node {
+                        action
+                        targetId
+                        reason
+                        snapshot
+                        actor {
+                            name
+                        }
+                    }
+                }
+            }
+        }
+    ";
+
+    // only admins can access the audit log
+    let res = setup::graphql(query_log, json!(null), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    assert!(body.as_object().unwrap().contains_key("errors"));
+
+    let res = setup::graphql(query_log, json!(null), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let edges = body["data"]["auditLog"]["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 1);
+
+    let node = &edges[0]["node"];
+    assert_eq!(node["action"], json!("DELETE_URL"));
+    assert_eq!(node["targetId"], url_id);
+    assert_eq!(node["reason"], json!("Spam"));
+
+    let snapshot: Value = serde_json::from_str(node["snapshot"].as_str().unwrap()).unwrap();
+    assert_eq!(snapshot["url"], json!("https://example.com/audited"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_domain_rules_are_audited() {
+    let (server, ctx) = setup::mock().await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let query = "
+        mutation SetDomainRule($domain: String!, $action: DomainAction!) {
+            setDomainRule(domain: $domain, action: $action) {
+                domain
+            }
+        }
+    ";
+    for action in ["REQUIRE_MODERATION", "BLOCK"] {
+        let vars = json!({ "domain": "spam.example", "action": action });
+        setup::graphql(query, vars, &session_admin)
+            .reply(&server)
+            .await;
+    }
+
+    let query_log = "
+        query AuditLog {
+            auditLog(first: 10) {
+                edges {
+                    node {
+                        action
+                        snapshot
+                    }
+                }
+            }
+        }
+    ";
+    let res = setup::graphql(query_log, json!(null), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let edges = body["data"]["auditLog"]["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 2);
+
+    let snapshots: Vec<Value> = edges
+        .iter()
+        .map(|edge| {
+            assert_eq!(edge["node"]["action"], json!("SET_DOMAIN_RULE"));
+            serde_json::from_str(edge["node"]["snapshot"].as_str().unwrap()).unwrap()
+        })
+        .collect();
+    assert!(snapshots.contains(&json!({
+        "domain": "spam.example",
+        "action": "Block",
+        "previous_action": "Require moderation",
+    })));
+}
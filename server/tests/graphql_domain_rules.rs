@@ -0,0 +1,209 @@
+use serde_json::{json, Value};
+mod setup;
+
+const SUBMIT_URL: &str = "
+    mutation SubmitUrl($url: String!) {
+        submitUrl(input: { url: $url }) {
+            id
+            heldAt
+        }
+    }
+";
+
+const SET_RULE: &str = "
+    mutation SetDomainRule($domain: String!, $action: DomainAction!) {
+        setDomainRule(domain: $domain, action: $action) {
+            domain
+            action
+        }
+    }
+";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_and_allow_domains() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let vars = json!({ "url": "https://blog.spam.example/old" });
+    let res = setup::graphql(SUBMIT_URL, vars, &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"]["submitUrl"]["id"].is_string());
+
+    // only admins can manage domain rules
+    let vars = json!({ "domain": "spam.example", "action": "BLOCK" });
+    let res = setup::graphql(SET_RULE, vars.clone(), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    assert!(body.as_object().unwrap().contains_key("errors"));
+
+    let res = setup::graphql(SET_RULE, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({ "data": { "setDomainRule": { "domain": "spam.example", "action": "BLOCK" } } })
+    );
+
+    // subdomains are blocked as well
+    let vars = json!({ "url": "https://www.blog.spam.example/new" });
+    let res = setup::graphql(SUBMIT_URL, vars.clone(), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    assert!(body.as_object().unwrap().contains_key("errors"));
+
+    // more specific rules take precedence
+    let rule = json!({ "domain": "blog.spam.example", "action": "ALLOW" });
+    setup::graphql(SET_RULE, rule, &session_admin)
+        .reply(&server)
+        .await;
+    let res = setup::graphql(SUBMIT_URL, vars, &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let new_id = body["data"]["submitUrl"]["id"].clone();
+    assert!(new_id.is_string());
+
+    // existing submissions can be reviewed in bulk
+    let query = "
+        query Rules {
+            domainRules(first: 10) {
+                edges {
+                    node {
+                        domain
+                        matchingUrlCount
+                        matchingUrls(first: 1) {
+                            edges {
+                                node {
+                                    id
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    ";
+    let res = setup::graphql(query, json!(null), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let rules = &body["data"]["domainRules"]["edges"];
+    assert_eq!(rules[0]["node"]["domain"], json!("blog.spam.example"));
+    assert_eq!(rules[1]["node"]["domain"], json!("spam.example"));
+    assert_eq!(rules[1]["node"]["matchingUrlCount"], json!(2));
+    assert_eq!(
+        rules[1]["node"]["matchingUrls"]["edges"][0]["node"]["id"],
+        new_id
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_hold_for_moderation() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let rule = json!({ "domain": "held.example", "action": "REQUIRE_MODERATION" });
+    setup::graphql(SET_RULE, rule, &session_admin)
+        .reply(&server)
+        .await;
+
+    let vars = json!({ "url": "https://held.example/post" });
+    let res = setup::graphql(SUBMIT_URL, vars, &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let url_id = body["data"]["submitUrl"]["id"].clone();
+    assert!(body["data"]["submitUrl"]["heldAt"].is_string());
+
+    let query_listed = "
+        query Submissions {
+            submissions(first: 10) {
+                edges {
+                    node {
+                        id
+                    }
+                }
+            }
+        }
+    ";
+    let res = setup::graphql(query_listed, json!(null), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["data"]["submissions"]["edges"], json!([]));
+
+    // only the submitter and moderators can open a held submission
+    let query_url = "
+        query Url($url: ID!) {
+            fetch__Url(id: $url) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(query_url, vars.clone(), "")
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    for viewer in &[&session, &session_admin] {
+        let res = setup::graphql(query_url, vars.clone(), viewer)
+            .reply(&server)
+            .await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(body["data"]["fetch__Url"]["id"], url_id);
+    }
+
+    let path = format!("/comments/{}", url_id.as_str().unwrap());
+    let res = warp::test::request().path(&path).reply(&server).await;
+    assert_eq!(res.status(), 404);
+    for viewer in &[&session, &session_admin] {
+        let res = warp::test::request()
+            .path(&path)
+            .header("Cookie", format!("session={}", viewer))
+            .reply(&server)
+            .await;
+        assert_eq!(res.status(), 200);
+    }
+
+    let query = "
+        mutation ApproveUrl($url: ID!) {
+            approveUrl(url: $url) {
+                heldAt
+            }
+        }
+    ";
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(query, vars.clone(), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({ "data": { "approveUrl": { "heldAt": null } } })
+    );
+
+    let res = setup::graphql(query_listed, json!(null), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body["data"]["submissions"]["edges"],
+        json!([{ "node": { "id": url_id } }])
+    );
+}
@@ -0,0 +1,171 @@
+use serde_json::{json, Value};
+mod setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_flag_and_dismiss_url() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let query = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": "https://example.com/spam" });
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let url_id = body["data"]["submitUrl"]["id"].clone();
+
+    let query = "
+        mutation FlagUrl($url: ID!) {
+            flagUrl(url: $url, reason: SPAM) {
+                flaggedByViewer
+            }
+        }
+    ";
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(query, vars.clone(), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({ "data": { "flagUrl": { "flaggedByViewer": true } } })
+    );
+
+    // flagging the same url twice fails
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    assert!(body.as_object().unwrap().contains_key("errors"));
+
+    let query_queue = "
+        query Queue {
+            flaggedUrls(first: 10) {
+                edges {
+                    node {
+                        id
+                        flags {
+                            reason
+                        }
+                    }
+                }
+            }
+        }
+    ";
+
+    // only moderators can access the queue
+    let res = setup::graphql(query_queue, json!(null), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    assert!(body.as_object().unwrap().contains_key("errors"));
+
+    let res = setup::graphql(query_queue, json!(null), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body["data"]["flaggedUrls"]["edges"],
+        json!([{ "node": { "id": url_id, "flags": [{ "reason": "SPAM" }] } }])
+    );
+
+    let query = "
+        mutation DismissUrlFlags($url: ID!) {
+            dismissUrlFlags(url: $url) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(query, vars.clone(), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body.as_object().unwrap().contains_key("errors"));
+
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["data"]["dismissUrlFlags"]["id"], url_id);
+
+    let res = setup::graphql(query_queue, json!(null), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["data"]["flaggedUrls"]["edges"], json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_flag_queue_order() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let query = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let mut ids = vec![];
+    for url in ["https://example.com/once", "https://example.com/twice"] {
+        let vars = json!({ "url": url });
+        let res = setup::graphql(query, vars, &session).reply(&server).await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        ids.push(body["data"]["submitUrl"]["id"].clone());
+    }
+    let (once, twice) = (ids[0].clone(), ids[1].clone());
+
+    let query = "
+        mutation FlagUrl($url: ID!) {
+            flagUrl(url: $url, reason: SPAM) {
+                id
+            }
+        }
+    ";
+    for (url, viewer) in [
+        (&once, &session),
+        (&twice, &session),
+        (&twice, &session_admin),
+    ] {
+        let vars = json!({ "url": url });
+        setup::graphql(query, vars, viewer).reply(&server).await;
+    }
+
+    // the most flagged url comes first
+    let query = "
+        query Queue($after: String) {
+            flaggedUrls(first: 1, after: $after) {
+                edges {
+                    node {
+                        id
+                    }
+                }
+                pageInfo {
+                    endCursor
+                }
+            }
+        }
+    ";
+    let res = setup::graphql(query, json!({ "after": null }), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let queue = &body["data"]["flaggedUrls"];
+    assert_eq!(queue["edges"], json!([{ "node": { "id": twice } }]));
+
+    let vars = json!({ "after": queue["pageInfo"]["endCursor"] });
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let queue = &body["data"]["flaggedUrls"];
+    assert_eq!(queue["edges"], json!([{ "node": { "id": once } }]));
+}
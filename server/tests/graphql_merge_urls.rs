@@ -0,0 +1,138 @@
+use serde_json::{json, Value};
+mod setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_merge_urls() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let query = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let mut ids = vec![];
+    for url in ["https://example.com/story", "https://example.com/story-amp"] {
+        let vars = json!({ "url": url });
+        let res = setup::graphql(query, vars, &session).reply(&server).await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        ids.push(body["data"]["submitUrl"]["id"].clone());
+    }
+    let (into, from) = (ids[0].clone(), ids[1].clone());
+
+    // the user upvotes both submissions, the admin only the duplicate
+    let query = "
+        mutation UpvoteUrl($url: ID!) {
+            upvoteUrl(url: $url) {
+                id
+            }
+        }
+    ";
+    for id in [&into, &from] {
+        let vars = json!({ "url": id });
+        setup::graphql(query, vars, &session).reply(&server).await;
+    }
+    let vars = json!({ "url": from });
+    setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+
+    let query = "
+        mutation Comment($url: ID!) {
+            comment(input: { comment: \"Same story\", url: $url }) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": from });
+    setup::graphql(query, vars, &session).reply(&server).await;
+
+    let query = "
+        mutation MergeUrls($from: ID!, $into: ID!) {
+            mergeUrls(from: $from, into: $into) {
+                id
+                upvoteCount
+                comments(first: 10) {
+                    edges {
+                        node {
+                            text
+                        }
+                    }
+                }
+            }
+        }
+    ";
+    let vars = json!({ "from": from, "into": into });
+
+    // only moderators can merge submissions
+    let res = setup::graphql(query, vars.clone(), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    assert!(body.as_object().unwrap().contains_key("errors"));
+
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "data": {
+                "mergeUrls": {
+                    "id": into,
+                    "upvoteCount": 2,
+                    "comments": { "edges": [{ "node": { "text": "Same story" } }] },
+                }
+            }
+        })
+    );
+
+    let res = warp::test::request()
+        .path(&format!("/comments/{}", from.as_str().unwrap()))
+        .reply(&server)
+        .await;
+    assert_eq!(res.status(), 301);
+    assert_eq!(
+        res.headers()["location"],
+        format!("/comments/{}", into.as_str().unwrap())
+    );
+
+    // submissions can not be merged into held submissions
+    let query_rule = "
+        mutation SetDomainRule($domain: String!) {
+            setDomainRule(domain: $domain, action: REQUIRE_MODERATION) {
+                domain
+            }
+        }
+    ";
+    let vars = json!({ "domain": "held.example" });
+    setup::graphql(query_rule, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let query_submit = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": "https://held.example/story" });
+    let res = setup::graphql(query_submit, vars, &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let held = body["data"]["submitUrl"]["id"].clone();
+
+    let vars = json!({ "from": into, "into": held });
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    assert!(body.as_object().unwrap().contains_key("errors"));
+}
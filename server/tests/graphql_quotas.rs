@@ -0,0 +1,50 @@
+use serde_json::{json, Value};
+mod setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_submission_quota() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let query = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let per_hour = ctx.rate_limits().submissions(true).per_hour();
+    for n in 0..per_hour {
+        let vars = json!({ "url": format!("https://example.com/story-{}", n) });
+        let res = setup::graphql(query, vars, &session).reply(&server).await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        assert!(body["data"]["submitUrl"]["id"].is_string());
+    }
+
+    // the new account has used up its hourly quota
+    let vars = json!({ "url": "https://example.com/one-more" });
+    let res = setup::graphql(query, vars.clone(), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+    let error = &body["errors"][0];
+    assert!(error["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Exceeded the limit of"));
+    assert_eq!(error["extensions"]["code"], "QUOTA_EXCEEDED");
+    assert_eq!(error["extensions"]["remainingHour"], 0);
+    assert!(error["extensions"]["resetsAt"].is_string());
+
+    // moderators are not limited
+    for n in 0..=per_hour {
+        let vars = json!({ "url": format!("https://example.com/admin-{}", n) });
+        let res = setup::graphql(query, vars, &session_admin)
+            .reply(&server)
+            .await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        assert!(body["data"]["submitUrl"]["id"].is_string());
+    }
+}
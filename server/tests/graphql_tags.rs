@@ -0,0 +1,150 @@
+use serde_json::{json, Value};
+mod setup;
+
+const SUBMIT_URL: &str = "
+    mutation SubmitUrl($url: String!, $tags: [String!]) {
+        submitUrl(input: { url: $url, tags: $tags }) {
+            id
+        }
+    }
+";
+
+const URL_TAGS: &str = "
+    query Url($url: ID!) {
+        fetch__Url(id: $url) {
+            tags {
+                name
+            }
+        }
+    }
+";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_retag_url_permissions() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let vars = json!({ "url": "https://example.com/own", "tags": ["rust"] });
+    let res = setup::graphql(SUBMIT_URL, vars, &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let own_id = body["data"]["submitUrl"]["id"].clone();
+
+    let vars = json!({ "url": "https://example.com/other", "tags": ["rust"] });
+    let res = setup::graphql(SUBMIT_URL, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let other_id = body["data"]["submitUrl"]["id"].clone();
+
+    let query = "
+        mutation RetagUrl($url: ID!, $tags: [String!]!) {
+            retagUrl(url: $url, tags: $tags) {
+                tags {
+                    name
+                }
+            }
+        }
+    ";
+
+    // users can retag their own submissions
+    let vars = json!({ "url": own_id, "tags": ["Databases", "rust"] });
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body["data"]["retagUrl"]["tags"],
+        json!([{ "name": "databases" }, { "name": "rust" }])
+    );
+
+    // but not those of other users
+    let vars = json!({ "url": other_id, "tags": ["spam"] });
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+
+    let vars = json!({ "url": other_id });
+    let res = setup::graphql(URL_TAGS, vars, &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body["data"]["fetch__Url"]["tags"],
+        json!([{ "name": "rust" }])
+    );
+
+    // moderators can retag any submission
+    let vars = json!({ "url": own_id, "tags": [] });
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["data"]["retagUrl"]["tags"], json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_merge_tags() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let mut url_ids = vec![];
+    for (url, tags) in [
+        ("https://example.com/both", json!(["rust", "rustlang"])),
+        ("https://example.com/old", json!(["rustlang"])),
+    ] {
+        let vars = json!({ "url": url, "tags": tags });
+        let res = setup::graphql(SUBMIT_URL, vars, &session)
+            .reply(&server)
+            .await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        url_ids.push(body["data"]["submitUrl"]["id"].clone());
+    }
+
+    let query = "
+        mutation MergeTags($from: String!, $into: String!) {
+            mergeTags(from: $from, into: $into) {
+                name
+                urlCount
+            }
+        }
+    ";
+    let vars = json!({ "from": "rustlang", "into": "rust" });
+
+    // only moderators can merge tags
+    let res = setup::graphql(query, vars.clone(), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({ "data": { "mergeTags": { "name": "rust", "urlCount": 2 } } })
+    );
+
+    // submissions which had both tags are only labeled once
+    for url_id in &url_ids {
+        let vars = json!({ "url": url_id });
+        let res = setup::graphql(URL_TAGS, vars, &session)
+            .reply(&server)
+            .await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(
+            body["data"]["fetch__Url"]["tags"],
+            json!([{ "name": "rust" }])
+        );
+    }
+
+    let query = "{ tag(name: \"rustlang\") { name } }";
+    let res = setup::graphql(query, json!(null), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+}
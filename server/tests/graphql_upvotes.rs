@@ -0,0 +1,201 @@
+use serde_json::{json, Value};
+mod setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_upvotes_visibility() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    // the admin submits and the user upvotes
+    let query = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": "https://example.com/upvoted" });
+    let res = setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let url_id = body["data"]["submitUrl"]["id"].clone();
+
+    let query = "
+        mutation Upvote($url: ID!) {
+            upvoteUrl(url: $url) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["data"]["upvoteUrl"]["id"], url_id);
+
+    let query = "{ viewer { user { id } } }";
+    let res = setup::graphql(query, json!({}), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let user_id = body["data"]["viewer"]["user"]["id"].clone();
+
+    let upvotes = "
+        query Upvotes($url: ID!) {
+            fetch__Url(id: $url) {
+                upvotes(first: 10) {
+                    edges {
+                        node {
+                            user {
+                                id
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    ";
+    let upvoted_urls = "
+        query UpvotedUrls($user: ID!) {
+            fetch__User(id: $user) {
+                upvotedUrls(first: 10) {
+                    edges {
+                        node {
+                            id
+                        }
+                    }
+                }
+            }
+        }
+    ";
+    let upvoters = |body: &Value| -> Vec<Value> {
+        body["data"]["fetch__Url"]["upvotes"]["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| edge["node"]["user"]["id"].clone())
+            .collect()
+    };
+
+    // moderators see every upvote
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(upvotes, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(upvoters(&body), vec![user_id.clone()]);
+
+    let vars = json!({ "user": user_id });
+    let res = setup::graphql(upvoted_urls, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body["data"]["fetch__User"]["upvotedUrls"]["edges"],
+        json!([{ "node": { "id": url_id } }])
+    );
+
+    // everyone else only sees public upvotes
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(upvotes, vars, "").reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(upvoters(&body), Vec::<Value>::new());
+
+    let vars = json!({ "user": user_id });
+    let res = setup::graphql(upvoted_urls, vars, "").reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body["errors"][0]["message"],
+        "Upvotes of this user are private"
+    );
+
+    // once the user opts in, their upvotes are public
+    let query = "
+        mutation {
+            updateUser(input: { publicUpvotes: true }) {
+                user {
+                    publicUpvotes
+                }
+            }
+        }
+    ";
+    let res = setup::graphql(query, json!({}), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["data"]["updateUser"]["user"]["publicUpvotes"], true);
+
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(upvotes, vars, "").reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(upvoters(&body), vec![user_id.clone()]);
+
+    let vars = json!({ "user": user_id });
+    let res = setup::graphql(upvoted_urls, vars, "").reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body["data"]["fetch__User"]["upvotedUrls"]["edges"],
+        json!([{ "node": { "id": url_id } }])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_upvotes_of_held_submissions() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    let query = "
+        mutation SetDomainRule($domain: String!) {
+            setDomainRule(domain: $domain, action: REQUIRE_MODERATION) {
+                domain
+            }
+        }
+    ";
+    let vars = json!({ "domain": "held.example" });
+    setup::graphql(query, vars, &session_admin)
+        .reply(&server)
+        .await;
+
+    let query = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let vars = json!({ "url": "https://held.example/upvoted" });
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let url_id = body["data"]["submitUrl"]["id"].clone();
+
+    // only the submitter and moderators see who upvoted a held submission
+    let upvotes = "
+        query Upvotes($url: ID!) {
+            fetch__Url(id: $url) {
+                upvotes(first: 10) {
+                    edges {
+                        node {
+                            createdAt
+                        }
+                    }
+                }
+            }
+        }
+    ";
+    let vars = json!({ "url": url_id });
+    let res = setup::graphql(upvotes, vars.clone(), "")
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["errors"].is_array());
+
+    for viewer in &[&session, &session_admin] {
+        let res = setup::graphql(upvotes, vars.clone(), viewer)
+            .reply(&server)
+            .await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        assert!(body["errors"].is_null());
+    }
+}
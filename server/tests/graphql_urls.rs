@@ -0,0 +1,55 @@
+use serde_json::{json, Value};
+mod setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_submit_url_is_crawled_later() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+
+    let query = "
+        mutation SubmitUrl($url: String!, $tags: [String!]) {
+            submitUrl(input: { url: $url, tags: $tags }) {
+                url
+                crawlState
+                title
+                tags {
+                    name
+                }
+            }
+        }
+    ";
+
+    let vars = json!({
+        "url": "https://example.com/submitted?utm_source=test",
+        "tags": ["Rust", "#web dev"],
+    });
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    assert_eq!(res.status(), 200);
+
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "data": {
+                "submitUrl": {
+                    "url": "https://example.com/submitted",
+                    "crawlState": "PENDING",
+                    "title": null,
+                    "tags": [
+                        { "name": "rust" },
+                        { "name": "web-dev" },
+                    ],
+                },
+            },
+        })
+    );
+
+    // submitting the same url twice fails
+    let vars = json!({ "url": "https://example.com/submitted" });
+    let res = setup::graphql(query, vars, &session).reply(&server).await;
+    assert_eq!(res.status(), 200);
+
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body.as_object().unwrap().get("data").unwrap().is_null());
+    assert!(body.as_object().unwrap().contains_key("errors"));
+}
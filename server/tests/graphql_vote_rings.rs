@@ -0,0 +1,173 @@
+use serde_json::{json, Value};
+use server::db::models::VoteRing;
+mod setup;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_discount_vote_ring() {
+    let (server, ctx) = setup::mock().await;
+    let session = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let session_admin = setup::session_token(&ctx, "test.admin@urls.fyi").await;
+
+    // the user upvotes every submission of the admin
+    let submit = "
+        mutation SubmitUrl($url: String!) {
+            submitUrl(input: { url: $url }) {
+                id
+            }
+        }
+    ";
+    let upvote = "
+        mutation Upvote($url: ID!) {
+            upvoteUrl(url: $url) {
+                upvoteCount
+            }
+        }
+    ";
+    let mut url_ids = vec![];
+    for n in 0..5 {
+        let vars = json!({ "url": format!("https://example.com/ring-{}", n) });
+        let res = setup::graphql(submit, vars, &session_admin)
+            .reply(&server)
+            .await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        let url_id = body["data"]["submitUrl"]["id"].clone();
+
+        let vars = json!({ "url": url_id });
+        let res = setup::graphql(upvote, vars, &session).reply(&server).await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(body["data"]["upvoteUrl"]["upvoteCount"], 1);
+        url_ids.push(url_id);
+    }
+
+    assert_eq!(VoteRing::detect(&ctx).await.unwrap(), 1);
+
+    let query = "
+        {
+            voteRings(first: 10) {
+                edges {
+                    node {
+                        id
+                        ringUpvotes
+                        singleSubmitter
+                        members {
+                            name
+                        }
+                    }
+                }
+            }
+        }
+    ";
+
+    // only administrators can see the report
+    let res = setup::graphql(query, json!({}), &session)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["errors"].is_array());
+
+    let res = setup::graphql(query, json!({}), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let edges = body["data"]["voteRings"]["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 1);
+    let ring = &edges[0]["node"];
+    assert_eq!(ring["ringUpvotes"], 5);
+    assert_eq!(ring["singleSubmitter"], true);
+    assert_eq!(ring["members"].as_array().unwrap().len(), 2);
+
+    let discount = "
+        mutation Discount($ring: ID!) {
+            discountVoteRing(ring: $ring, reason: \"Upvotes every submission\") {
+                ringUpvotes
+                discountedAt
+            }
+        }
+    ";
+    let vars = json!({ "ring": ring["id"] });
+    let res = setup::graphql(discount, vars.clone(), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["data"]["discountVoteRing"]["ringUpvotes"], 0);
+    assert!(body["data"]["discountVoteRing"]["discountedAt"].is_string());
+
+    // a ring is only discounted once
+    let res = setup::graphql(discount, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"].is_null());
+
+    // discounted upvotes no longer count
+    let query = "
+        query Url($url: ID!) {
+            fetch__Url(id: $url) {
+                upvoteCount
+                upvotedByViewer
+            }
+        }
+    ";
+    for url_id in &url_ids {
+        let vars = json!({ "url": url_id });
+        let res = setup::graphql(query, vars, &session).reply(&server).await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(
+            body["data"]["fetch__Url"],
+            json!({ "upvoteCount": 0, "upvotedByViewer": true })
+        );
+    }
+
+    // the discounted ring is not reported again
+    assert_eq!(VoteRing::detect(&ctx).await.unwrap(), 0);
+    let query = "{ voteRings(first: 10) { edges { node { id } } } }";
+    let res = setup::graphql(query, json!({}), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(body["data"]["voteRings"]["edges"], json!([]));
+
+    // upvoting each other again reports the ring again
+    for n in 5..10 {
+        let vars = json!({ "url": format!("https://example.com/ring-{}", n) });
+        let res = setup::graphql(submit, vars, &session_admin)
+            .reply(&server)
+            .await;
+        let body: Value = serde_json::from_slice(res.body()).unwrap();
+        let vars = json!({ "url": body["data"]["submitUrl"]["id"] });
+        setup::graphql(upvote, vars, &session).reply(&server).await;
+    }
+    assert_eq!(VoteRing::detect(&ctx).await.unwrap(), 1);
+    let query = "{ voteRings(first: 10) { edges { node { id ringUpvotes discountedAt } } } }";
+    let res = setup::graphql(query, json!({}), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    let ring = &body["data"]["voteRings"]["edges"][0]["node"];
+    assert_eq!(ring["ringUpvotes"], 5);
+    assert!(ring["discountedAt"].is_null());
+
+    let dismiss = "
+        mutation Dismiss($ring: ID!) {
+            dismissVoteRing(ring: $ring, reason: \"Friends\") {
+                dismissedAt
+            }
+        }
+    ";
+    let vars = json!({ "ring": ring["id"] });
+    let res = setup::graphql(dismiss, vars, &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert!(body["data"]["dismissVoteRing"]["dismissedAt"].is_string());
+
+    let query = "{ auditLog(first: 10) { edges { node { action reason } } } }";
+    let res = setup::graphql(query, json!({}), &session_admin)
+        .reply(&server)
+        .await;
+    let body: Value = serde_json::from_slice(res.body()).unwrap();
+    assert_eq!(
+        body["data"]["auditLog"]["edges"][0]["node"],
+        json!({ "action": "DISMISS_VOTE_RING", "reason": "Friends" })
+    );
+}
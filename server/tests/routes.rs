@@ -25,10 +25,25 @@ check_status!(recent_page, "/recent", 200);
 check_status!(rss_feed, "/feed.xml", 200);
 check_status!(search_page, "/search", 200);
 check_status!(login_page, "/login", 200);
+check_status!(submit_page, "/submit?url=https%3A%2F%2Furls.fyi", 200);
 check_status!(logout_page, "/logout", 307);
 check_status!(account_page, "/account", 307);
 check_status!(graphiql_page, "/graphql/playground", 200);
 check_status!(not_found, "/404", 404);
+check_status!(unknown_tag, "/tag/unknown", 404);
+check_status!(unknown_domain, "/domain/unknown.example", 404);
+check_status!(unknown_archive, "/archive/unknown", 404);
+check_status!(unknown_thumbnail, "/img/unknown", 404);
+check_status!(dead_links_no_user, "/admin/dead", 404);
+check_status!(moderation_no_user, "/moderation", 404);
+check_status!(audit_log_no_user, "/admin/audit", 404);
+check_status!(vote_rings_no_user, "/admin/rings", 404);
+check_status!(saved_no_user, "/saved", 307);
+check_status!(
+    saved_feed_unknown_token,
+    "/saved/feed.xml?token=unknown",
+    404
+);
 
 #[tokio::test(flavor = "multi_thread")]
 async fn admin_backup_permissions() {
@@ -106,3 +121,68 @@ async fn server_side_cookies_are_set_correctly() {
         .iter()
         .any(|v| v.contains(&format!("session={}", sess_user))));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submit_form() {
+    let (server, ctx) = setup::mock().await;
+    let sess_user = setup::session_token(&ctx, "test.user@urls.fyi").await;
+    let form = "xsrf_token=test-xsrf&url=https%3A%2F%2Furls.fyi%2Fshared&tags=rust";
+
+    let res_bad_xsrf = warp::test::request()
+        .method("POST")
+        .path("/submit")
+        .header("Cookie", format!("xsrf=other-xsrf; session={}", sess_user))
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(form)
+        .reply(&server)
+        .await;
+    assert_eq!(res_bad_xsrf.status(), 400);
+
+    let res_no_user = warp::test::request()
+        .method("POST")
+        .path("/submit")
+        .header("Cookie", "xsrf=test-xsrf")
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(form)
+        .reply(&server)
+        .await;
+    assert_eq!(res_no_user.status(), 200);
+    assert!(String::from_utf8_lossy(res_no_user.body()).contains("log in to submit"));
+
+    let res_user = warp::test::request()
+        .method("POST")
+        .path("/submit")
+        .header("Cookie", format!("xsrf=test-xsrf; session={}", sess_user))
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(form)
+        .reply(&server)
+        .await;
+    assert_eq!(res_user.status(), 303);
+    let location = res_user.headers()["location"].to_str().unwrap();
+    assert!(location.starts_with("/comments/"));
+
+    // share targets may pass the link as part of the text
+    let res_existing = warp::test::request()
+        .path("/submit?text=Look%20at%20this%20https%3A%2F%2Furls.fyi%2Fshared")
+        .reply(&server)
+        .await;
+    assert_eq!(res_existing.status(), 200);
+    let body = String::from_utf8_lossy(res_existing.body());
+    assert!(body.contains("already submitted"));
+    assert!(body.contains(location));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn submit_bookmarklet_uses_configured_host() {
+    let (server, _) = setup::mock().await;
+    let res = warp::test::request()
+        .path("/submit")
+        .header("Host", "evil.example')+alert(1)+('")
+        .reply(&server)
+        .await;
+    assert_eq!(res.status(), 200);
+    let body = String::from_utf8_lossy(res.body());
+    assert!(body.contains("javascript:location.href="));
+    assert!(body.contains("localhost"));
+    assert!(!body.contains("evil.example"));
+}
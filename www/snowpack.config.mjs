@@ -16,6 +16,7 @@ export default {
       "src/comments.jsx",
       "src/header.jsx",
       "src/login.jsx",
+      "src/moderation.jsx",
       "src/register.jsx",
       "src/search.jsx",
     ],
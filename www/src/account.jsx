@@ -10,15 +10,20 @@ import ManageInvites from "@app/account/ManageInvites";
 import ChangeName from "@app/account/ChangeName";
 import ChangeEmail from "@app/account/ChangeEmail";
 import ManageLogins from "@app/account/ManageLogins";
+import SavedFeed from "@app/account/SavedFeed";
+import ManageMutes from "@app/account/ManageMutes";
+import PublicUpvotes from "@app/account/PublicUpvotes";
 
 function Account() {
   const { data, loading } = useQuery(graphql`
     query AccountQuery {
       viewer {
         id
+        savedFeedPath
         user {
           id
           name
+          publicUpvotes
         }
       }
     }
@@ -46,6 +51,18 @@ function Account() {
           <Section title="Active sessions" initiallyExpanded={false}>
             <ManageLogins />
           </Section>
+          <Section title="Saved links feed" initiallyExpanded={false}>
+            <SavedFeed currentPath={data?.viewer?.savedFeedPath} />
+          </Section>
+          <Section title="Public upvotes" initiallyExpanded={false}>
+            <PublicUpvotes
+              userId={data?.viewer?.user?.id}
+              currentValue={data?.viewer?.user?.publicUpvotes}
+            />
+          </Section>
+          <Section title="Hidden and muted" initiallyExpanded={false}>
+            <ManageMutes />
+          </Section>
           <Section title="Invite a friend" initiallyExpanded={false}>
             <ManageInvites />
           </Section>
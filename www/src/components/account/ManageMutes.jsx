@@ -0,0 +1,140 @@
+import { Fragment, h } from "preact";
+import { useState } from "preact/hooks";
+import { graphql, useMutation, useQuery } from "picoql";
+
+import ActivityIndicator from "@app/ActivityIndicator";
+import TextInput from "@app/TextInput";
+import Button from "@app/Button";
+import Notice from "@app/Notice";
+
+function Row({ href, title, action, onClick }) {
+  return <div class="flex items-center justify-between space-x-2">
+    <a class="truncate hover:underline" href={href}>{title}</a>
+    <Button title={action} type="flat" onClick={onClick} />
+  </div>;
+}
+
+export default function ManageMutes() {
+  const [domain, setDomain] = useState("");
+  const [error, setError] = useState(null);
+
+  const { data, loading, refetch } = useQuery(graphql`
+    query ManageMutesQuery {
+      viewer {
+        id
+        mutedDomains
+        mutedUsers {
+          id
+          name
+        }
+        hiddenUrls {
+          id
+          title
+          url
+        }
+      }
+    }
+  `);
+
+  const onCommit = () => {
+    setError(null);
+    refetch();
+  };
+  const onError = ([{message}]) => setError(message);
+
+  const muteDomain = useMutation(graphql`
+    mutation MuteDomain($domain: String!) {
+      muteDomain(domain: $domain) {
+        id
+      }
+    }
+  `, {
+    onCommit: () => {
+      setDomain("");
+      onCommit();
+    },
+    onError,
+  });
+  const unmuteDomain = useMutation(graphql`
+    mutation UnmuteDomain($domain: String!) {
+      unmuteDomain(domain: $domain) {
+        id
+      }
+    }
+  `, { onCommit, onError });
+  const unmuteUser = useMutation(graphql`
+    mutation UnmuteUser($user: ID!) {
+      unmuteUser(user: $user) {
+        id
+      }
+    }
+  `, { onCommit, onError });
+  const unhideUrl = useMutation(graphql`
+    mutation UnhideUrl($url: ID!) {
+      unhideUrl(url: $url) {
+        id
+      }
+    }
+  `, { onCommit, onError });
+
+  const canSubmit = !muteDomain.inFlight && domain.trim().length > 0;
+  const submit = e => {
+    e.preventDefault();
+    if (canSubmit)
+      muteDomain.commit({ domain: domain.trim() });
+  };
+
+  const domains = data?.viewer?.mutedDomains ?? [];
+  const users = data?.viewer?.mutedUsers ?? [];
+  const urls = data?.viewer?.hiddenUrls ?? [];
+
+  return <div class="flex flex-col gap-y-2">
+    {error && <Notice message={error} type="error" />}
+    <p class="text-sm text-gray-500">
+      Submissions you hid, and submissions from muted domains or
+      users, are left out of your listings, feeds, and search results.
+    </p>
+    <form onSubmit={submit}>
+      <TextInput
+        label="Mute a domain"
+        placeholder="example.com"
+        value={domain}
+        onChange={setDomain}
+        style="mb-2"
+      />
+      <Button
+        title="Mute"
+        onClick={submit}
+        disabled={!canSubmit}
+        loading={muteDomain.inFlight}
+        style="w-full"
+      />
+    </form>
+    {loading ? <ActivityIndicator size="large" style="my-4 mx-auto" /> : <>
+      <h3 class="font-semibold">Muted domains</h3>
+      {domains.length === 0 && <p class="text-gray-500">No muted domains</p>}
+      {domains.map(domain => <Row
+        href={`/domain/${domain}`}
+        title={domain}
+        action="Unmute"
+        onClick={() => unmuteDomain.commit({ domain })}
+      />)}
+      <h3 class="font-semibold">Muted users</h3>
+      {users.length === 0 && <p class="text-gray-500">No muted users</p>}
+      {users.map(user => <Row
+        href={`/user/${user.id}`}
+        title={user.name}
+        action="Unmute"
+        onClick={() => unmuteUser.commit({ user: user.id })}
+      />)}
+      <h3 class="font-semibold">Hidden links</h3>
+      {urls.length === 0 && <p class="text-gray-500">No hidden links</p>}
+      {urls.map(url => <Row
+        href={`/comments/${url.id}`}
+        title={url.title ?? url.url}
+        action="Unhide"
+        onClick={() => unhideUrl.commit({ url: url.id })}
+      />)}
+    </>}
+  </div>;
+}
@@ -0,0 +1,54 @@
+import { h } from "preact";
+import { useState } from "preact/hooks";
+import { graphql, useMutation } from "picoql";
+
+import Button from "@app/Button";
+import Notice from "@app/Notice";
+
+export default function PublicUpvotes({ userId, currentValue }) {
+  const [isPublic, setPublic] = useState(!!currentValue);
+  const [error, setError] = useState(null);
+
+  const { commit, inFlight } = useMutation(graphql`
+    mutation SetPublicUpvotes($value: Boolean!) {
+      updateUser(input: {publicUpvotes: $value}) {
+        id
+        user {
+          id
+          publicUpvotes
+        }
+      }
+    }
+  `, {
+    onCommit: ({ updateUser }) => {
+      setPublic(!!updateUser?.user?.publicUpvotes);
+      setError(null);
+    },
+    onError: ([{message}]) => setError(`Failed to update upvotes: ${message}`),
+  });
+
+  const submit = e => {
+    e.preventDefault();
+    if (!inFlight)
+      commit({ value: !isPublic });
+  };
+
+  return <form onSubmit={submit}>
+    {error && <Notice message={error} type="error" style="mb-2" />}
+    <p class="mb-2 text-sm text-gray-500">
+      {isPublic
+        ? <span>
+            Your upvotes are public, anyone can see them on
+            your <a class="underline" href={`/user/${userId}/upvoted`}>favorites page</a>.
+          </span>
+        : "Your upvotes are private, only moderators can see them."}
+    </p>
+    <Button
+      title={isPublic ? "Make upvotes private" : "Make upvotes public"}
+      onClick={submit}
+      disabled={inFlight}
+      loading={inFlight}
+      style="w-full"
+    />
+  </form>;
+}
@@ -0,0 +1,57 @@
+import { h } from "preact";
+import { useState } from "preact/hooks";
+import { graphql, useMutation } from "picoql";
+
+import TextInput from "@app/TextInput";
+import Button from "@app/Button";
+import Notice from "@app/Notice";
+
+function feedUrl(path) {
+  return path ? `${window.location.origin}${path}` : "";
+}
+
+export default function SavedFeed({ currentPath }) {
+  const [path, setPath] = useState(currentPath);
+  const [error, setError] = useState(null);
+
+  const { commit, inFlight } = useMutation(graphql`
+    mutation ResetSavedFeedToken {
+      resetSavedFeedToken {
+        id
+        savedFeedPath
+      }
+    }
+  `, {
+    onCommit: ({ resetSavedFeedToken }) => {
+      setPath(resetSavedFeedToken?.savedFeedPath);
+      setError(null);
+    },
+    onError: ([{message}]) => setError(`Failed to create feed link: ${message}`),
+  });
+
+  const submit = e => {
+    e.preventDefault();
+    if (!inFlight)
+      commit();
+  };
+
+  return <form onSubmit={submit}>
+    {error && <Notice message={error} type="error" style="mb-2" />}
+    <p class="mb-2 text-sm text-gray-500">
+      Subscribe to your saved links in any feed reader. The link is
+      private, anyone who has it can read your saved links.
+    </p>
+    {path && <TextInput
+      label="Private feed link"
+      value={feedUrl(path)}
+      style="mb-2"
+    />}
+    <Button
+      title={path ? "Reset link" : "Create link"}
+      onClick={submit}
+      disabled={inFlight}
+      loading={inFlight}
+      style="w-full"
+    />
+  </form>;
+}
@@ -0,0 +1,71 @@
+import { h } from "preact";
+import { useState } from "preact/hooks";
+import { graphql, useMutation } from "picoql";
+
+import TextInput from "@app/TextInput";
+import Button from "@app/Button";
+import Notice from "@app/Notice";
+
+export default function SubmitText() {
+  const [title, setTitle] = useState("");
+  const [text, setText] = useState("");
+  const [tags, setTags] = useState("");
+  const [error, setError] = useState(null);
+
+  const { commit, inFlight } = useMutation(graphql`
+    mutation SubmitTextPost($title: String!, $text: String!, $tags: [String!]) {
+      submitTextPost(input: { title: $title, text: $text, tags: $tags }) {
+        id
+      }
+    }
+  `, {
+    onCommit: ({ submitTextPost: { id } }) => {
+      window.location.href = `/comments/${id}`;
+    },
+    onError: ([{message}]) => {
+      setError(`Failed to submit: ${message}`);
+    },
+  });
+
+  const submit = e => {
+    e.preventDefault();
+    commit({
+      title: title.trim(),
+      text: text.trim(),
+      tags: tags.split(",").map(tag => tag.trim()).filter(tag => tag.length > 0),
+    });
+  };
+
+  return (
+    <form class="w-full" onSubmit={submit}>
+      {error && <Notice message={error} type="error" style="mb-2" />}
+      <TextInput
+        label="Title"
+        placeholder="What would you like to discuss?"
+        value={title}
+        onChange={setTitle}
+      />
+      <div class="w-full p-2 mt-2 rounded-md bg-gray-200 dark:bg-gray-600">
+        <textarea
+          class="w-full h-28 resize-none bg-transparent leading-tight"
+          placeholder="Your thoughts, formatted with *markdown* ..."
+          onInput={e => setText(e.target.value)}
+          value={text}
+        >{text}</textarea>
+      </div>
+      <TextInput
+        label="Tags (comma separated)"
+        placeholder="rust, databases"
+        value={tags}
+        onChange={setTags}
+        style="mt-2"
+      />
+      <Button
+        title="Submit"
+        style="w-full mt-2"
+        loading={inFlight}
+        disabled={inFlight}
+      />
+    </form>
+  );
+}
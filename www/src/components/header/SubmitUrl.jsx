@@ -8,12 +8,13 @@ import Notice from "@app/Notice";
 
 export default function SubmitUrl() {
   const [url, setUrl] = useState("");
+  const [tags, setTags] = useState("");
   const [notice, setNotice] = useState(null);
   const [error, setError] = useState(null);
 
   const { commit, inFlight } = useMutation(graphql`
-    mutation SubmitUrl($url: String!) {
-      submitUrl(input: { url: $url }) {
+    mutation SubmitUrl($url: String!, $tags: [String!]) {
+      submitUrl(input: { url: $url, tags: $tags }) {
         id
         title
       }
@@ -21,6 +22,7 @@ export default function SubmitUrl() {
   `, {
     onCommit: ({ submitUrl: { id, title } }) => {
       setUrl("");
+      setTags("");
       setError(null);
       if (title) {
         setNotice(`"${title}" was successfully submitted`);
@@ -36,7 +38,10 @@ export default function SubmitUrl() {
 
   const submit = e => {
     e.preventDefault();
-    commit({ url: url.trim() });
+    commit({
+      url: url.trim(),
+      tags: tags.split(",").map(tag => tag.trim()).filter(tag => tag.length > 0),
+    });
   };
 
   return (
@@ -49,6 +54,13 @@ export default function SubmitUrl() {
         value={url}
         onChange={setUrl}
       />
+      <TextInput
+        label="Tags (comma separated)"
+        placeholder="rust, databases"
+        value={tags}
+        onChange={setTags}
+        style="mt-2"
+      />
       <Button
         title="Submit"
         style="w-full mt-2"
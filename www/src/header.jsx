@@ -1,11 +1,15 @@
 import { render, h, Fragment } from "preact";
 import { useState } from "preact/hooks";
 import hydrateUpvotes from "./upvote.jsx";
+import hydrateSaves from "./save.jsx";
+import hydrateHides from "./hide.jsx";
+import hydrateMutes from "./mute.jsx";
 
 import ErrorBoundary from "@app/ErrorBoundary";
 import Button from "@app/Button";
 import Link from "@app/Link";
 import SubmitUrl from "@app/header/SubmitUrl";
+import SubmitText from "@app/header/SubmitText";
 
 const MENU_ITEM = <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" viewBox="0 0 20 20" fill="currentColor">
   <path fill-rule="evenodd" d="M3 5a1 1 0 011-1h12a1 1 0 110 2H4a1 1 0 01-1-1zM3 10a1 1 0 011-1h12a1 1 0 110 2H4a1 1 0 01-1-1zM9 15a1 1 0 011-1h6a1 1 0 110 2h-6a1 1 0 01-1-1z" clip-rule="evenodd" />
@@ -16,6 +20,7 @@ const MENU_SEARCH = <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" view
 
 function Header() {
   const [showSubmit, setShowSubmit] = useState(false);
+  const [submitText, setSubmitText] = useState(false);
   const [showMenu, setShowMenu] = useState(false);
 
   let containerClasses = "w-full rounded-t-lg pb-8 -mb-6 p-2";
@@ -28,6 +33,7 @@ function Header() {
       <Link title="recent" href="/recent" />
       <Link title="best" href="/best" />
       <Link title="mine" href="/mine" />
+      <Link title="saved" href="/saved" />
     </>
   );
 
@@ -43,7 +49,7 @@ function Header() {
   return (
     <div class={containerClasses}>
       <div class="flex items-center justify-between">
-        <Button title="Submit url" onClick={toggleSubmit} style="mr-2 whitespace-nowrap" />
+        <Button title="Submit" onClick={toggleSubmit} style="mr-2 whitespace-nowrap" />
         <div class="w-full sm:flex hidden">
           {links}
         </div>
@@ -53,7 +59,11 @@ function Header() {
         </div>
       </div>
       <div class="mt-4" style={{ display: showSubmit ? undefined : "none" }}>
-        <SubmitUrl />
+        <div class="flex space-x-2 mb-2">
+          <Button title="Link" type={submitText ? "flat" : undefined} onClick={() => setSubmitText(false)} />
+          <Button title="Text" type={submitText ? undefined : "flat"} onClick={() => setSubmitText(true)} />
+        </div>
+        {submitText ? <SubmitText /> : <SubmitUrl />}
       </div>
       {showMenu && <div class="mt-4">{links}</div>}
     </div>
@@ -62,3 +72,6 @@ function Header() {
 
 render(<ErrorBoundary><Header /></ErrorBoundary>, document.getElementById("header"));
 hydrateUpvotes();
+hydrateSaves();
+hydrateHides();
+hydrateMutes();
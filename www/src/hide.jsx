@@ -0,0 +1,58 @@
+import { render, h } from "preact";
+import { useState } from "preact/hooks";
+import { graphql, useMutation } from "picoql";
+
+function HideButton({ urlID, initDidHide }) {
+  const [didHide, setDidHide] = useState(initDidHide ?? false);
+
+  const onCommit = ({ url }) => setDidHide(url?.hiddenByViewer ?? didHide);
+  const onError = () => setDidHide(!didHide);
+
+  const hide = useMutation(graphql`
+    mutation HideUrl($id: ID!) {
+      url: hideUrl(url: $id) {
+        id
+        hiddenByViewer
+      }
+    }
+  `, { onCommit, onError });
+
+  const unhide = useMutation(graphql`
+    mutation UnhideUrl($id: ID!) {
+      url: unhideUrl(url: $id) {
+        id
+        hiddenByViewer
+      }
+    }
+  `, { onCommit, onError });
+
+  const click = e => {
+    e.preventDefault();
+    setDidHide(!didHide);
+    if (didHide)
+      unhide.commit({ id: urlID });
+    else
+      hide.commit({ id: urlID });
+  };
+
+  return (
+    <button
+      class={`block p-1 rounded-xl flex items-center hover:bg-gray-300 ${didHide ? "not-italic text-blue-500" : ""}`}
+      onClick={click}
+      title={didHide ? "Show in your listings again" : "Hide from your listings"}
+    >
+      <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4 mr-1" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13.875 18.825A10.05 10.05 0 0112 19c-4.478 0-8.268-2.943-9.543-7a9.97 9.97 0 011.563-3.029m5.858.908a3 3 0 114.243 4.243M9.878 9.878l4.242 4.242M9.88 9.88l-3.29-3.29m7.532 7.532l3.29 3.29M3 3l3.59 3.59m0 0A9.953 9.953 0 0112 5c4.478 0 8.268 2.943 9.543 7a10.025 10.025 0 01-4.132 5.411m0 0L21 21" />
+      </svg>
+      {didHide ? "Hidden" : "Hide"}
+    </button>
+  );
+}
+
+export default function hydrate() {
+  for (const element of document.querySelectorAll("[data-hydrate-hide-button]")) {
+    const urlID = element.dataset.id;
+    const hidden = element.dataset.hidden === "true";
+    render(<HideButton urlID={urlID} initDidHide={hidden} />, element);
+  }
+}
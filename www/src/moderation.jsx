@@ -0,0 +1,117 @@
+import { h, render } from "preact";
+import { useState } from "preact/hooks";
+import { graphql, useMutation } from "picoql";
+
+import ErrorBoundary, { errorToString } from "@app/ErrorBoundary";
+import Button from "@app/Button";
+
+const MUTATIONS = {
+  held: {
+    dismiss: graphql`
+      mutation ApproveHeldUrl($id: ID!) {
+        approveUrl(url: $id) {
+          id
+        }
+      }
+    `,
+    remove: graphql`
+      mutation DeleteHeldUrl($id: ID!) {
+        deleteUrl(url: $id) {
+          id
+        }
+      }
+    `,
+  },
+  url: {
+    dismiss: graphql`
+      mutation DismissUrlFlags($id: ID!) {
+        dismissUrlFlags(url: $id) {
+          id
+        }
+      }
+    `,
+    remove: graphql`
+      mutation DeleteFlaggedUrl($id: ID!) {
+        deleteUrl(url: $id) {
+          id
+        }
+      }
+    `,
+  },
+  comment: {
+    dismiss: graphql`
+      mutation DismissCommentFlags($id: ID!) {
+        dismissCommentFlags(comment: $id) {
+          id
+        }
+      }
+    `,
+    remove: graphql`
+      mutation DeleteFlaggedComment($id: ID!) {
+        deleteComment(comment: $id) {
+          id
+        }
+      }
+    `,
+  },
+  ring: {
+    dismiss: graphql`
+      mutation DismissVoteRing($id: ID!) {
+        dismissVoteRing(ring: $id) {
+          id
+        }
+      }
+    `,
+    remove: graphql`
+      mutation DiscountVoteRing($id: ID!) {
+        discountVoteRing(ring: $id) {
+          id
+        }
+      }
+    `,
+  },
+};
+
+function ModerationActions({ kind, id, onResolved }) {
+  const [error, setError] = useState(null);
+  const onError = errors => setError(errorToString(errors));
+
+  const dismiss = useMutation(MUTATIONS[kind].dismiss, { onCommit: onResolved, onError });
+  const remove = useMutation(MUTATIONS[kind].remove, { onCommit: onResolved, onError });
+  const inFlight = dismiss.inFlight || remove.inFlight;
+
+  return (
+    <div class="flex flex-col items-end space-y-1 p-2">
+      <div class="flex space-x-2">
+        <Button
+          title={kind === "held" ? "Approve" : "Dismiss"}
+          type="flat"
+          disabled={inFlight}
+          loading={dismiss.inFlight}
+          onClick={() => dismiss.commit({ id })}
+        />
+        <Button
+          title={kind === "ring" ? "Discount" : "Remove"}
+          disabled={inFlight}
+          loading={remove.inFlight}
+          onClick={() => remove.commit({ id })}
+        />
+      </div>
+      {error !== null && <span class="text-sm text-red-500">{error}</span>}
+    </div>
+  );
+}
+
+for (const element of document.querySelectorAll("[data-hydrate-moderation-actions]")) {
+  const item = element.closest("[data-moderation-item]");
+  render(
+    <ErrorBoundary>
+      <ModerationActions
+        kind={element.dataset.kind}
+        id={element.dataset.id}
+        onResolved={() => item.remove()}
+      />
+    </ErrorBoundary>,
+    element,
+  );
+}
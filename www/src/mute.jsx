@@ -0,0 +1,71 @@
+import { render, h } from "preact";
+import { useState } from "preact/hooks";
+import { graphql, useMutation } from "picoql";
+
+import Button from "@app/Button";
+
+function MuteButton({ kind, target, initDidMute }) {
+  const [didMute, setDidMute] = useState(initDidMute ?? false);
+
+  const onCommit = () => {};
+  const onError = () => setDidMute(!didMute);
+
+  const muteUser = useMutation(graphql`
+    mutation MuteUser($user: ID!) {
+      muteUser(user: $user) {
+        id
+        mutedByViewer
+      }
+    }
+  `, { onCommit, onError });
+
+  const unmuteUser = useMutation(graphql`
+    mutation UnmuteUser($user: ID!) {
+      unmuteUser(user: $user) {
+        id
+        mutedByViewer
+      }
+    }
+  `, { onCommit, onError });
+
+  const muteDomain = useMutation(graphql`
+    mutation MuteDomain($domain: String!) {
+      muteDomain(domain: $domain) {
+        id
+      }
+    }
+  `, { onCommit, onError });
+
+  const unmuteDomain = useMutation(graphql`
+    mutation UnmuteDomain($domain: String!) {
+      unmuteDomain(domain: $domain) {
+        id
+      }
+    }
+  `, { onCommit, onError });
+
+  const click = e => {
+    e.preventDefault();
+    setDidMute(!didMute);
+    if (kind === "user")
+      (didMute ? unmuteUser : muteUser).commit({ user: target });
+    else
+      (didMute ? unmuteDomain : muteDomain).commit({ domain: target });
+  };
+
+  return (
+    <Button
+      title={didMute ? "Unmute" : "Mute"}
+      type={didMute ? "default" : "flat"}
+      onClick={click}
+    />
+  );
+}
+
+export default function hydrate() {
+  for (const element of document.querySelectorAll("[data-hydrate-mute-button]")) {
+    const { kind, target } = element.dataset;
+    const muted = element.dataset.muted === "true";
+    render(<MuteButton kind={kind} target={target} initDidMute={muted} />, element);
+  }
+}
@@ -0,0 +1,58 @@
+import { render, h } from "preact";
+import { useState } from "preact/hooks";
+import { graphql, useMutation } from "picoql";
+
+function SaveButton({ urlID, initDidSave }) {
+  const [didSave, setDidSave] = useState(initDidSave ?? false);
+
+  const onCommit = ({ url }) => setDidSave(url?.savedByViewer ?? didSave);
+  const onError = () => setDidSave(!didSave);
+
+  const save = useMutation(graphql`
+    mutation SaveUrl($id: ID!) {
+      url: saveUrl(url: $id) {
+        id
+        savedByViewer
+      }
+    }
+  `, { onCommit, onError });
+
+  const unsave = useMutation(graphql`
+    mutation UnsaveUrl($id: ID!) {
+      url: unsaveUrl(url: $id) {
+        id
+        savedByViewer
+      }
+    }
+  `, { onCommit, onError });
+
+  const click = e => {
+    e.preventDefault();
+    setDidSave(!didSave);
+    if (didSave)
+      unsave.commit({ id: urlID });
+    else
+      save.commit({ id: urlID });
+  };
+
+  return (
+    <button
+      class={`block p-1 rounded-xl flex items-center hover:bg-gray-300 ${didSave ? "not-italic text-blue-500" : ""}`}
+      onClick={click}
+      title={didSave ? "Remove from saved links" : "Save for later"}
+    >
+      <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4 mr-1" viewBox="0 0 20 20" fill={didSave ? "currentColor" : "none"} stroke="currentColor">
+        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M5 5a2 2 0 012-2h6a2 2 0 012 2v12l-5-2.5L5 17V5z" />
+      </svg>
+      {didSave ? "Saved" : "Save"}
+    </button>
+  );
+}
+
+export default function hydrate() {
+  for (const element of document.querySelectorAll("[data-hydrate-save-button]")) {
+    const urlID = element.dataset.id;
+    const saved = element.dataset.saved === "true";
+    render(<SaveButton urlID={urlID} initDidSave={saved} />, element);
+  }
+}